@@ -34,26 +34,26 @@ fn benchmark_fractal_functions(c: &mut Criterion) {
     group.finish();
 }
 
-use fractals_rs::fractals::fractal_type::FractalType;
+use fractals_rs::fractals::fractal_type::EscapeTime;
 use fractals_rs::utils::precision_mode::PrecisionMode;
 
 #[allow(unused)]
 fn mandelbrot_iterations(x: f64, y: f64, max_iter: u16) -> u16 {
-    FractalType::Mandelbrot.iterations(x, y, max_iter, &Point::new(0.0, 0.0), PrecisionMode::Fast)
+    EscapeTime::Mandelbrot.iterations(x, y, max_iter, &Point::new(0.0, 0.0), PrecisionMode::Fast)
 }
 
 #[allow(unused)]
 fn julia_iterations(x: f64, y: f64, max_iter: u16, c: &Point) -> u16 {
-    FractalType::Julia.iterations(x, y, max_iter, c, PrecisionMode::Fast)
+    EscapeTime::Julia.iterations(x, y, max_iter, c, PrecisionMode::Fast)
 }
 
 #[allow(unused)]
 fn burning_ship_iterations(x: f64, y: f64, max_iter: u16) -> u16 {
-    FractalType::BurningShip.iterations(x, y, max_iter, &Point::new(0.0, 0.0), PrecisionMode::Fast)
+    EscapeTime::BurningShip.iterations(x, y, max_iter, &Point::new(0.0, 0.0), PrecisionMode::Fast)
 }
 #[allow(unused)]
 fn tricorn_iterations(x: f64, y: f64, max_iter: u16) -> u16 {
-    FractalType::Tricorn.iterations(x, y, max_iter, &Point::new(0.0, 0.0), PrecisionMode::Fast)
+    EscapeTime::Tricorn.iterations(x, y, max_iter, &Point::new(0.0, 0.0), PrecisionMode::Fast)
 }
 
 criterion_group!(benches, benchmark_fractal_functions);
@@ -0,0 +1,232 @@
+use crate::animation::keyframe::{Keyframe, Timeline};
+use crate::utils::point::Point;
+use std::f64::consts::TAU;
+
+/// Shape of the path followed by the Julia constant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum JuliaPathShape {
+    #[default]
+    Circle,
+    Line,     // Back and forth between two points
+    Spline,   // Closed Catmull-Rom spline through picked points
+    Cardioid, // Boundary of the Mandelbrot set's main cardioid
+}
+
+impl JuliaPathShape {
+    /// Returns the name of the path shape.
+    #[inline]
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Circle => "Circle",
+            Self::Line => "Line",
+            Self::Spline => "Spline",
+            Self::Cardioid => "Main Cardioid",
+        }
+    }
+
+    /// Returns all available path shapes.
+    #[inline]
+    #[must_use]
+    pub const fn all() -> [Self; 4] {
+        [Self::Circle, Self::Line, Self::Spline, Self::Cardioid]
+    }
+}
+
+/// A closed loop in the complex plane for the Julia constant to travel along.
+/// Every shape keeps its own parameters, so switching shapes does not lose them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JuliaPath {
+    pub shape: JuliaPathShape,
+    pub center: Point, // Circle
+    pub radius: f64,
+    pub start: Point, // Line
+    pub end: Point,
+    pub points: Vec<Point>, // Spline
+    pub scale: f64,         // Cardioid: 1 follows the boundary, below 1 stays just inside the set
+}
+
+impl Default for JuliaPath {
+    /// Creates a circle of radius 0.7885 around the origin, which passes through many classic Julia sets.
+    #[inline]
+    fn default() -> Self {
+        Self {
+            shape: JuliaPathShape::Circle,
+            center: Point::new(0.0, 0.0),
+            radius: 0.7885,
+            start: Point::new(-0.8, 0.156),
+            end: Point::new(0.285, 0.01),
+            points: Vec::new(),
+            scale: 1.0,
+        }
+    }
+}
+
+impl JuliaPath {
+    /// Returns the point at `t`, where `[0, 1)` covers the loop once and the path repeats outside it.
+    pub fn point_at(&self, t: f64) -> Point {
+        let t = t.rem_euclid(1.0);
+        match self.shape {
+            JuliaPathShape::Circle => {
+                let (sin, cos) = (TAU * t).sin_cos();
+                Point::new(self.radius.mul_add(cos, self.center.x), self.radius.mul_add(sin, self.center.y))
+            }
+            JuliaPathShape::Line => {
+                // Ping-pong so the loop has no jump
+                let s = 1.0 - (2.0f64.mul_add(t, -1.0)).abs();
+                Point::new(
+                    s.mul_add(self.end.x - self.start.x, self.start.x),
+                    s.mul_add(self.end.y - self.start.y, self.start.y),
+                )
+            }
+            JuliaPathShape::Spline => catmull_rom_loop(&self.points, t).unwrap_or(self.center),
+            JuliaPathShape::Cardioid => {
+                // c = e^iθ/2 − e^2iθ/4
+                let (sin, cos) = (TAU * t).sin_cos();
+                let (sin2, cos2) = (2.0 * TAU * t).sin_cos();
+                Point::new(
+                    self.scale * 0.25f64.mul_add(-cos2, 0.5 * cos),
+                    self.scale * 0.25f64.mul_add(-sin2, 0.5 * sin),
+                )
+            }
+        }
+    }
+
+    /// Samples the loop at `count` evenly spaced parameters, for drawing it.
+    #[must_use]
+    pub fn polyline(&self, count: usize) -> Vec<Point> {
+        (0..=count).map(|i| self.point_at(i as f64 / count as f64)).collect()
+    }
+}
+
+/// Evaluates a closed uniform Catmull-Rom spline through `points` at `t` in `[0, 1)`.
+/// Returns `None` without points; one point is a constant, two go back and forth.
+fn catmull_rom_loop(points: &[Point], t: f64) -> Option<Point> {
+    let n = points.len();
+    match n {
+        0 => None,
+        1 => Some(points[0]),
+        _ => {
+            let position = t * n as f64;
+            let segment = (position as usize).min(n - 1);
+            let u = position - segment as f64;
+            let [p0, p1, p2, p3] = [n - 1, 0, 1, 2].map(|offset| points[(segment + offset) % n]);
+
+            let (u2, u3) = (u * u, u * u * u);
+            let blend = |a: f64, b: f64, c: f64, d: f64| {
+                0.5 * ((2.0 * b)
+                    + (c - a) * u
+                    + (2.0f64.mul_add(a, -5.0 * b) + 4.0f64.mul_add(c, -d)) * u2
+                    + (3.0f64.mul_add(b, -a) + 3.0f64.mul_add(-c, d)) * u3)
+            };
+            Some(Point::new(
+                blend(p0.x, p1.x, p2.x, p3.x),
+                blend(p0.y, p1.y, p2.y, p3.y),
+            ))
+        }
+    }
+}
+
+/// A fixed view whose Julia constant loops once along a path over `frames` frames.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JuliaPathAnimation {
+    pub path: JuliaPath,
+    pub view: Keyframe,
+    pub frames: u32,
+    pub fps: u32,
+}
+
+impl Timeline for JuliaPathAnimation {
+    #[inline]
+    fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    #[inline]
+    fn fps(&self) -> u32 {
+        self.fps
+    }
+
+    /// The last frame stops one step short of the start, so the sequence loops seamlessly.
+    fn keyframe(&self, frame: u32) -> Keyframe {
+        Keyframe {
+            julia_c: self.path.point_at(f64::from(frame) / f64::from(self.frames.max(1))),
+            ..self.view
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: Point, b: Point) -> bool {
+        (a.x - b.x).abs() < 1e-12 && (a.y - b.y).abs() < 1e-12
+    }
+
+    #[test]
+    fn test_paths_are_closed_loops() {
+        let mut path = JuliaPath {
+            points: vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0), Point::new(0.5, 1.0)],
+            ..JuliaPath::default()
+        };
+        for shape in JuliaPathShape::all() {
+            path.shape = shape;
+            assert!(close(path.point_at(0.0), path.point_at(1.0)), "{}", shape.name());
+        }
+    }
+
+    #[test]
+    fn test_line_ping_pongs() {
+        let path = JuliaPath {
+            shape: JuliaPathShape::Line,
+            ..JuliaPath::default()
+        };
+        assert!(close(path.point_at(0.0), path.start));
+        assert!(close(path.point_at(0.5), path.end));
+    }
+
+    #[test]
+    fn test_spline_passes_through_points() {
+        let points = vec![Point::new(-0.5, 0.5), Point::new(0.3, 0.1), Point::new(0.0, -0.6), Point::new(-0.8, 0.0)];
+        let path = JuliaPath {
+            shape: JuliaPathShape::Spline,
+            points: points.clone(),
+            ..JuliaPath::default()
+        };
+        for (i, point) in points.iter().enumerate() {
+            assert!(close(path.point_at(i as f64 / points.len() as f64), *point));
+        }
+    }
+
+    #[test]
+    fn test_cardioid_cusp_and_tip() {
+        let path = JuliaPath {
+            shape: JuliaPathShape::Cardioid,
+            ..JuliaPath::default()
+        };
+        // θ = 0 is the cusp at 1/4, θ = π the junction with the period-2 bulb at −3/4
+        assert!(close(path.point_at(0.0), Point::new(0.25, 0.0)));
+        assert!(close(path.point_at(0.5), Point::new(-0.75, 0.0)));
+    }
+
+    #[test]
+    fn test_animation_varies_only_julia_constant() {
+        let view = Keyframe {
+            center: Point::new(0.0, 0.0),
+            zoom: 1.5,
+            julia_c: Point::new(9.0, 9.0),
+            palette_offset: 0.2,
+        };
+        let animation = JuliaPathAnimation {
+            path: JuliaPath::default(),
+            view,
+            frames: 4,
+            fps: 30,
+        };
+        let frame = animation.keyframe(1);
+        assert_eq!((frame.center, frame.zoom, frame.palette_offset), (view.center, view.zoom, view.palette_offset));
+        assert!(close(frame.julia_c, Point::new(0.0, 0.7885)));
+    }
+}
@@ -0,0 +1,173 @@
+use crate::utils::point::Point;
+
+/// A view to pass through during an animation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Keyframe {
+    pub center: Point,
+    pub zoom: f64,
+    pub julia_c: Point,
+    pub palette_offset: f32,
+}
+
+/// An ordered list of keyframes played over a fixed number of frames.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Animation {
+    pub keyframes: Vec<Keyframe>,
+    pub frames: u32,
+    pub fps: u32,
+}
+
+/// Anything that can be rendered frame by frame: a view per frame, at a fixed frame rate.
+pub trait Timeline {
+    /// Returns the number of frames.
+    fn frames(&self) -> u32;
+
+    /// Returns the playback rate in frames per second.
+    fn fps(&self) -> u32;
+
+    /// Returns the view shown at `frame` (0-based).
+    fn keyframe(&self, frame: u32) -> Keyframe;
+}
+
+/// Ease-in/ease-out curve so motion starts and stops without a jolt.
+#[inline(always)]
+fn smooth_step(t: f64) -> f64 {
+    let t = t.clamp(0.0, 1.0);
+    t * t * 2.0f64.mul_add(-t, 3.0)
+}
+
+impl Keyframe {
+    /// Interpolates between two keyframes at `t` in `[0, 1]`.
+    ///
+    /// Zoom is interpolated exponentially, so every frame zooms by the same factor.
+    /// The center follows the view width rather than time: when zooming in, the
+    /// target point converges at the rate the view shrinks and never drifts off-screen.
+    #[must_use]
+    pub fn interpolate(&self, other: &Self, t: f64) -> Self {
+        let s = smooth_step(t);
+        let zoom = self.zoom * (other.zoom / self.zoom).powf(s);
+
+        let (inv_from, inv_to) = (1.0 / self.zoom, 1.0 / other.zoom);
+        let travel = if ((inv_to - inv_from) / inv_from).abs() > 1e-9 {
+            (inv_from - 1.0 / zoom) / (inv_from - inv_to)
+        } else {
+            s
+        };
+
+        let lerp = |a: f64, b: f64, t: f64| t.mul_add(b - a, a);
+        Self {
+            center: Point::new(
+                lerp(self.center.x, other.center.x, travel),
+                lerp(self.center.y, other.center.y, travel),
+            ),
+            zoom,
+            julia_c: Point::new(
+                lerp(self.julia_c.x, other.julia_c.x, s),
+                lerp(self.julia_c.y, other.julia_c.y, s),
+            ),
+            palette_offset: (s as f32).mul_add(other.palette_offset - self.palette_offset, self.palette_offset),
+        }
+    }
+}
+
+impl Animation {
+    /// Returns the keyframe shown at `frame` (0-based).
+    /// Frames are spread evenly over the segments between consecutive keyframes.
+    #[must_use]
+    pub fn frame(&self, frame: u32) -> Keyframe {
+        let progress = if self.frames > 1 {
+            f64::from(frame.min(self.frames - 1)) / f64::from(self.frames - 1)
+        } else {
+            0.0
+        };
+        self.at(progress)
+    }
+
+    /// Returns the keyframe at overall progress `t` in `[0, 1]`.
+    #[must_use]
+    pub fn at(&self, t: f64) -> Keyframe {
+        match self.keyframes.as_slice() {
+            [] => panic!("an animation needs at least one keyframe"),
+            [only] => *only,
+            keyframes => {
+                let segments = keyframes.len() - 1;
+                let position = t.clamp(0.0, 1.0) * segments as f64;
+                let segment = (position as usize).min(segments - 1);
+                keyframes[segment].interpolate(&keyframes[segment + 1], position - segment as f64)
+            }
+        }
+    }
+}
+
+impl Timeline for Animation {
+    #[inline]
+    fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    #[inline]
+    fn fps(&self) -> u32 {
+        self.fps
+    }
+
+    #[inline]
+    fn keyframe(&self, frame: u32) -> Keyframe {
+        self.frame(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyframe(x: f64, zoom: f64) -> Keyframe {
+        Keyframe {
+            center: Point::new(x, 0.0),
+            zoom,
+            julia_c: Point::new(0.0, 0.0),
+            palette_offset: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_interpolate_endpoints() {
+        let (a, b) = (keyframe(-0.5, 1.0), keyframe(-0.75, 1000.0));
+        assert_eq!(a.interpolate(&b, 0.0), a);
+        let end = a.interpolate(&b, 1.0);
+        assert!((end.zoom - 1000.0).abs() < 1e-9);
+        assert!((end.center.x + 0.75).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_zoom_is_exponential() {
+        let (a, b) = (keyframe(0.0, 1.0), keyframe(0.0, 10_000.0));
+        // Halfway through the eased curve is exactly half the zoom exponent
+        assert!((a.interpolate(&b, 0.5).zoom - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_center_tracks_view_width() {
+        let (a, b) = (keyframe(0.0, 1.0), keyframe(1.0, 1e6));
+        // Halfway in log-zoom the view is 1000× narrower: the center is almost at the target
+        assert!(a.interpolate(&b, 0.5).center.x > 0.99);
+    }
+
+    #[test]
+    fn test_pan_without_zoom_is_smooth() {
+        let (a, b) = (keyframe(0.0, 2.0), keyframe(1.0, 2.0));
+        assert!((a.interpolate(&b, 0.5).center.x - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_animation_frames_cover_keyframes() {
+        let animation = Animation {
+            keyframes: vec![keyframe(0.0, 1.0), keyframe(1.0, 1.0), keyframe(2.0, 1.0)],
+            frames: 5,
+            fps: 30,
+        };
+        assert_eq!(animation.frame(0), animation.keyframes[0]);
+        assert!((animation.frame(2).center.x - 1.0).abs() < 1e-12);
+        assert!((animation.frame(4).center.x - 2.0).abs() < 1e-12);
+        assert!((animation.frame(99).center.x - 2.0).abs() < 1e-12);
+    }
+}
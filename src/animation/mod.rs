@@ -0,0 +1,3 @@
+pub mod keyframe;
+pub mod julia_path;
+pub mod sequence;
@@ -0,0 +1,326 @@
+use crate::animation::keyframe::Timeline;
+use crate::fractals::renderer::RenderParams;
+use crate::utils::color_mapping::ColorSettings;
+use crate::utils::export;
+use crate::utils::scene::Scene;
+use image::RgbImage;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Where the frames of an animation are written.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SequenceOutput {
+    pub directory: PathBuf,  // Numbered PNGs go here as `frame_00000.png`, ...
+    pub write_png: bool,
+    pub y4m: Option<PathBuf>, // Raw YUV4MPEG2 stream; `-` writes to stdout for piping into encoders
+}
+
+/// What `render_sequence` did, frame by frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SequenceSummary {
+    pub rendered: u32,
+    pub skipped: u32,
+}
+
+/// Returns the path of the PNG for `frame` inside `directory`.
+#[inline]
+#[must_use]
+pub fn frame_path(directory: &Path, frame: u32) -> PathBuf {
+    directory.join(format!("frame_{frame:05}.png"))
+}
+
+/// Returns the render parameters and colors of `frame`.
+#[must_use]
+pub fn frame_settings(
+    animation: &impl Timeline,
+    base: &RenderParams,
+    colors: &ColorSettings,
+    frame: u32,
+) -> (RenderParams, ColorSettings) {
+    let keyframe = animation.keyframe(frame);
+    let params = RenderParams {
+        center: keyframe.center,
+        zoom: keyframe.zoom,
+        julia_c: keyframe.julia_c,
+        ..base.clone()
+    };
+    let colors = ColorSettings {
+        offset: keyframe.palette_offset.rem_euclid(1.0),
+        ..colors.clone()
+    };
+    (params, colors)
+}
+
+/// Renders a single frame of the animation.
+#[must_use]
+pub fn render_frame(animation: &impl Timeline, base: &RenderParams, colors: &ColorSettings, frame: u32) -> RgbImage {
+    let (params, colors) = frame_settings(animation, base, colors, frame);
+    export::render_image(&params, &colors)
+}
+
+/// Renders every frame of a timeline to `output`.
+///
+/// The render is resumable: PNG frames already on disk are skipped (or reloaded when the
+/// y4m stream still needs them), and an existing y4m file is truncated to its last complete
+/// frame and continued. `cancel` is polled between frames; `progress` receives the number
+/// of finished frames.
+pub fn render_sequence(
+    animation: &impl Timeline,
+    base: &RenderParams,
+    colors: &ColorSettings,
+    output: &SequenceOutput,
+    cancel: &AtomicBool,
+    mut progress: impl FnMut(u32),
+) -> io::Result<SequenceSummary> {
+    if output.write_png {
+        fs::create_dir_all(&output.directory)?;
+    }
+
+    let header = y4m_header(base.width, base.height, animation.fps());
+    let (mut y4m, y4m_start): (Option<Box<dyn Write>>, u32) = match &output.y4m {
+        None => (None, u32::MAX),
+        Some(path) if path.as_os_str() == "-" => (Some(Box::new(io::stdout().lock())), 0),
+        Some(path) => {
+            let (file, complete) = open_y4m(path, &header, y4m_frame_size(base.width, base.height))?;
+            (Some(Box::new(BufWriter::new(file))), complete)
+        }
+    };
+
+    let mut summary = SequenceSummary::default();
+    for frame in 0..animation.frames() {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+
+        let path = frame_path(&output.directory, frame);
+        let png_done = output.write_png && path.is_file();
+        let needs_png = output.write_png && !png_done;
+        let needs_y4m = y4m.is_some() && frame >= y4m_start;
+
+        if needs_png || needs_y4m {
+            let image = match png_done.then(|| image::open(&path)) {
+                Some(Ok(image)) if image.width() == base.width && image.height() == base.height => {
+                    image.into_rgb8()
+                }
+                _ => {
+                    summary.rendered += 1;
+                    render_frame(animation, base, colors, frame)
+                }
+            };
+
+            if needs_png {
+                // Write then rename: an interrupted frame never looks complete on resume
+                let partial = path.with_extension("png.part");
+                let (params, colors) = frame_settings(animation, base, colors, frame);
+                export::write_png(&image, &partial, Some(&Scene::new(params, colors)))?;
+                fs::rename(&partial, &path)?;
+            }
+            if let Some(writer) = y4m.as_mut().filter(|_| needs_y4m) {
+                write_y4m_frame(writer, &image)?;
+            }
+        } else {
+            summary.skipped += 1;
+        }
+
+        progress(frame + 1);
+    }
+
+    if let Some(writer) = y4m.as_mut() {
+        writer.flush()?;
+    }
+    Ok(summary)
+}
+
+/// Returns the YUV4MPEG2 stream header for full-range 4:2:0 frames.
+#[must_use]
+pub fn y4m_header(width: u32, height: u32, fps: u32) -> String {
+    format!("YUV4MPEG2 W{width} H{height} F{fps}:1 Ip A1:1 C420jpeg XCOLORRANGE=FULL\n")
+}
+
+/// Returns the size in bytes of one y4m frame, including its `FRAME` marker.
+#[must_use]
+pub const fn y4m_frame_size(width: u32, height: u32) -> u64 {
+    let (width, height) = (width as u64, height as u64);
+    let chroma = width.div_ceil(2) * height.div_ceil(2);
+    6 + width * height + 2 * chroma
+}
+
+/// Opens (or creates) a y4m file for appending and returns it with its number of complete frames.
+/// A file with a different header is started over.
+fn open_y4m(path: &Path, header: &str, frame_size: u64) -> io::Result<(File, u32)> {
+    let mut file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)?;
+
+    let mut existing = vec![0u8; header.len()];
+    let len = file.metadata()?.len();
+    let same_header = len >= header.len() as u64 && {
+        file.read_exact(&mut existing)?;
+        existing == header.as_bytes()
+    };
+
+    let complete = if same_header {
+        ((len - header.len() as u64) / frame_size) as u32
+    } else {
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(header.as_bytes())?;
+        0
+    };
+
+    // Drop a partially written trailing frame, then append after the last complete one
+    let end = header.len() as u64 + u64::from(complete) * frame_size;
+    file.set_len(end)?;
+    file.seek(SeekFrom::Start(end))?;
+    Ok((file, complete))
+}
+
+/// Writes one frame as full-range BT.601 4:2:0 YCbCr.
+pub fn write_y4m_frame(writer: &mut impl Write, image: &RgbImage) -> io::Result<()> {
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let raw = image.as_raw();
+    let rgb = |x: usize, y: usize| {
+        let i = (y * width + x) * 3;
+        (f32::from(raw[i]), f32::from(raw[i + 1]), f32::from(raw[i + 2]))
+    };
+
+    let mut luma = Vec::with_capacity(width * height);
+    for y in 0..height {
+        for x in 0..width {
+            let (r, g, b) = rgb(x, y);
+            luma.push(0.114f32.mul_add(b, 0.299f32.mul_add(r, 0.587 * g)).round() as u8);
+        }
+    }
+
+    // Chroma is averaged over each 2×2 block (clamped at odd edges)
+    let (chroma_width, chroma_height) = (width.div_ceil(2), height.div_ceil(2));
+    let mut cb = Vec::with_capacity(chroma_width * chroma_height);
+    let mut cr = Vec::with_capacity(chroma_width * chroma_height);
+    for cy in 0..chroma_height {
+        for cx in 0..chroma_width {
+            let (mut r, mut g, mut b) = (0.0, 0.0, 0.0);
+            for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                let (pr, pg, pb) = rgb((2 * cx + x).min(width - 1), (2 * cy + y).min(height - 1));
+                r += pr / 4.0;
+                g += pg / 4.0;
+                b += pb / 4.0;
+            }
+            cb.push(0.5f32.mul_add(b, (-0.168_736f32).mul_add(r, (-0.331_264f32).mul_add(g, 128.0))).round() as u8);
+            cr.push(0.5f32.mul_add(r, (-0.418_688f32).mul_add(g, (-0.081_312f32).mul_add(b, 128.0))).round() as u8);
+        }
+    }
+
+    writer.write_all(b"FRAME\n")?;
+    writer.write_all(&luma)?;
+    writer.write_all(&cb)?;
+    writer.write_all(&cr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animation::keyframe::{Animation, Keyframe};
+    use crate::fractals::anti_aliasing::AntiAliasing;
+    use crate::fractals::fractal_type::FractalType;
+    use crate::fractals::lyapunov::LyapunovSequence;
+    use crate::fractals::mariani_silver::RenderAlgorithm;
+    use crate::utils::point::Point;
+    use crate::utils::precision_mode::PrecisionMode;
+
+    fn animation(frames: u32) -> Animation {
+        let keyframe = |zoom| Keyframe {
+            center: Point::new(-0.75, 0.1),
+            zoom,
+            julia_c: Point::new(0.0, 0.0),
+            palette_offset: 0.0,
+        };
+        Animation {
+            keyframes: vec![keyframe(1.0), keyframe(50.0)],
+            frames,
+            fps: 24,
+        }
+    }
+
+    fn base() -> RenderParams {
+        RenderParams {
+            fractal_type: FractalType::Mandelbrot,
+            center: Point::new(0.0, 0.0),
+            zoom: 1.0,
+            julia_c: Point::new(0.0, 0.0),
+            lyapunov_sequence: LyapunovSequence::default(),
+            max_iterations: 50,
+            precision_mode: PrecisionMode::High,
+            anti_aliasing: AntiAliasing::Off,
+            algorithm: RenderAlgorithm::BruteForce,
+            width: 9,
+            height: 7,
+        }
+    }
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("fractals_rs_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_y4m_frame_size_matches_writer() {
+        let image = RgbImage::from_pixel(9, 7, image::Rgb([255, 0, 0]));
+        let mut bytes = Vec::new();
+        write_y4m_frame(&mut bytes, &image).unwrap();
+        assert_eq!(bytes.len() as u64, y4m_frame_size(9, 7));
+        // Full-range BT.601 red
+        assert_eq!(bytes[6], 76);
+    }
+
+    #[test]
+    fn test_render_sequence_resumes() {
+        let dir = scratch_dir("sequence");
+        let output = SequenceOutput {
+            directory: dir.join("frames"),
+            write_png: true,
+            y4m: Some(dir.join("out.y4m")),
+        };
+        let cancel = AtomicBool::new(false);
+
+        // Stop after three frames, as if interrupted
+        let summary = render_sequence(&animation(6), &base(), &ColorSettings::default(), &output, &cancel, |done| {
+            if done == 3 {
+                cancel.store(true, Ordering::Relaxed);
+            }
+        })
+        .unwrap();
+        assert_eq!(summary.rendered, 3);
+
+        cancel.store(false, Ordering::Relaxed);
+        let summary = render_sequence(&animation(6), &base(), &ColorSettings::default(), &output, &cancel, |_| {}).unwrap();
+        assert_eq!(summary, SequenceSummary { rendered: 3, skipped: 3 });
+
+        for frame in 0..6 {
+            assert!(frame_path(&output.directory, frame).is_file());
+        }
+        let y4m_len = fs::metadata(dir.join("out.y4m")).unwrap().len();
+        assert_eq!(y4m_len, y4m_header(9, 7, 24).len() as u64 + 6 * y4m_frame_size(9, 7));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_y4m_truncates_partial_frame() {
+        let dir = scratch_dir("y4m");
+        let path = dir.join("partial.y4m");
+        let header = y4m_header(9, 7, 24);
+        let frame_size = y4m_frame_size(9, 7);
+
+        let mut bytes = header.clone().into_bytes();
+        bytes.extend(vec![0u8; frame_size as usize * 2 + 10]);
+        fs::write(&path, bytes).unwrap();
+
+        let (_, complete) = open_y4m(&path, &header, frame_size).unwrap();
+        assert_eq!(complete, 2);
+        assert_eq!(fs::metadata(&path).unwrap().len(), header.len() as u64 + 2 * frame_size);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
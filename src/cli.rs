@@ -0,0 +1,876 @@
+use crate::animation::keyframe::{Animation, Keyframe};
+use crate::animation::sequence::{self, SequenceOutput};
+use crate::density::buddhabrot::{self, BuddhabrotSettings, OrbitDensity};
+use crate::density::density_map::ToneMapping;
+use crate::density::ifs::{self, IfsPreset, IfsSettings, IfsSystem};
+use crate::fractals::anti_aliasing::AntiAliasing;
+use crate::fractals::fractal_type::{FractalType, normalize_name};
+use crate::fractals::renderer::RenderParams;
+use crate::utils::color_mapping::{ColorMapping, ColorSettings};
+use crate::utils::color_scheme::ColorScheme;
+use crate::server::{self, TileServer};
+use crate::utils::export;
+use crate::utils::poster;
+use crate::utils::point::Point;
+use crate::utils::scene::Scene;
+use crate::utils::tiles::{self, MAX_XYZ_ZOOM, PyramidSettings, TileLayout};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+
+pub const USAGE: &str = "\
+Usage:
+  fractals-rs                      Open the viewer
+  fractals-rs render SCENE [options]
+                                   Render a scene file (or exported PNG) to a PNG
+  fractals-rs tiles SCENE [options]
+                                   Render a scene as a DZI or XYZ tile pyramid
+  fractals-rs serve [options]      Serve XYZ tiles rendered on demand over HTTP
+  fractals-rs animate [options]    Render a zoom animation without a window
+  fractals-rs density [options]    Accumulate a Buddhabrot, Nebulabrot or Anti-Buddhabrot to a PNG
+  fractals-rs ifs [options]        Draw an iterated function system or flame with the chaos game
+
+Render options:
+  --out PATH            Output PNG (default: fractal.png)
+  --size WxH            Override the scene's size
+  --tiled               Render in bands of rows, keeping memory bounded (automatic
+                        above 32 megapixels); re-running the same command resumes
+  --band-height N       Rows per band (default: as many as hold 16M samples, so
+                        wider or supersampled images get thinner bands)
+
+Tile options:
+  --layout NAME         dzi (NAME.dzi + NAME_files/) or xyz (Z/X/Y.png) (default: dzi)
+  --out PATH            DZI descriptor or XYZ directory (default: tiles.dzi or tiles)
+  --size WxH            DZI: size of the deepest level (default: the scene's size)
+  --tile-size N         Tile side in pixels, 16 to 1024 (default: 256)
+  --overlap N           DZI: pixels shared with neighbouring tiles (default: 1)
+  --max-zoom N          XYZ: deepest zoom level, up to 12 (default: 6)
+
+Tiles already on disk are kept, so re-running a command only renders what is missing.
+
+Serve options:
+  --port N              Port to listen on (default: 8080)
+  --address ADDR        Address to listen on (default: 127.0.0.1)
+  --cache-mb N          Memory for cached tiles, in MiB (default: 256)
+
+Tiles are answered at /tiles/FRACTAL/Z/X/Y.png?palette=NAME&iter=N&julia=CX,CY, and a
+Leaflet map of them at /.
+
+Animation options:
+  --scene PATH          Start from a scene file; the options below override it
+  --fractal NAME        mandelbrot, julia, burning-ship, tricorn, lyapunov (default: mandelbrot)
+  --sequence AB         Lyapunov A/B sequence, e.g. AABAB (default: AB)
+  --from X,Y,ZOOM       First keyframe (default: the scene's view, or the fractal's home view)
+  --to X,Y,ZOOM         Last keyframe (required)
+  --julia CX,CY         Julia constant at the first keyframe
+  --julia-to CX,CY      Julia constant at the last keyframe
+  --offset F            Palette offset at the first keyframe (default: 0)
+  --offset-to F         Palette offset at the last keyframe
+  --frames N            Number of frames (default: 120)
+  --fps N               Frame rate written to the y4m header (default: 30)
+  --size WxH            Frame size in pixels (default: 1280x720)
+  --iterations N        Maximum iterations (default: 500)
+  --palette NAME        Color scheme, e.g. classic, viridis, deep-ocean
+  --histogram           Use histogram-equalized color mapping
+  --aa MODE             off, 2x2, 3x3, 4x4, jittered, adaptive (default: off)
+  --out DIR             Directory for numbered PNG frames (default: frames)
+  --no-png              Do not write PNG frames
+  --y4m PATH            Also write a YUV4MPEG2 stream; `-` writes to stdout
+
+Existing frames are kept, so re-running the same command resumes an interrupted render.
+
+Density options:
+  --kind NAME           buddhabrot, nebulabrot, anti-buddhabrot (default: buddhabrot)
+  --view X,Y,ZOOM       Center and zoom of the plot (default: -0.5,0,1)
+  --size WxH            Image size in pixels (default: 1920x1080)
+  --samples N           Random points to follow (default: 10000000)
+  --iterations N|R,G,B  Iteration limit, or one per channel for a Nebulabrot (default: 5000,500,50)
+  --tone NAME           linear, square-root, logarithmic (default: square-root)
+  --exposure F          Brightness multiplier applied after tone mapping (default: 1)
+  --seed N              Seed of the random samples (default: 0)
+  --out PATH            Output PNG (default: buddhabrot.png)
+
+IFS options:
+  --preset NAME         sierpinski-triangle, barnsley-fern, dragon-curve, flame
+                        (default: sierpinski-triangle)
+  --system PATH         JSON file of transforms, each with `affine` [a, b, c, d, e, f],
+                        `weight`, `color` and `variations` such as [[\"Swirl\", 0.5]]
+  --view X,Y,ZOOM       Center and zoom of the plot (default: framed to fit the attractor)
+  --size WxH            Image size in pixels (default: 1920x1080)
+  --samples N           Points to plot (default: 20000000)
+  --palette NAME        Color scheme the transforms' colors are taken from (default: classic)
+  --exposure F          Brightness multiplier applied after log-density mapping (default: 1)
+  --seed N              Seed of the chaos game (default: 0)
+  --out PATH            Output PNG (default: ifs.png)";
+
+/// What the program was asked to do.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    Gui,
+    Help,
+    Animate(AnimateArgs),
+    Render(RenderArgs),
+    Tiles(TilesArgs),
+    Serve(ServeArgs),
+    Density(DensityArgs),
+    Ifs(IfsArgs),
+}
+
+/// Everything a headless animation render needs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimateArgs {
+    pub animation: Animation,
+    pub base: RenderParams,
+    pub colors: ColorSettings,
+    pub output: SequenceOutput,
+}
+
+/// A scene to render to a single PNG.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenderArgs {
+    pub scene: Scene,
+    pub output: PathBuf,
+    pub band_height: Option<u32>, // Render in bands of this many rows
+}
+
+/// A scene to render as a tile pyramid.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TilesArgs {
+    pub scene: Scene,
+    pub settings: PyramidSettings,
+    pub output: PathBuf,
+}
+
+/// Where the tile server listens and how much it caches.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ServeArgs {
+    pub address: String,
+    pub port: u16,
+    pub cache_bytes: usize,
+}
+
+/// An orbit density plot to accumulate and save.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DensityArgs {
+    pub settings: BuddhabrotSettings,
+    pub samples: u64,
+    pub tone: ToneMapping,
+    pub exposure: f64,
+    pub output: PathBuf,
+}
+
+/// An iterated function system to draw and save.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IfsArgs {
+    pub settings: IfsSettings,
+    pub samples: u64,
+    pub scheme: ColorScheme,
+    pub exposure: f64,
+    pub output: PathBuf,
+}
+
+/// Parses the command line (without the program name).
+pub fn parse(args: &[String]) -> Result<Command, String> {
+    match args.first().map(String::as_str) {
+        None => Ok(Command::Gui),
+        Some("-h" | "--help" | "help") => Ok(Command::Help),
+        Some("animate") => parse_animate(&args[1..]).map(Command::Animate),
+        Some("render") => parse_render(&args[1..]).map(Command::Render),
+        Some("tiles") => parse_tiles(&args[1..]).map(Command::Tiles),
+        Some("serve") => parse_serve(&args[1..]).map(Command::Serve),
+        Some("density") => parse_density(&args[1..]).map(Command::Density),
+        Some("ifs") => parse_ifs(&args[1..]).map(Command::Ifs),
+        Some(other) => Err(format!("unknown command `{other}`")),
+    }
+}
+
+/// Runs a parsed command that does not need a window.
+pub fn run(command: &Command) -> Result<(), String> {
+    match command {
+        Command::Gui => Ok(()),
+        Command::Help => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        Command::Animate(args) => run_animate(args),
+        Command::Render(args) => run_render(args),
+        Command::Tiles(args) => run_tiles(args),
+        Command::Serve(args) => run_serve(args),
+        Command::Density(args) => run_density(args),
+        Command::Ifs(args) => run_ifs(args),
+    }
+}
+
+/// Loads the scene named by `--scene`, if the arguments contain one.
+fn scene_argument(args: &[String]) -> Result<Option<Scene>, String> {
+    match args.iter().position(|arg| arg == "--scene") {
+        Some(index) => {
+            let path = args.get(index + 1).ok_or("`--scene` needs a value")?;
+            Scene::load(Path::new(path)).map(Some)
+        }
+        None => Ok(None),
+    }
+}
+
+fn parse_animate(args: &[String]) -> Result<AnimateArgs, String> {
+    // A scene provides the defaults; every other option overrides it
+    let scene = scene_argument(args)?;
+    let (mut base, mut colors, mut from) = match scene {
+        Some(scene) => {
+            let view = (scene.render.center.x, scene.render.center.y, scene.render.zoom);
+            (scene.render, scene.colors, Some(view))
+        }
+        None => {
+            let base = RenderParams {
+                max_iterations: 500,
+                width: 1280,
+                height: 720,
+                ..RenderParams::default()
+            };
+            (base, ColorSettings::default(), None)
+        }
+    };
+    let mut to: Option<(f64, f64, f64)> = None;
+    let mut julia_to: Option<Point> = None;
+    let mut offset_to: Option<f32> = None;
+    let mut frames = 120u32;
+    let mut fps = 30u32;
+    let mut output = SequenceOutput {
+        directory: PathBuf::from("frames"),
+        write_png: true,
+        y4m: None,
+    };
+
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("`{flag}` needs a value"));
+        match flag.as_str() {
+            "--scene" => {
+                value()?;
+            }
+            "--fractal" => {
+                let name = value()?;
+                base.fractal_type = FractalType::from_name(name).ok_or_else(|| format!("unknown fractal `{name}`"))?;
+            }
+            "--from" => from = Some(parse_view(value()?)?),
+            "--to" => to = Some(parse_view(value()?)?),
+            "--sequence" => base.lyapunov_sequence = value()?.parse()?,
+            "--julia" => base.julia_c = parse_point(value()?)?,
+            "--julia-to" => julia_to = Some(parse_point(value()?)?),
+            "--offset" => colors.offset = parse_number(value()?)?,
+            "--offset-to" => offset_to = Some(parse_number(value()?)?),
+            "--frames" => frames = parse_number(value()?)?,
+            "--fps" => fps = parse_number(value()?)?,
+            "--size" => (base.width, base.height) = parse_size(value()?)?,
+            "--iterations" => base.max_iterations = parse_number(value()?)?,
+            "--palette" => {
+                let name = value()?;
+                colors.scheme = ColorScheme::from_name(name).ok_or_else(|| format!("unknown palette `{name}`"))?;
+                colors.gradient = None;
+            }
+            "--histogram" => colors.mapping = ColorMapping::Histogram,
+            "--aa" => base.anti_aliasing = parse_anti_aliasing(value()?)?,
+            "--out" => output.directory = PathBuf::from(value()?),
+            "--no-png" => output.write_png = false,
+            "--y4m" => output.y4m = Some(PathBuf::from(value()?)),
+            other => return Err(format!("unknown option `{other}`")),
+        }
+    }
+
+    let to = to.ok_or("`--to X,Y,ZOOM` is required")?;
+    let home = base.fractal_type.default_center();
+    let from = from.unwrap_or((home.x, home.y, 1.0));
+    if frames == 0 || fps == 0 {
+        return Err(String::from("`--frames` and `--fps` must be positive"));
+    }
+    if !output.write_png && output.y4m.is_none() {
+        return Err(String::from("nothing to write: `--no-png` needs `--y4m`"));
+    }
+
+    let keyframe = |(x, y, zoom): (f64, f64, f64), julia_c, palette_offset| Keyframe {
+        center: Point::new(x, y),
+        zoom,
+        julia_c,
+        palette_offset,
+    };
+    let animation = Animation {
+        keyframes: vec![
+            keyframe(from, base.julia_c, colors.offset),
+            keyframe(to, julia_to.unwrap_or(base.julia_c), offset_to.unwrap_or(colors.offset)),
+        ],
+        frames,
+        fps,
+    };
+    (base.center, base.zoom) = (Point::new(from.0, from.1), from.2);
+    Ok(AnimateArgs {
+        animation,
+        base,
+        colors,
+        output,
+    })
+}
+
+fn parse_render(args: &[String]) -> Result<RenderArgs, String> {
+    let mut scene: Option<Scene> = None;
+    let mut size: Option<(u32, u32)> = None;
+    let mut output: Option<PathBuf> = None;
+    let mut band_height: Option<u32> = None;
+    let mut tiled = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("`{arg}` needs a value"));
+        match arg.as_str() {
+            "--out" => output = Some(PathBuf::from(value()?)),
+            "--size" => size = Some(parse_size(value()?)?),
+            "--tiled" => tiled = true,
+            "--band-height" => {
+                band_height = Some(parse_number(value()?)?).filter(|&rows| rows > 0);
+                if band_height.is_none() {
+                    return Err(String::from("`--band-height` must be positive"));
+                }
+            }
+            flag if flag.starts_with("--") => return Err(format!("unknown option `{flag}`")),
+            path if scene.is_none() => scene = Some(Scene::load(Path::new(path))?),
+            extra => return Err(format!("unexpected argument `{extra}`")),
+        }
+    }
+
+    let mut scene = scene.ok_or("`render` needs a scene file")?;
+    if let Some((width, height)) = size {
+        (scene.render.width, scene.render.height) = (width, height);
+    }
+    if tiled || poster::needs_bands(scene.render.width, scene.render.height) {
+        band_height = band_height.or_else(|| Some(poster::default_band_height(&scene.render)));
+    }
+    Ok(RenderArgs {
+        scene,
+        output: output.unwrap_or_else(|| PathBuf::from("fractal.png")),
+        band_height,
+    })
+}
+
+fn run_render(args: &RenderArgs) -> Result<(), String> {
+    let (params, colors) = (&args.scene.render, &args.scene.colors);
+    let Some(band_height) = args.band_height else {
+        export::render_png(params, colors, &args.output).map_err(|error| error.to_string())?;
+        eprintln!("Saved {}", args.output.display());
+        return Ok(());
+    };
+
+    let total = poster::band_count(params.height, band_height.min(params.height));
+    let cancel = AtomicBool::new(false);
+    let summary = poster::render_poster(params, colors, &args.output, band_height, &cancel, |done| {
+        eprint!("\rBand {done}/{total}");
+    })
+    .map_err(|error| error.to_string())?;
+
+    eprintln!(
+        "\rSaved {} ({} band(s) rendered, {} resumed from disk)",
+        args.output.display(),
+        summary.rendered,
+        summary.reused
+    );
+    Ok(())
+}
+
+fn parse_tiles(args: &[String]) -> Result<TilesArgs, String> {
+    let mut scene: Option<Scene> = None;
+    let mut size: Option<(u32, u32)> = None;
+    let mut output: Option<PathBuf> = None;
+    let mut settings = PyramidSettings::default();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("`{arg}` needs a value"));
+        match arg.as_str() {
+            "--layout" => {
+                let name = value()?;
+                settings.layout = TileLayout::all()
+                    .into_iter()
+                    .find(|layout| layout.name().eq_ignore_ascii_case(name))
+                    .ok_or_else(|| format!("unknown tile layout `{name}`"))?;
+            }
+            "--out" => output = Some(PathBuf::from(value()?)),
+            "--size" => size = Some(parse_size(value()?)?),
+            "--tile-size" => settings.tile_size = parse_number(value()?)?,
+            "--overlap" => settings.overlap = parse_number(value()?)?,
+            "--max-zoom" => settings.max_zoom = parse_number(value()?)?,
+            flag if flag.starts_with("--") => return Err(format!("unknown option `{flag}`")),
+            path if scene.is_none() => scene = Some(Scene::load(Path::new(path))?),
+            extra => return Err(format!("unexpected argument `{extra}`")),
+        }
+    }
+
+    let mut scene = scene.ok_or("`tiles` needs a scene file")?;
+    if let Some((width, height)) = size {
+        (scene.render.width, scene.render.height) = (width, height);
+    }
+    if !(16..=1024).contains(&settings.tile_size) {
+        return Err(String::from("`--tile-size` must be between 16 and 1024"));
+    }
+    if settings.overlap >= settings.tile_size / 2 {
+        return Err(String::from("`--overlap` must be less than half the tile size"));
+    }
+    if settings.max_zoom > MAX_XYZ_ZOOM {
+        return Err(format!("`--max-zoom` must be at most {MAX_XYZ_ZOOM}"));
+    }
+    let output = output.unwrap_or_else(|| match settings.layout {
+        TileLayout::Dzi => PathBuf::from("tiles.dzi"),
+        TileLayout::Xyz => PathBuf::from("tiles"),
+    });
+    Ok(TilesArgs {
+        scene,
+        settings,
+        output,
+    })
+}
+
+fn run_tiles(args: &TilesArgs) -> Result<(), String> {
+    let total = tiles::tile_count(&args.scene.render, &args.settings);
+    let cancel = AtomicBool::new(false);
+    let summary = tiles::export_pyramid(
+        &args.scene.render,
+        &args.scene.colors,
+        &args.settings,
+        &args.output,
+        &cancel,
+        |done| eprint!("\rTile {done}/{total}"),
+    )
+    .map_err(|error| error.to_string())?;
+
+    eprintln!(
+        "\rRendered {} tile(s) to {}, {} already on disk",
+        summary.rendered,
+        args.output.display(),
+        summary.skipped
+    );
+    Ok(())
+}
+
+fn parse_serve(args: &[String]) -> Result<ServeArgs, String> {
+    let mut serve = ServeArgs {
+        address: String::from("127.0.0.1"),
+        port: 8080,
+        cache_bytes: server::DEFAULT_CACHE_BYTES,
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("`{arg}` needs a value"));
+        match arg.as_str() {
+            "--port" => serve.port = parse_number(value()?)?,
+            "--address" => serve.address = value()?.clone(),
+            "--cache-mb" => serve.cache_bytes = parse_number::<usize>(value()?)?.saturating_mul(1024 * 1024),
+            other => return Err(format!("unknown option `{other}`")),
+        }
+    }
+    Ok(serve)
+}
+
+fn run_serve(args: &ServeArgs) -> Result<(), String> {
+    let listener = TcpListener::bind((args.address.as_str(), args.port))
+        .map_err(|error| format!("cannot listen on {}:{}: {error}", args.address, args.port))?;
+    let address = listener.local_addr().map_err(|error| error.to_string())?;
+    eprintln!("Serving tiles on http://{address}/ (Ctrl+C to stop)");
+    server::serve(&listener, &TileServer::new(args.cache_bytes))
+}
+
+fn parse_density(args: &[String]) -> Result<DensityArgs, String> {
+    let mut density = DensityArgs {
+        settings: BuddhabrotSettings {
+            width: 1920,
+            height: 1080,
+            ..BuddhabrotSettings::default()
+        },
+        samples: 10_000_000,
+        tone: ToneMapping::default(),
+        exposure: 1.0,
+        output: PathBuf::from("buddhabrot.png"),
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("`{arg}` needs a value"));
+        let settings = &mut density.settings;
+        match arg.as_str() {
+            "--kind" => {
+                let name = value()?;
+                settings.kind = OrbitDensity::all()
+                    .into_iter()
+                    .find(|kind| normalize_name(kind.name()) == normalize_name(name))
+                    .ok_or_else(|| format!("unknown density plot `{name}`"))?;
+            }
+            "--view" => {
+                let (x, y, zoom) = parse_view(value()?)?;
+                (settings.center, settings.zoom) = (Point::new(x, y), zoom);
+            }
+            "--size" => (settings.width, settings.height) = parse_size(value()?)?,
+            "--samples" => density.samples = parse_number(value()?)?,
+            "--iterations" => {
+                let text = value()?;
+                let limits = text.split(',').map(parse_number).collect::<Result<Vec<u32>, _>>()?;
+                match limits.as_slice() {
+                    [limit] => settings.limits[0] = *limit,
+                    [red, green, blue] => settings.limits = [*red, *green, *blue],
+                    _ => return Err(format!("expected `N` or `R,G,B`, got `{text}`")),
+                }
+            }
+            "--tone" => {
+                let name = value()?;
+                density.tone = ToneMapping::all()
+                    .into_iter()
+                    .find(|tone| normalize_name(tone.name()) == normalize_name(name))
+                    .ok_or_else(|| format!("unknown tone mapping `{name}`"))?;
+            }
+            "--exposure" => density.exposure = parse_number(value()?)?,
+            "--seed" => settings.seed = parse_number(value()?)?,
+            "--out" => density.output = PathBuf::from(value()?),
+            other => return Err(format!("unknown option `{other}`")),
+        }
+    }
+    if density.samples == 0 || density.settings.limits.contains(&0) {
+        return Err(String::from("`--samples` and `--iterations` must be positive"));
+    }
+    Ok(density)
+}
+
+fn run_density(args: &DensityArgs) -> Result<(), String> {
+    const SAMPLES_PER_PASS: u64 = 1_000_000;
+    let map = args.settings.new_map();
+    let cancel = AtomicBool::new(false);
+    let passes = args.samples.div_ceil(SAMPLES_PER_PASS);
+    for pass in 0..passes {
+        let samples = SAMPLES_PER_PASS.min(args.samples - pass * SAMPLES_PER_PASS);
+        buddhabrot::accumulate(&args.settings, &map, pass, samples, &cancel);
+        eprint!("\rSamples {}/{}", map.samples(), args.samples);
+    }
+
+    map.tone_map(args.tone, args.exposure)
+        .save(&args.output)
+        .map_err(|error| error.to_string())?;
+    eprintln!("\rSaved {} ({} samples)", args.output.display(), map.samples());
+    Ok(())
+}
+
+fn parse_ifs(args: &[String]) -> Result<IfsArgs, String> {
+    let mut system = IfsSystem::default();
+    let mut view: Option<(f64, f64, f64)> = None;
+    let (mut width, mut height) = (1920, 1080);
+    let mut seed = 0;
+    let mut ifs = IfsArgs {
+        settings: IfsSettings::default(),
+        samples: 20_000_000,
+        scheme: ColorScheme::default(),
+        exposure: 1.0,
+        output: PathBuf::from("ifs.png"),
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("`{arg}` needs a value"));
+        match arg.as_str() {
+            "--preset" => {
+                let name = value()?;
+                system = IfsPreset::all()
+                    .into_iter()
+                    .find(|preset| normalize_name(preset.name()).starts_with(&normalize_name(name)))
+                    .ok_or_else(|| format!("unknown preset `{name}`"))?
+                    .system();
+            }
+            "--system" => {
+                let path = value()?;
+                let text = std::fs::read_to_string(path).map_err(|error| format!("cannot read `{path}`: {error}"))?;
+                system = serde_json::from_str(&text).map_err(|error| format!("invalid system `{path}`: {error}"))?;
+            }
+            "--view" => view = Some(parse_view(value()?)?),
+            "--size" => (width, height) = parse_size(value()?)?,
+            "--samples" => ifs.samples = parse_number(value()?)?,
+            "--palette" => {
+                let name = value()?;
+                ifs.scheme = ColorScheme::from_name(name).ok_or_else(|| format!("unknown palette `{name}`"))?;
+            }
+            "--exposure" => ifs.exposure = parse_number(value()?)?,
+            "--seed" => seed = parse_number(value()?)?,
+            "--out" => ifs.output = PathBuf::from(value()?),
+            other => return Err(format!("unknown option `{other}`")),
+        }
+    }
+    if ifs.samples == 0 {
+        return Err(String::from("`--samples` must be positive"));
+    }
+    if system.transforms.is_empty() {
+        return Err(String::from("the system has no transforms"));
+    }
+
+    ifs.settings = IfsSettings::fitted(system, width, height);
+    if let Some((x, y, zoom)) = view {
+        (ifs.settings.center, ifs.settings.zoom) = (Point::new(x, y), zoom);
+    }
+    ifs.settings.seed = seed;
+    Ok(ifs)
+}
+
+fn run_ifs(args: &IfsArgs) -> Result<(), String> {
+    const SAMPLES_PER_PASS: u64 = 1_000_000;
+    let map = args.settings.new_map();
+    let cancel = AtomicBool::new(false);
+    let passes = args.samples.div_ceil(SAMPLES_PER_PASS);
+    for pass in 0..passes {
+        let samples = SAMPLES_PER_PASS.min(args.samples - pass * SAMPLES_PER_PASS);
+        if ifs::accumulate(&args.settings, &map, pass, samples, &cancel) == 0 {
+            return Err(String::from("the system throws every point to infinity: there is nothing to plot"));
+        }
+        eprint!("\rSamples {}/{}", map.samples(), args.samples);
+    }
+
+    ifs::render(&map, args.scheme, args.exposure)
+        .save(&args.output)
+        .map_err(|error| error.to_string())?;
+    eprintln!("\rSaved {} ({} samples)", args.output.display(), map.samples());
+    Ok(())
+}
+
+fn run_animate(args: &AnimateArgs) -> Result<(), String> {
+    let total = args.animation.frames;
+    let cancel = AtomicBool::new(false);
+    let summary = sequence::render_sequence(&args.animation, &args.base, &args.colors, &args.output, &cancel, |done| {
+        eprint!("\rFrame {done}/{total}");
+    })
+    .map_err(|error| error.to_string())?;
+
+    eprintln!(
+        "\rRendered {} frame(s), {} already on disk",
+        summary.rendered, summary.skipped
+    );
+    Ok(())
+}
+
+fn parse_number<T: std::str::FromStr>(text: &str) -> Result<T, String> {
+    text.trim().parse().map_err(|_| format!("invalid number `{text}`"))
+}
+
+fn parse_point(text: &str) -> Result<Point, String> {
+    match text.split(',').collect::<Vec<_>>().as_slice() {
+        [x, y] => Ok(Point::new(parse_number(x)?, parse_number(y)?)),
+        _ => Err(format!("expected `X,Y`, got `{text}`")),
+    }
+}
+
+fn parse_view(text: &str) -> Result<(f64, f64, f64), String> {
+    match text.split(',').collect::<Vec<_>>().as_slice() {
+        [x, y, zoom] => {
+            let zoom: f64 = parse_number(zoom)?;
+            if zoom > 0.0 {
+                Ok((parse_number(x)?, parse_number(y)?, zoom))
+            } else {
+                Err(format!("zoom must be positive in `{text}`"))
+            }
+        }
+        _ => Err(format!("expected `X,Y,ZOOM`, got `{text}`")),
+    }
+}
+
+fn parse_size(text: &str) -> Result<(u32, u32), String> {
+    let (width, height) = text
+        .split_once(['x', 'X', '×'])
+        .ok_or_else(|| format!("expected `WxH`, got `{text}`"))?;
+    let (width, height) = (parse_number(width)?, parse_number(height)?);
+    if width == 0 || height == 0 {
+        return Err(format!("size must be positive, got `{text}`"));
+    }
+    Ok((width, height))
+}
+
+fn parse_anti_aliasing(text: &str) -> Result<AntiAliasing, String> {
+    let wanted = normalize_name(&text.replace('×', "x"));
+    AntiAliasing::all()
+        .into_iter()
+        .find(|mode| normalize_name(&mode.name().replace('×', "x")) == wanted)
+        .ok_or_else(|| format!("unknown anti-aliasing mode `{text}`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_no_arguments_opens_gui() {
+        assert_eq!(parse(&[]), Ok(Command::Gui));
+        assert!(parse(&args("frobnicate")).is_err());
+    }
+
+    #[test]
+    fn test_parse_animate() {
+        let Ok(Command::Animate(parsed)) = parse(&args(
+            "animate --fractal julia --to 0.1,-0.2,1e6 --frames 10 --size 320x200 --palette viridis --aa 2x2 --y4m out.y4m",
+        )) else {
+            panic!("expected an animate command");
+        };
+
+        assert_eq!(parsed.base.fractal_type, FractalType::Julia);
+        assert_eq!((parsed.base.width, parsed.base.height), (320, 200));
+        assert_eq!(parsed.base.anti_aliasing, AntiAliasing::Grid2x2);
+        assert_eq!(parsed.colors.scheme, ColorScheme::Viridis);
+        assert_eq!(parsed.animation.frames, 10);
+        assert_eq!(parsed.animation.keyframes[1].zoom, 1e6);
+        assert_eq!(parsed.output.y4m, Some(PathBuf::from("out.y4m")));
+        assert!(parsed.output.write_png);
+
+        let Ok(Command::Animate(lyapunov)) =
+            parse(&args("animate --fractal lyapunov --sequence bbbbbbaaaaaa --to 3.4,3.0,4"))
+        else {
+            panic!("expected an animate command");
+        };
+        assert_eq!(lyapunov.base.fractal_type, FractalType::Lyapunov);
+        assert_eq!(lyapunov.base.lyapunov_sequence.to_string(), "BBBBBBAAAAAA");
+        assert!(parse(&args("animate --fractal lyapunov --sequence ABC --to 3.4,3.0,4")).is_err());
+    }
+
+    #[test]
+    fn test_scene_defaults_and_overrides() {
+        let path = std::env::temp_dir().join(format!("fractals_rs_cli_scene_{}.json", std::process::id()));
+        let mut scene = Scene::default();
+        scene.render.center = Point::new(-1.25, 0.02);
+        scene.render.zoom = 30.0;
+        scene.render.max_iterations = 1234;
+        scene.colors.scheme = ColorScheme::Inferno;
+        scene.save(&path).unwrap();
+        let path_arg = path.to_string_lossy();
+
+        let Ok(Command::Render(render)) = parse(&args(&format!("render {path_arg} --size 64x32 --out a.png"))) else {
+            panic!("expected a render command");
+        };
+        assert_eq!((render.scene.render.width, render.scene.render.height), (64, 32));
+        assert_eq!(render.scene.render.max_iterations, 1234);
+        assert_eq!(render.output, PathBuf::from("a.png"));
+        assert_eq!(render.band_height, None);
+
+        let Ok(Command::Render(poster)) = parse(&args(&format!("render {path_arg} --size 60000x40000"))) else {
+            panic!("expected a render command");
+        };
+        assert_eq!(poster.band_height, Some(poster::default_band_height(&poster.scene.render)));
+        let Ok(Command::Render(banded)) = parse(&args(&format!("render {path_arg} --band-height 64"))) else {
+            panic!("expected a render command");
+        };
+        assert_eq!(banded.band_height, Some(64));
+        assert!(parse(&args(&format!("render {path_arg} --band-height 0"))).is_err());
+
+        let Ok(Command::Animate(animate)) =
+            parse(&args(&format!("animate --iterations 99 --scene {path_arg} --to 0,0,2")))
+        else {
+            panic!("expected an animate command");
+        };
+        assert_eq!(animate.animation.keyframes[0].center, Point::new(-1.25, 0.02));
+        assert_eq!(animate.animation.keyframes[0].zoom, 30.0);
+        assert_eq!(animate.base.max_iterations, 99);
+        assert_eq!(animate.colors.scheme, ColorScheme::Inferno);
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(parse(&args("render")).is_err());
+    }
+
+    #[test]
+    fn test_parse_tiles() {
+        let path = std::env::temp_dir().join(format!("fractals_rs_cli_tiles_{}.json", std::process::id()));
+        Scene::default().save(&path).unwrap();
+        let path_arg = path.to_string_lossy();
+
+        let Ok(Command::Tiles(dzi)) = parse(&args(&format!("tiles {path_arg} --size 8192x4096"))) else {
+            panic!("expected a tiles command");
+        };
+        assert_eq!(dzi.settings, PyramidSettings::default());
+        assert_eq!((dzi.scene.render.width, dzi.scene.render.height), (8192, 4096));
+        assert_eq!(dzi.output, PathBuf::from("tiles.dzi"));
+
+        let Ok(Command::Tiles(xyz)) = parse(&args(&format!("tiles {path_arg} --layout XYZ --max-zoom 8 --tile-size 512")))
+        else {
+            panic!("expected a tiles command");
+        };
+        assert_eq!(xyz.settings.layout, TileLayout::Xyz);
+        assert_eq!((xyz.settings.max_zoom, xyz.settings.tile_size), (8, 512));
+        assert_eq!(xyz.output, PathBuf::from("tiles"));
+
+        assert!(parse(&args(&format!("tiles {path_arg} --layout google"))).is_err());
+        assert!(parse(&args(&format!("tiles {path_arg} --max-zoom 30"))).is_err());
+        assert!(parse(&args(&format!("tiles {path_arg} --tile-size 8"))).is_err());
+        std::fs::remove_file(&path).unwrap();
+        assert!(parse(&args("tiles")).is_err());
+    }
+
+    #[test]
+    fn test_parse_serve() {
+        let Ok(Command::Serve(serve)) = parse(&args("serve --port 9000 --cache-mb 64")) else {
+            panic!("expected a serve command");
+        };
+        assert_eq!(serve.address, "127.0.0.1");
+        assert_eq!(serve.port, 9000);
+        assert_eq!(serve.cache_bytes, 64 * 1024 * 1024);
+        assert!(parse(&args("serve --port 70000")).is_err());
+        assert!(parse(&args("serve --open")).is_err());
+    }
+
+    #[test]
+    fn test_parse_density() {
+        let Ok(Command::Density(density)) = parse(&args(
+            "density --kind nebulabrot --view -0.2,0.1,4 --size 300x200 --iterations 2000,200,20 --tone logarithmic --samples 5000",
+        )) else {
+            panic!("expected a density command");
+        };
+        assert_eq!(density.settings.kind, OrbitDensity::Nebulabrot);
+        assert_eq!((density.settings.center, density.settings.zoom), (Point::new(-0.2, 0.1), 4.0));
+        assert_eq!((density.settings.width, density.settings.height), (300, 200));
+        assert_eq!(density.settings.limits, [2000, 200, 20]);
+        assert_eq!(density.samples, 5000);
+        assert_eq!(density.tone, ToneMapping::Logarithmic);
+        assert!(parse(&args("density --tone log")).is_err());
+        assert!(parse(&args("density --iterations 1,2")).is_err());
+        assert!(parse(&args("density --kind anti-buddhabrot --samples 0")).is_err());
+    }
+
+    #[test]
+    fn test_parse_ifs() {
+        let Ok(Command::Ifs(fern)) = parse(&args("ifs --preset barnsley-fern --size 300x400 --palette viridis --samples 5000"))
+        else {
+            panic!("expected an ifs command");
+        };
+        assert_eq!(fern.settings.system, IfsPreset::BarnsleyFern.system());
+        assert_eq!((fern.settings.width, fern.settings.height), (300, 400));
+        assert_eq!(fern.settings.system.fit(300, 400), (fern.settings.center, fern.settings.zoom));
+        assert_eq!(fern.scheme, ColorScheme::Viridis);
+        assert_eq!(fern.samples, 5000);
+
+        let Ok(Command::Ifs(dragon)) = parse(&args("ifs --preset dragon --view 0.5,0.2,3 --seed 7")) else {
+            panic!("expected an ifs command");
+        };
+        assert_eq!(dragon.settings.system, IfsPreset::DragonCurve.system());
+        assert_eq!((dragon.settings.center, dragon.settings.zoom), (Point::new(0.5, 0.2), 3.0));
+        assert_eq!(dragon.settings.seed, 7);
+
+        let path = std::env::temp_dir().join(format!("fractals_rs_cli_ifs_{}.json", std::process::id()));
+        std::fs::write(&path, r#"{"transforms": [{"affine": [0.5, 0, 0, 0, 0.5, 0], "variations": [["Swirl", 1.0]]}]}"#)
+            .unwrap();
+        let Ok(Command::Ifs(custom)) = parse(&args(&format!("ifs --system {}", path.to_string_lossy()))) else {
+            panic!("expected an ifs command");
+        };
+        assert_eq!(custom.settings.system.transforms[0].variations, [(ifs::Variation::Swirl, 1.0)]);
+        assert_eq!(custom.settings.system.transforms[0].weight, 1.0);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(parse(&args("ifs --preset koch")).is_err());
+        assert!(parse(&args("ifs --samples 0")).is_err());
+    }
+
+    #[test]
+    fn test_parse_animate_errors() {
+        assert!(parse(&args("animate")).is_err());
+        assert!(parse(&args("animate --to 0,0,0")).is_err());
+        assert!(parse(&args("animate --to 0,0,2 --size 10")).is_err());
+        assert!(parse(&args("animate --to 0,0,2 --no-png")).is_err());
+        assert!(parse(&args("animate --to 0,0,2 --frames")).is_err());
+    }
+}
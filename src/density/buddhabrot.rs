@@ -0,0 +1,242 @@
+use crate::density::density_map::DensityMap;
+use crate::density::random::Random;
+use crate::fractals::orbit::ESCAPE_RADIUS_SQUARED;
+use crate::utils::point::Point;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Samples handed to one thread at a time; cancellation is checked between batches.
+const BATCH_SIZE: u64 = 4096;
+
+/// Half the side of the square `c` is sampled from, which contains the whole Mandelbrot set.
+const SAMPLE_RADIUS: f64 = 2.0;
+
+/// Which orbits of the Mandelbrot iteration are plotted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum OrbitDensity {
+    #[default]
+    Buddhabrot,     // Orbits that escape
+    Nebulabrot,     // Escaping orbits, with one iteration limit per color channel
+    AntiBuddhabrot, // Orbits that never escape
+}
+
+impl OrbitDensity {
+    /// Returns the name of the plot.
+    #[inline]
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Buddhabrot => "Buddhabrot",
+            Self::Nebulabrot => "Nebulabrot",
+            Self::AntiBuddhabrot => "Anti-Buddhabrot",
+        }
+    }
+
+    /// Returns all available plots.
+    #[inline]
+    #[must_use]
+    pub const fn all() -> [Self; 3] {
+        [Self::Buddhabrot, Self::Nebulabrot, Self::AntiBuddhabrot]
+    }
+
+    /// Returns the number of color channels the plot accumulates.
+    #[inline]
+    #[must_use]
+    pub const fn channels(&self) -> usize {
+        match self {
+            Self::Nebulabrot => 3,
+            Self::Buddhabrot | Self::AntiBuddhabrot => 1,
+        }
+    }
+}
+
+/// Everything that determines an orbit density plot: which orbits, which part of the plane, and
+/// how long orbits are followed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BuddhabrotSettings {
+    pub kind: OrbitDensity,
+    pub center: Point,
+    pub zoom: f64,
+    pub width: u32,
+    pub height: u32,
+    pub limits: [u32; 3], // Iteration limits of the red, green and blue channels; one channel uses the first
+    pub seed: u64,
+}
+
+impl Default for BuddhabrotSettings {
+    /// The whole set at 800×600, with the classic 5000/500/50 Nebulabrot limits.
+    #[inline]
+    fn default() -> Self {
+        Self {
+            kind: OrbitDensity::Buddhabrot,
+            center: Point::new(-0.5, 0.0),
+            zoom: 1.0,
+            width: 800,
+            height: 600,
+            limits: [5000, 500, 50],
+            seed: 0,
+        }
+    }
+}
+
+impl BuddhabrotSettings {
+    /// Returns the iteration limit of every channel the plot uses.
+    #[inline]
+    #[must_use]
+    pub fn channel_limits(&self) -> &[u32] {
+        &self.limits[..self.kind.channels()]
+    }
+
+    /// Creates an empty map to accumulate this plot into.
+    #[must_use]
+    pub fn new_map(&self) -> DensityMap {
+        DensityMap::new(self.width, self.height, self.kind.channels())
+    }
+}
+
+/// Returns `true` if `c` lies in the main cardioid or the period-2 bulb, whose orbits never escape.
+#[inline]
+fn in_main_components(c: Point) -> bool {
+    let x_offset = c.x - 0.25;
+    let q = x_offset.mul_add(x_offset, c.y * c.y);
+    let x_plus_one = c.x + 1.0;
+    q.mul_add(q + x_offset, -(0.25 * c.y * c.y)) < 0.0 || x_plus_one.mul_add(x_plus_one, c.y * c.y) < 0.0625
+}
+
+/// Follows the orbit of `c` into `orbit` for at most `limit` steps and returns its iteration count,
+/// counted as the escape-time kernels do; the count equals `limit` if the orbit did not escape.
+fn follow(c: Point, limit: u32, orbit: &mut Vec<Point>) -> u32 {
+    orbit.clear();
+    let mut z = Point::new(0.0, 0.0);
+    let mut iterations = 0;
+    while iterations < limit && z.x.mul_add(z.x, z.y * z.y) <= ESCAPE_RADIUS_SQUARED {
+        z = Point::new(z.x.mul_add(z.x, -z.y * z.y) + c.x, (2.0 * z.x).mul_add(z.y, c.y));
+        orbit.push(z);
+        iterations += 1;
+    }
+    iterations
+}
+
+/// Takes `samples` random values of `c` and adds their orbits, from z₂ on, to `map`; `pass` selects an
+/// independent stream of samples, so successive passes keep refining the same plot. Orbits are
+/// also recorded mirrored, since the set is symmetric about the real axis. Returns early, with the
+/// samples taken so far added, if `cancel` is set.
+pub fn accumulate(settings: &BuddhabrotSettings, map: &DensityMap, pass: u64, samples: u64, cancel: &AtomicBool) {
+    let limits = settings.channel_limits();
+    let longest = limits.iter().copied().max().unwrap_or(0);
+    let pixel_size = 4.0 / settings.zoom / f64::from(settings.height);
+    let batches = samples.div_ceil(BATCH_SIZE);
+    let anti = settings.kind == OrbitDensity::AntiBuddhabrot;
+
+    (0..batches).into_par_iter().for_each_init(
+        || Vec::with_capacity(longest.min(1 << 16) as usize),
+        |orbit, batch| {
+            if cancel.load(Ordering::Relaxed) {
+                return;
+            }
+            let mut random = Random::for_batch(settings.seed ^ pass.wrapping_mul(0xA076_1D64_78BD_642F), batch);
+            let count = BATCH_SIZE.min(samples - batch * BATCH_SIZE);
+            for _ in 0..count {
+                let c = Point::new(
+                    random.range(-SAMPLE_RADIUS, SAMPLE_RADIUS),
+                    random.range(-SAMPLE_RADIUS, SAMPLE_RADIUS),
+                );
+                if !anti && in_main_components(c) {
+                    continue;
+                }
+                let iterations = follow(c, longest, orbit);
+                for (channel, &limit) in limits.iter().enumerate() {
+                    let plotted = if anti { iterations >= limit } else { iterations < limit };
+                    if !plotted {
+                        continue;
+                    }
+                    // z₁ = c itself is skipped: it would only paint the sampled square uniformly
+                    let length = (iterations.min(limit) as usize).min(orbit.len());
+                    for &z in orbit.get(1..length).unwrap_or_default() {
+                        for point in [z, Point::new(z.x, -z.y)] {
+                            if let Some((x, y)) = map.pixel(point, settings.center, pixel_size) {
+                                map.record(x, y, channel);
+                            }
+                        }
+                    }
+                }
+            }
+            map.add_samples(count);
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(kind: OrbitDensity) -> BuddhabrotSettings {
+        BuddhabrotSettings {
+            kind,
+            width: 64,
+            height: 48,
+            limits: [200, 50, 20],
+            ..BuddhabrotSettings::default()
+        }
+    }
+
+    fn total(map: &DensityMap, channel: usize) -> u64 {
+        (0..map.height as usize)
+            .flat_map(|y| (0..map.width as usize).map(move |x| (x, y)))
+            .map(|(x, y)| map.count(x, y, channel))
+            .sum()
+    }
+
+    #[test]
+    fn test_accumulation_is_reproducible_and_symmetric() {
+        let settings = settings(OrbitDensity::Buddhabrot);
+        let cancel = AtomicBool::new(false);
+        let (first, second) = (settings.new_map(), settings.new_map());
+        accumulate(&settings, &first, 0, 20_000, &cancel);
+        accumulate(&settings, &second, 0, 20_000, &cancel);
+        assert_eq!(first.samples(), 20_000);
+        assert!(total(&first, 0) > 0);
+        for y in 0..48 {
+            for x in 0..64 {
+                assert_eq!(first.count(x, y, 0), second.count(x, y, 0));
+                assert_eq!(first.count(x, y, 0), first.count(x, 47 - y, 0), "({x}, {y})");
+            }
+        }
+
+        // Another pass adds new samples instead of repeating the first ones
+        accumulate(&settings, &second, 1, 20_000, &cancel);
+        assert_eq!(second.samples(), 40_000);
+        assert_ne!(total(&second, 0), 2 * total(&first, 0));
+    }
+
+    #[test]
+    fn test_nebulabrot_channels_follow_their_limits() {
+        let settings = settings(OrbitDensity::Nebulabrot);
+        let map = settings.new_map();
+        accumulate(&settings, &map, 0, 20_000, &AtomicBool::new(false));
+        // A longer limit plots every orbit a shorter one does, and more
+        let (red, green, blue) = (total(&map, 0), total(&map, 1), total(&map, 2));
+        assert!(red > green && green > blue && blue > 0, "{red} {green} {blue}");
+    }
+
+    #[test]
+    fn test_anti_buddhabrot_stays_inside_the_set() {
+        let settings = settings(OrbitDensity::AntiBuddhabrot);
+        let map = settings.new_map();
+        accumulate(&settings, &map, 0, 5_000, &AtomicBool::new(false));
+        assert!(total(&map, 0) > 0);
+        // Nothing lands far right of the set, where every orbit escapes at once
+        assert!((0..48).all(|y| map.count(63, y, 0) == 0));
+    }
+
+    #[test]
+    fn test_cancel_stops_accumulation() {
+        let settings = settings(OrbitDensity::Buddhabrot);
+        let map = settings.new_map();
+        accumulate(&settings, &map, 0, 100_000, &AtomicBool::new(true));
+        assert_eq!(map.samples(), 0);
+    }
+}
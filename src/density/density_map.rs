@@ -0,0 +1,188 @@
+use crate::utils::point::Point;
+use image::RgbImage;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// How hit counts are compressed into brightness, relative to the busiest pixel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum ToneMapping {
+    Linear, // Brightness proportional to hits
+    #[default]
+    SquareRoot,  // Brings out faint structure while keeping contrast
+    Logarithmic, // Strongest compression, for very uneven densities
+}
+
+impl ToneMapping {
+    /// Returns the name of the tone mapping.
+    #[inline]
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Linear => "Linear",
+            Self::SquareRoot => "Square Root",
+            Self::Logarithmic => "Logarithmic",
+        }
+    }
+
+    /// Returns all available tone mappings.
+    #[inline]
+    #[must_use]
+    pub const fn all() -> [Self; 3] {
+        [Self::Linear, Self::SquareRoot, Self::Logarithmic]
+    }
+
+    /// Returns the brightness in `[0, 1]` of `count` hits when the busiest pixel has `max`.
+    #[inline]
+    #[must_use]
+    pub fn brightness(self, count: u64, max: u64) -> f64 {
+        if max == 0 {
+            return 0.0;
+        }
+        let (count, max) = (count as f64, max as f64);
+        match self {
+            Self::Linear => count / max,
+            Self::SquareRoot => (count / max).sqrt(),
+            Self::Logarithmic => count.ln_1p() / max.ln_1p(),
+        }
+    }
+}
+
+/// Hit counts of a density plot per pixel and channel, shared by every thread accumulating into it.
+/// Counts are 64-bit, so neither long runs nor channels summing weighted hits wrap around.
+pub struct DensityMap {
+    pub width: u32,
+    pub height: u32,
+    pub channels: usize, // 1 for a grey plot, 3 for red, green and blue
+    counts: Vec<AtomicU64>,
+    samples: AtomicU64, // Samples accumulated so far
+}
+
+impl DensityMap {
+    /// Creates an empty `width`×`height` map with `channels` counts per pixel.
+    #[must_use]
+    pub fn new(width: u32, height: u32, channels: usize) -> Self {
+        let len = width as usize * height as usize * channels;
+        Self {
+            width,
+            height,
+            channels,
+            counts: (0..len).map(|_| AtomicU64::new(0)).collect(),
+            samples: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the pixel the plane point `z` falls into, if it is in view. The view has the same
+    /// geometry as the escape-time renderer: `height` pixels of `pixel_size` centered on `center`.
+    #[inline]
+    #[must_use]
+    pub fn pixel(&self, z: Point, center: Point, pixel_size: f64) -> Option<(usize, usize)> {
+        let x = (z.x - center.x) / pixel_size + f64::from(self.width) / 2.0;
+        let y = (z.y - center.y) / pixel_size + f64::from(self.height) / 2.0;
+        (x >= 0.0 && y >= 0.0 && x < f64::from(self.width) && y < f64::from(self.height))
+            .then_some((x as usize, y as usize))
+    }
+
+    /// Adds one hit to `channel` of pixel `(x, y)`, which must be inside the map.
+    #[inline]
+    pub fn record(&self, x: usize, y: usize, channel: usize) {
+        self.add(x, y, channel, 1);
+    }
+
+    /// Adds `amount` to `channel` of pixel `(x, y)`, which must be inside the map.
+    #[inline]
+    pub fn add(&self, x: usize, y: usize, channel: usize, amount: u64) {
+        let index = (y * self.width as usize + x) * self.channels + channel;
+        self.counts[index].fetch_add(amount, Ordering::Relaxed);
+    }
+
+    /// Returns the hits of `channel` at pixel `(x, y)`.
+    #[inline]
+    #[must_use]
+    pub fn count(&self, x: usize, y: usize, channel: usize) -> u64 {
+        self.counts[(y * self.width as usize + x) * self.channels + channel].load(Ordering::Relaxed)
+    }
+
+    /// Returns the hits of the busiest pixel of `channel`.
+    #[must_use]
+    pub fn max_count(&self, channel: usize) -> u64 {
+        self.counts
+            .iter()
+            .skip(channel)
+            .step_by(self.channels)
+            .map(|count| count.load(Ordering::Relaxed))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Records that `samples` more samples were taken.
+    #[inline]
+    pub fn add_samples(&self, samples: u64) {
+        self.samples.fetch_add(samples, Ordering::Relaxed);
+    }
+
+    /// Returns the number of samples accumulated so far.
+    #[inline]
+    #[must_use]
+    pub fn samples(&self) -> u64 {
+        self.samples.load(Ordering::Relaxed)
+    }
+
+    /// Converts the counts to an image: each channel is tone mapped against its own busiest pixel
+    /// and scaled by `exposure`; a single channel is drawn in grey.
+    #[must_use]
+    pub fn tone_map(&self, tone: ToneMapping, exposure: f64) -> RgbImage {
+        let maxima: Vec<u64> = (0..self.channels).map(|channel| self.max_count(channel)).collect();
+        let mut image = RgbImage::new(self.width, self.height);
+        for (pixel, counts) in image.pixels_mut().zip(self.counts.chunks_exact(self.channels)) {
+            for (rgb, value) in pixel.0.iter_mut().enumerate() {
+                let channel = rgb.min(self.channels - 1);
+                let brightness = tone.brightness(counts[channel].load(Ordering::Relaxed), maxima[channel]) * exposure;
+                *value = (brightness.clamp(0.0, 1.0) * 255.0).round() as u8;
+            }
+        }
+        image
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tone_map() {
+        let map = DensityMap::new(3, 1, 1);
+        for _ in 0..4 {
+            map.record(0, 0, 0);
+        }
+        map.record(1, 0, 0);
+        assert_eq!(map.max_count(0), 4);
+
+        let linear = map.tone_map(ToneMapping::Linear, 1.0);
+        assert_eq!(linear.get_pixel(0, 0).0, [255; 3]);
+        assert_eq!(linear.get_pixel(1, 0).0, [64; 3]);
+        assert_eq!(linear.get_pixel(2, 0).0, [0; 3]);
+        assert_eq!(map.tone_map(ToneMapping::SquareRoot, 1.0).get_pixel(1, 0).0, [128; 3]);
+        assert_eq!(map.tone_map(ToneMapping::Linear, 2.0).get_pixel(1, 0).0, [128; 3]);
+    }
+
+    #[test]
+    fn test_counts_do_not_wrap() {
+        let map = DensityMap::new(1, 1, 2);
+        map.add(0, 0, 1, u64::from(u32::MAX));
+        map.add(0, 0, 1, 255);
+        assert_eq!(map.count(0, 0, 1), u64::from(u32::MAX) + 255);
+        assert_eq!(map.max_count(1), u64::from(u32::MAX) + 255);
+    }
+
+    #[test]
+    fn test_channels_are_mapped_separately() {
+        let map = DensityMap::new(1, 2, 3);
+        map.record(0, 0, 0);
+        map.record(0, 1, 0);
+        map.record(0, 1, 2);
+        let image = map.tone_map(ToneMapping::Linear, 1.0);
+        assert_eq!(image.get_pixel(0, 0).0, [255, 0, 0]);
+        assert_eq!(image.get_pixel(0, 1).0, [255, 0, 255]);
+    }
+}
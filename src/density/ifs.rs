@@ -0,0 +1,507 @@
+use crate::density::density_map::{DensityMap, ToneMapping};
+use crate::density::random::Random;
+use crate::utils::color_scheme::ColorScheme;
+use crate::utils::point::Point;
+use image::RgbImage;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Points handed to one thread at a time, each batch playing its own chaos game; cancellation is
+/// checked between batches.
+const BATCH_SIZE: u64 = 4096;
+
+/// Steps a chaos game takes before its points are plotted, so they lie on the attractor.
+const SETTLE_STEPS: u32 = 20;
+
+/// Points sampled to find the extent of an attractor.
+const FIT_SAMPLES: usize = 20_000;
+
+/// Points thrown to infinity in a row after which a batch is given up: the system has no
+/// attractor to plot.
+const MAX_RESTARTS: u32 = 1000;
+
+/// Channel of the map counting hits.
+const HITS: usize = 0;
+
+/// Channel of the map summing the palette positions of the hits, out of 255.
+const COLOR: usize = 1;
+
+/// A nonlinear function applied to a point after a transform's affine map, as in flame fractals.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum Variation {
+    #[default]
+    Linear,       // Leaves the point where the affine map put it
+    Sinusoidal,   // Folds the plane into a square
+    Spherical,    // Inverts in the unit circle
+    Swirl,        // Rotates by the squared distance from the origin
+    Horseshoe,    // Bends lines into arcs
+    Polar,        // Unrolls angle and radius into x and y
+    Handkerchief, // Twists by angle plus radius
+    Heart,        // Pinches the plane into a heart
+    Disc,         // Wraps angle around radius
+    Spiral,       // Curls around the origin
+}
+
+impl Variation {
+    /// Returns the name of the variation.
+    #[inline]
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Linear => "Linear",
+            Self::Sinusoidal => "Sinusoidal",
+            Self::Spherical => "Spherical",
+            Self::Swirl => "Swirl",
+            Self::Horseshoe => "Horseshoe",
+            Self::Polar => "Polar",
+            Self::Handkerchief => "Handkerchief",
+            Self::Heart => "Heart",
+            Self::Disc => "Disc",
+            Self::Spiral => "Spiral",
+        }
+    }
+
+    /// Returns all available variations.
+    #[inline]
+    #[must_use]
+    pub const fn all() -> [Self; 10] {
+        [
+            Self::Linear,
+            Self::Sinusoidal,
+            Self::Spherical,
+            Self::Swirl,
+            Self::Horseshoe,
+            Self::Polar,
+            Self::Handkerchief,
+            Self::Heart,
+            Self::Disc,
+            Self::Spiral,
+        ]
+    }
+
+    /// Applies the variation to `p`, with the flame conventions r = |p| and θ = atan2(x, y).
+    #[inline]
+    pub fn apply(self, p: Point) -> Point {
+        let r2 = p.x.mul_add(p.x, p.y * p.y);
+        let r = r2.sqrt();
+        let theta = p.x.atan2(p.y);
+        match self {
+            Self::Linear => p,
+            Self::Sinusoidal => Point::new(p.x.sin(), p.y.sin()),
+            Self::Spherical => Point::new(p.x / r2, p.y / r2),
+            Self::Swirl => {
+                let (sin, cos) = r2.sin_cos();
+                Point::new(p.x.mul_add(sin, -p.y * cos), p.x.mul_add(cos, p.y * sin))
+            }
+            Self::Horseshoe => Point::new((p.x - p.y) * (p.x + p.y) / r, 2.0 * p.x * p.y / r),
+            Self::Polar => Point::new(theta / PI, r - 1.0),
+            Self::Handkerchief => Point::new(r * (theta + r).sin(), r * (theta - r).cos()),
+            Self::Heart => Point::new(r * (theta * r).sin(), -r * (theta * r).cos()),
+            Self::Disc => Point::new(theta / PI * (PI * r).sin(), theta / PI * (PI * r).cos()),
+            Self::Spiral => Point::new((theta.cos() + r.sin()) / r, (theta.sin() - r.cos()) / r),
+        }
+    }
+}
+
+/// One map of an iterated function system: an affine map followed by a weighted blend of
+/// variations.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Transform {
+    pub affine: [f64; 6], // [a, b, c, d, e, f]: x' = a·x + b·y + c, y' = d·x + e·y + f
+    pub weight: f64,      // Relative probability of being picked
+    pub color: f64,       // Palette position in [0, 1] the transform pulls its points toward
+    pub variations: Vec<(Variation, f64)>, // Empty for a purely affine map
+}
+
+impl Default for Transform {
+    /// Halves the plane toward the origin.
+    #[inline]
+    fn default() -> Self {
+        Self::affine([0.5, 0.0, 0.0, 0.0, 0.5, 0.0], 1.0, 0.0)
+    }
+}
+
+impl Transform {
+    /// Creates a purely affine transform.
+    #[inline]
+    #[must_use]
+    pub const fn affine(affine: [f64; 6], weight: f64, color: f64) -> Self {
+        Self {
+            affine,
+            weight,
+            color,
+            variations: Vec::new(),
+        }
+    }
+
+    /// Adds `variation` to the blend, with `weight`.
+    #[must_use]
+    pub fn with_variation(mut self, variation: Variation, weight: f64) -> Self {
+        self.variations.push((variation, weight));
+        self
+    }
+
+    /// Maps `p` through the affine map, then through the blend of variations.
+    #[inline]
+    pub fn apply(&self, p: Point) -> Point {
+        let [a, b, c, d, e, f] = self.affine;
+        let q = Point::new(a.mul_add(p.x, b.mul_add(p.y, c)), d.mul_add(p.x, e.mul_add(p.y, f)));
+        if self.variations.is_empty() {
+            return q;
+        }
+        self.variations.iter().fold(Point::new(0.0, 0.0), |sum, &(variation, weight)| {
+            let v = variation.apply(q);
+            Point::new(weight.mul_add(v.x, sum.x), weight.mul_add(v.y, sum.y))
+        })
+    }
+}
+
+/// A set of transforms whose attractor the chaos game draws.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IfsSystem {
+    pub transforms: Vec<Transform>,
+}
+
+impl Default for IfsSystem {
+    /// The Sierpinski triangle.
+    #[inline]
+    fn default() -> Self {
+        IfsPreset::default().system()
+    }
+}
+
+impl IfsSystem {
+    /// Returns the running totals of the transforms' weights, to pick them from a uniform number.
+    fn cumulative_weights(&self) -> Vec<f64> {
+        self.transforms
+            .iter()
+            .scan(0.0, |total, transform| {
+                *total += transform.weight.max(0.0);
+                Some(*total)
+            })
+            .collect()
+    }
+
+    /// Returns the transform `random` lands on, each picked in proportion to its weight.
+    #[inline]
+    fn pick<'a>(&'a self, cumulative: &[f64], random: &mut Random) -> &'a Transform {
+        let total = cumulative.last().copied().unwrap_or(0.0);
+        let target = random.next_f64() * total;
+        let index = cumulative.partition_point(|&sum| sum <= target);
+        &self.transforms[index.min(self.transforms.len() - 1)]
+    }
+
+    /// Returns the center and zoom that frame the attractor in a `width`×`height` view, with a
+    /// margin; the sparsest 0.5% of points on each side are left out, so rare outliers of
+    /// nonlinear variations do not shrink the picture.
+    pub fn fit(&self, width: u32, height: u32) -> (Point, f64) {
+        let cumulative = self.cumulative_weights();
+        if cumulative.last().is_none_or(|&total| total <= 0.0) {
+            return (Point::new(0.0, 0.0), 1.0);
+        }
+        let mut random = Random::new(0);
+        let mut p = Point::new(random.range(-1.0, 1.0), random.range(-1.0, 1.0));
+        let (mut xs, mut ys) = (Vec::with_capacity(FIT_SAMPLES), Vec::with_capacity(FIT_SAMPLES));
+        for step in 0..FIT_SAMPLES as u32 + SETTLE_STEPS {
+            p = self.pick(&cumulative, &mut random).apply(p);
+            if !(p.x.is_finite() && p.y.is_finite()) {
+                p = Point::new(random.range(-1.0, 1.0), random.range(-1.0, 1.0));
+            } else if step >= SETTLE_STEPS {
+                xs.push(p.x);
+                ys.push(p.y);
+            }
+        }
+        if xs.is_empty() {
+            return (Point::new(0.0, 0.0), 1.0);
+        }
+
+        let extent = |values: &mut Vec<f64>| {
+            values.sort_by(f64::total_cmp);
+            let cut = values.len() / 200;
+            (values[cut], values[values.len() - 1 - cut])
+        };
+        let ((x_min, x_max), (y_min, y_max)) = (extent(&mut xs), extent(&mut ys));
+        let aspect = f64::from(width.max(1)) / f64::from(height.max(1));
+        let span = (y_max - y_min).max((x_max - x_min) / aspect) * 1.1;
+        let zoom = if span > 0.0 && span.is_finite() { 4.0 / span } else { 1.0 };
+        (Point::new(f64::midpoint(x_min, x_max), f64::midpoint(y_min, y_max)), zoom)
+    }
+}
+
+/// Ready-made systems. The plane's y axis points down on the canvas, so the affine maps are
+/// written mirrored to stand upright.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum IfsPreset {
+    #[default]
+    Sierpinski,   // Three half-size copies of a triangle
+    BarnsleyFern, // Four maps drawing a fern leaf
+    DragonCurve,  // Heighway's dragon, two rotating maps
+    Flame,        // Three maps bent by sinusoidal, spherical and swirl variations
+}
+
+impl IfsPreset {
+    /// Returns the name of the preset.
+    #[inline]
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Sierpinski => "Sierpinski Triangle",
+            Self::BarnsleyFern => "Barnsley Fern",
+            Self::DragonCurve => "Dragon Curve",
+            Self::Flame => "Flame",
+        }
+    }
+
+    /// Returns all available presets.
+    #[inline]
+    #[must_use]
+    pub const fn all() -> [Self; 4] {
+        [Self::Sierpinski, Self::BarnsleyFern, Self::DragonCurve, Self::Flame]
+    }
+
+    /// Returns the preset's system.
+    #[must_use]
+    pub fn system(&self) -> IfsSystem {
+        let transforms = match self {
+            Self::Sierpinski => {
+                let apex = -(3.0f64.sqrt() / 4.0);
+                vec![
+                    Transform::affine([0.5, 0.0, 0.0, 0.0, 0.5, 0.0], 1.0, 0.0),
+                    Transform::affine([0.5, 0.0, 0.5, 0.0, 0.5, 0.0], 1.0, 0.5),
+                    Transform::affine([0.5, 0.0, 0.25, 0.0, 0.5, apex], 1.0, 1.0),
+                ]
+            }
+            Self::BarnsleyFern => vec![
+                Transform::affine([0.0, 0.0, 0.0, 0.0, 0.16, 0.0], 0.01, 0.0),
+                Transform::affine([0.85, -0.04, 0.0, 0.04, 0.85, -1.6], 0.85, 0.4),
+                Transform::affine([0.2, 0.26, 0.0, -0.23, 0.22, -1.6], 0.07, 0.7),
+                Transform::affine([-0.15, -0.28, 0.0, -0.26, 0.24, -0.44], 0.07, 1.0),
+            ],
+            Self::DragonCurve => vec![
+                Transform::affine([0.5, -0.5, 0.0, 0.5, 0.5, 0.0], 1.0, 0.0),
+                Transform::affine([-0.5, -0.5, 1.0, 0.5, -0.5, 0.0], 1.0, 1.0),
+            ],
+            Self::Flame => vec![
+                Transform::affine([0.562, -0.535, 0.0, 0.397, 0.501, 0.0], 1.0, 0.0)
+                    .with_variation(Variation::Sinusoidal, 1.0),
+                Transform::affine([-0.5, 0.3, 0.5, 0.3, 0.5, 0.2], 1.0, 0.5)
+                    .with_variation(Variation::Spherical, 1.0),
+                Transform::affine([0.4, 0.2, -0.3, -0.2, 0.4, 0.4], 1.0, 1.0).with_variation(Variation::Swirl, 1.0),
+            ],
+        };
+        IfsSystem { transforms }
+    }
+}
+
+/// Everything that determines an IFS plot: the system and which part of the plane is drawn.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IfsSettings {
+    pub system: IfsSystem,
+    pub center: Point,
+    pub zoom: f64,
+    pub width: u32,
+    pub height: u32,
+    pub seed: u64,
+}
+
+impl Default for IfsSettings {
+    /// The Sierpinski triangle at 800×600, framed to fit.
+    #[inline]
+    fn default() -> Self {
+        Self::fitted(IfsSystem::default(), 800, 600)
+    }
+}
+
+impl IfsSettings {
+    /// Creates settings for `system` whose view frames its attractor.
+    #[must_use]
+    pub fn fitted(system: IfsSystem, width: u32, height: u32) -> Self {
+        let (center, zoom) = system.fit(width, height);
+        Self {
+            system,
+            center,
+            zoom,
+            width,
+            height,
+            seed: 0,
+        }
+    }
+
+    /// Creates an empty map to accumulate this plot into.
+    #[must_use]
+    pub fn new_map(&self) -> DensityMap {
+        DensityMap::new(self.width, self.height, 2)
+    }
+}
+
+/// Plays `samples` steps of the chaos game and adds the points to `map`, each carrying a palette
+/// position that moves halfway toward the color of every transform applied; `pass` selects an
+/// independent stream, so successive passes keep refining the same plot. Points thrown to
+/// infinity by a variation restart from a random point; a batch whose points keep escaping
+/// `MAX_RESTARTS` times in a row is given up. Returns early, with the samples taken so far added,
+/// if `cancel` is set. Returns the number of samples taken, 0 for a system without an attractor.
+pub fn accumulate(settings: &IfsSettings, map: &DensityMap, pass: u64, samples: u64, cancel: &AtomicBool) -> u64 {
+    let system = &settings.system;
+    let cumulative = system.cumulative_weights();
+    if cumulative.last().is_none_or(|&total| total <= 0.0) {
+        return 0;
+    }
+    let pixel_size = 4.0 / settings.zoom / f64::from(settings.height);
+    let batches = samples.div_ceil(BATCH_SIZE);
+
+    (0..batches).into_par_iter().map(|batch| {
+        if cancel.load(Ordering::Relaxed) {
+            return 0;
+        }
+        let mut random = Random::for_batch(settings.seed ^ pass.wrapping_mul(0xA076_1D64_78BD_642F), batch);
+        let count = BATCH_SIZE.min(samples - batch * BATCH_SIZE);
+        let mut p = Point::new(random.range(-1.0, 1.0), random.range(-1.0, 1.0));
+        let mut color = 0.5;
+        let mut settling = SETTLE_STEPS;
+        let mut plotted = 0;
+        let mut restarts = 0;
+        while plotted < count {
+            let transform = system.pick(&cumulative, &mut random);
+            p = transform.apply(p);
+            color = f64::midpoint(color, transform.color.clamp(0.0, 1.0));
+            if !(p.x.is_finite() && p.y.is_finite()) {
+                restarts += 1;
+                if restarts >= MAX_RESTARTS || cancel.load(Ordering::Relaxed) {
+                    break;
+                }
+                p = Point::new(random.range(-1.0, 1.0), random.range(-1.0, 1.0));
+                settling = SETTLE_STEPS;
+                continue;
+            }
+            restarts = 0;
+            if settling > 0 {
+                settling -= 1;
+                continue;
+            }
+            if let Some((x, y)) = map.pixel(p, settings.center, pixel_size) {
+                map.record(x, y, HITS);
+                map.add(x, y, COLOR, (color * 255.0).round() as u64);
+            }
+            plotted += 1;
+        }
+        map.add_samples(plotted);
+        plotted
+    })
+    .sum()
+}
+
+/// Converts an IFS map to an image: each pixel takes the palette color of its hits' average
+/// position in `scheme`, with a brightness that grows with the logarithm of its hits, relative
+/// to the busiest pixel and scaled by `exposure`.
+#[must_use]
+pub fn render(map: &DensityMap, scheme: ColorScheme, exposure: f64) -> RgbImage {
+    let palette: Vec<[u8; 3]> = (0..256)
+        .map(|i| {
+            let color = scheme.color_at(i as f32 / 256.0);
+            [color.r(), color.g(), color.b()]
+        })
+        .collect();
+    let max = map.max_count(HITS);
+    let mut image = RgbImage::new(map.width, map.height);
+    for (x, y, pixel) in image.enumerate_pixels_mut() {
+        let (x, y) = (x as usize, y as usize);
+        let hits = map.count(x, y, HITS);
+        if hits == 0 {
+            continue;
+        }
+        let brightness = (ToneMapping::Logarithmic.brightness(hits, max) * exposure).clamp(0.0, 1.0);
+        let color = palette[(map.count(x, y, COLOR) / hits).min(255) as usize];
+        pixel.0 = color.map(|channel| (f64::from(channel) * brightness).round() as u8);
+    }
+    image
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(preset: IfsPreset) -> IfsSettings {
+        IfsSettings::fitted(preset.system(), 64, 48)
+    }
+
+    #[test]
+    fn test_variations() {
+        let p = Point::new(0.6, -0.8);
+        assert_eq!(Variation::Linear.apply(p), p);
+        let spherical = Variation::Spherical.apply(Point::new(2.0, 0.0));
+        assert!((spherical.x - 0.5).abs() < 1e-12 && spherical.y == 0.0);
+        // Polar maps the unit circle onto y = 0
+        assert!(Variation::Polar.apply(p).y.abs() < 1e-12);
+        for variation in Variation::all() {
+            let q = variation.apply(p);
+            assert!(q.x.is_finite() && q.y.is_finite(), "{}", variation.name());
+        }
+
+        let blend = Transform::default()
+            .with_variation(Variation::Linear, 0.5)
+            .with_variation(Variation::Sinusoidal, 0.5);
+        let half = Point::new(0.3, -0.4);
+        let expected = Point::new(0.5 * 0.3 + 0.5 * 0.3f64.sin(), 0.5 * -0.4 + 0.5 * (-0.4f64).sin());
+        assert!((blend.apply(p).x - expected.x).abs() < 1e-12 && (blend.apply(p).y - expected.y).abs() < 1e-12);
+        assert_eq!(Transform::default().apply(p), half);
+    }
+
+    #[test]
+    fn test_fit_frames_the_attractor() {
+        // The Sierpinski triangle spans x in [0, 1] and y in [-√3/2, 0]
+        let (center, zoom) = IfsPreset::Sierpinski.system().fit(800, 600);
+        assert!((center.x - 0.5).abs() < 0.02 && (center.y + 0.433).abs() < 0.02, "{center:?}");
+        assert!((4.0 / zoom - 0.866 * 1.1).abs() < 0.05, "{zoom}");
+    }
+
+    #[test]
+    fn test_accumulation_is_reproducible() {
+        for preset in IfsPreset::all() {
+            let settings = settings(preset);
+            let cancel = AtomicBool::new(false);
+            let (first, second) = (settings.new_map(), settings.new_map());
+            accumulate(&settings, &first, 0, 20_000, &cancel);
+            accumulate(&settings, &second, 0, 20_000, &cancel);
+            assert_eq!(first.samples(), 20_000);
+            assert!(first.max_count(HITS) > 0, "{}", preset.name());
+            assert_eq!(render(&first, ColorScheme::default(), 1.0), render(&second, ColorScheme::default(), 1.0));
+        }
+    }
+
+    #[test]
+    fn test_sierpinski_leaves_the_middle_empty() {
+        let settings = settings(IfsPreset::Sierpinski);
+        let map = settings.new_map();
+        accumulate(&settings, &map, 0, 50_000, &AtomicBool::new(false));
+        // The centroid of the triangle lies in the removed middle quarter
+        let pixel_size = 4.0 / settings.zoom / 48.0;
+        let (x, y) = map.pixel(Point::new(0.5, -0.289), settings.center, pixel_size).unwrap();
+        assert_eq!(map.count(x, y, HITS), 0);
+
+        let image = render(&map, ColorScheme::default(), 1.0);
+        assert_eq!(image.get_pixel(x as u32, y as u32).0, [0; 3]);
+        assert!(image.pixels().any(|pixel| pixel.0 != [0; 3]));
+    }
+
+    #[test]
+    fn test_accumulation_gives_up_without_attractor() {
+        // Every point is mapped to the origin, which the spherical variation throws to infinity
+        let system = IfsSystem {
+            transforms: vec![Transform::affine([0.0; 6], 1.0, 0.0).with_variation(Variation::Spherical, 1.0)],
+        };
+        let settings = IfsSettings {
+            system,
+            ..settings(IfsPreset::Sierpinski)
+        };
+        let map = settings.new_map();
+        assert_eq!(accumulate(&settings, &map, 0, 50_000, &AtomicBool::new(false)), 0);
+        assert_eq!(map.samples(), 0);
+        assert_eq!(map.max_count(HITS), 0);
+    }
+}
@@ -0,0 +1,4 @@
+pub mod random;
+pub mod density_map;
+pub mod buddhabrot;
+pub mod ifs;
\ No newline at end of file
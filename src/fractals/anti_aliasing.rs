@@ -0,0 +1,312 @@
+use crate::fractals::renderer::{self, IterationBuffer, RenderParams};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Supersampling strategy used to smooth the set boundary.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum AntiAliasing {
+    #[default]
+    Off,
+    Grid2x2,  // 4 samples per pixel on a regular grid
+    Grid3x3,  // 9 samples per pixel
+    Grid4x4,  // 16 samples per pixel
+    Jittered, // 9 stratified samples per pixel, randomly placed inside each cell
+    Adaptive, // 16 samples, only where neighbouring iteration counts differ strongly
+}
+
+/// Extra samples of the pixels that were supersampled.
+/// Pixel `i` is refined when `index[i] != 0`; its samples are then
+/// `samples[(index[i] - 1) * count..index[i] * count]`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Subsamples {
+    pub count: usize,
+    pub index: Vec<u32>,
+    pub samples: Vec<u16>,
+}
+
+impl AntiAliasing {
+    /// Returns the name of the anti-aliasing mode.
+    #[inline]
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::Grid2x2 => "2×2",
+            Self::Grid3x3 => "3×3",
+            Self::Grid4x4 => "4×4",
+            Self::Jittered => "Jittered",
+            Self::Adaptive => "Adaptive",
+        }
+    }
+
+    /// Returns all available anti-aliasing modes.
+    #[inline]
+    #[must_use]
+    pub const fn all() -> [Self; 6] {
+        [
+            Self::Off,
+            Self::Grid2x2,
+            Self::Grid3x3,
+            Self::Grid4x4,
+            Self::Jittered,
+            Self::Adaptive,
+        ]
+    }
+
+    /// Returns the side of the sample grid laid over each refined pixel.
+    #[inline]
+    #[must_use]
+    pub const fn grid_size(&self) -> usize {
+        match self {
+            Self::Off => 1,
+            Self::Grid2x2 => 2,
+            Self::Grid3x3 | Self::Jittered => 3,
+            Self::Grid4x4 | Self::Adaptive => 4,
+        }
+    }
+}
+
+/// Returns the offset, in pixels from the primary sample, of grid cell `cell` out of `grid`.
+#[inline(always)]
+fn grid_offset(cell: usize, grid: usize) -> f64 {
+    (cell as f64 + 0.5) / grid as f64 - 0.5
+}
+
+/// Deterministic hash to `[0, 1)`, so jittered renders (and their recolors) are reproducible.
+#[inline(always)]
+fn hash01(x: usize, y: usize, salt: usize) -> f64 {
+    let mut h = (x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (y as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+        ^ (salt as u64).wrapping_mul(0x1656_67B1_9E37_79F9);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xFF51_AFD7_ED55_8CCD);
+    h ^= h >> 33;
+    (h >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Computes the extra samples requested by `params.anti_aliasing` for a buffer of primary samples.
+#[must_use]
+pub fn supersample(params: &RenderParams, primary: &IterationBuffer) -> Option<Subsamples> {
+    let mode = params.anti_aliasing;
+    let grid = mode.grid_size();
+    let count = grid * grid;
+    let (width, height) = (primary.width, primary.height);
+
+    match mode {
+        AntiAliasing::Off => None,
+        _ if width == 0 || height == 0 => None,
+        AntiAliasing::Grid2x2 | AntiAliasing::Grid3x3 | AntiAliasing::Grid4x4 => {
+            // One SIMD row per grid cell, interleaved per pixel as it is computed, so no more than
+            // a row of each thread is held outside the interleaved buffer
+            let mut samples = vec![0u16; width * height * count];
+            samples
+                .par_chunks_mut(width * count)
+                .enumerate()
+                .for_each(|(y, row)| {
+                    let mut pass = vec![0u16; width];
+                    for cell in 0..count {
+                        let (x, y) = (grid_offset(cell % grid, grid), y as f64 + grid_offset(cell / grid, grid));
+                        renderer::fill_row(params, &mut pass, x, y);
+                        for (out, &sample) in row.chunks_exact_mut(count).zip(&pass) {
+                            out[cell] = sample;
+                        }
+                    }
+                });
+
+            Some(Subsamples {
+                count,
+                index: (1..=(width * height) as u32).collect(),
+                samples,
+            })
+        }
+        AntiAliasing::Jittered => {
+            let mut samples = vec![0u16; width * height * count];
+            samples
+                .par_chunks_mut(width * count)
+                .enumerate()
+                .for_each(|(y, row)| {
+                    for (x, out) in row.chunks_mut(count).enumerate() {
+                        for (cell, sample) in out.iter_mut().enumerate() {
+                            let ox = (hash01(x, y, 2 * cell) + (cell % grid) as f64) / grid as f64 - 0.5;
+                            let oy = (hash01(x, y, 2 * cell + 1) + (cell / grid) as f64) / grid as f64 - 0.5;
+                            *sample = renderer::sample_point(params, x as f64 + ox, y as f64 + oy);
+                        }
+                    }
+                });
+
+            Some(Subsamples {
+                count,
+                index: (1..=(width * height) as u32).collect(),
+                samples,
+            })
+        }
+        AntiAliasing::Adaptive => {
+            let edges = find_edges(primary);
+            let mut index = vec![0u32; width * height];
+            let mut refined = Vec::new();
+            for (pixel, _) in edges.iter().enumerate().filter(|(_, edge)| **edge) {
+                refined.push(pixel);
+                index[pixel] = refined.len() as u32;
+            }
+
+            let mut samples = vec![0u16; refined.len() * count];
+            samples
+                .par_chunks_mut(count)
+                .zip(refined.par_iter())
+                .for_each(|(out, &pixel)| {
+                    let (x, y) = ((pixel % width) as f64, (pixel / width) as f64);
+                    for (cell, sample) in out.iter_mut().enumerate() {
+                        *sample = renderer::sample_point(
+                            params,
+                            x + grid_offset(cell % grid, grid),
+                            y + grid_offset(cell / grid, grid),
+                        );
+                    }
+                });
+
+            Some(Subsamples {
+                count,
+                index,
+                samples,
+            })
+        }
+    }
+}
+
+/// Returns `true` when two neighbouring iteration counts differ enough to show aliasing:
+/// one is in the set and the other is not, or they are more than 1/8 apart.
+#[inline(always)]
+const fn differs_strongly(a: u16, b: u16, max_iterations: u16) -> bool {
+    if (a >= max_iterations) != (b >= max_iterations) {
+        return true;
+    }
+    let (low, high) = if a < b { (a, b) } else { (b, a) };
+    let threshold = if high / 8 > 1 { high / 8 } else { 1 };
+    high - low > threshold
+}
+
+/// Marks every pixel that differs strongly from one of its 8 neighbours.
+#[must_use]
+pub fn find_edges(buffer: &IterationBuffer) -> Vec<bool> {
+    let (width, height) = (buffer.width, buffer.height);
+    let iterations = &buffer.iterations;
+    let max_iterations = buffer.max_iterations;
+
+    let mut edges = vec![false; width * height];
+    edges
+        .par_chunks_mut(width)
+        .enumerate()
+        .for_each(|(y, row)| {
+            for (x, edge) in row.iter_mut().enumerate() {
+                let center = iterations[y * width + x];
+                *edge = (y.saturating_sub(1)..=(y + 1).min(height - 1)).any(|ny| {
+                    (x.saturating_sub(1)..=(x + 1).min(width - 1))
+                        .any(|nx| differs_strongly(center, iterations[ny * width + nx], max_iterations))
+                });
+            }
+        });
+    edges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fractals::fractal_type::FractalType;
+    use crate::fractals::lyapunov::LyapunovSequence;
+    use crate::fractals::mariani_silver::RenderAlgorithm;
+    use crate::utils::point::Point;
+    use crate::utils::precision_mode::PrecisionMode;
+
+    fn params(anti_aliasing: AntiAliasing) -> RenderParams {
+        RenderParams {
+            fractal_type: FractalType::Mandelbrot,
+            center: Point::new(-0.5, 0.0),
+            zoom: 1.0,
+            julia_c: Point::new(0.0, 0.0),
+            lyapunov_sequence: LyapunovSequence::default(),
+            max_iterations: 100,
+            precision_mode: PrecisionMode::High,
+            anti_aliasing,
+            algorithm: RenderAlgorithm::BruteForce,
+            width: 40,
+            height: 30,
+        }
+    }
+
+    #[test]
+    fn test_off_has_no_subsamples() {
+        assert!(renderer::compute_iterations(&params(AntiAliasing::Off)).subsamples.is_none());
+    }
+
+    #[test]
+    fn test_grid_modes_refine_every_pixel() {
+        for mode in [AntiAliasing::Grid2x2, AntiAliasing::Grid3x3, AntiAliasing::Grid4x4, AntiAliasing::Jittered] {
+            let buffer = renderer::compute_iterations(&params(mode));
+            let subsamples = buffer.subsamples.unwrap();
+            assert_eq!(subsamples.count, mode.grid_size() * mode.grid_size());
+            assert_eq!(subsamples.samples.len(), 40 * 30 * subsamples.count);
+            assert!(subsamples.index.iter().all(|&i| i != 0));
+        }
+    }
+
+    #[test]
+    fn test_grid_samples_match_scalar_evaluation() {
+        let params = params(AntiAliasing::Grid2x2);
+        let subsamples = renderer::compute_iterations(&params).subsamples.unwrap();
+        let pixel = 15 * 40 + 7;
+        for cell in 0..4 {
+            let expected = renderer::sample_point(
+                &params,
+                7.0 + grid_offset(cell % 2, 2),
+                15.0 + grid_offset(cell / 2, 2),
+            );
+            // SIMD and scalar kernels round differently: allow one iteration of slack
+            assert!(subsamples.samples[pixel * 4 + cell].abs_diff(expected) <= 1);
+        }
+    }
+
+    #[test]
+    fn test_grid_samples_match_offset_passes() {
+        let params = params(AntiAliasing::Grid3x3);
+        let subsamples = renderer::compute_iterations(&params).subsamples.unwrap();
+        for cell in 0..9 {
+            let pass = renderer::compute_samples(&params, grid_offset(cell % 3, 3), grid_offset(cell / 3, 3));
+            for (pixel, &expected) in pass.iter().enumerate() {
+                assert_eq!(subsamples.samples[pixel * 9 + cell], expected, "cell {cell}, pixel {pixel}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_adaptive_refines_only_edges() {
+        let params = params(AntiAliasing::Adaptive);
+        let buffer = renderer::compute_iterations(&params);
+        let subsamples = buffer.subsamples.as_ref().unwrap();
+        let edges = find_edges(&buffer);
+
+        let refined = subsamples.index.iter().filter(|&&i| i != 0).count();
+        assert!(refined > 0 && refined < 40 * 30);
+        for (edge, &index) in edges.iter().zip(&subsamples.index) {
+            assert_eq!(*edge, index != 0);
+        }
+        assert_eq!(subsamples.samples.len(), refined * 16);
+    }
+
+    #[test]
+    fn test_differs_strongly() {
+        assert!(differs_strongly(100, 3, 100));
+        assert!(!differs_strongly(10, 11, 100));
+        assert!(differs_strongly(10, 20, 100));
+        assert!(!differs_strongly(100, 100, 100));
+    }
+
+    #[test]
+    fn test_hash01_range_and_determinism() {
+        for i in 0..100 {
+            let value = hash01(i, i * 7, i * 13);
+            assert!((0.0..1.0).contains(&value));
+            assert_eq!(value, hash01(i, i * 7, i * 13));
+        }
+    }
+}
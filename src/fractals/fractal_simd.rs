@@ -66,7 +66,7 @@ pub fn mandelbrot_simd_f32(cx: &[f32; 4], cy: &[f32; 4], max_iteration: u16) ->
 
         // move_mask: 1 SIMD instruction (MOVMSKPS) — bit i = sign bit of lane i
         // cmp_gt sets all bits for true lanes, so sign bit is 1 when escaped
-        let escaped_bits = magnitude_sq.simd_gt(four).to_bitmask() as u32 & 0xF;
+        let escaped_bits = magnitude_sq.simd_gt(four).to_bitmask() & 0xF;
         let newly_escaped = escaped_bits & active_bits;
 
         if newly_escaped != 0 {
@@ -136,7 +136,7 @@ pub fn mandelbrot_simd_f64(cx: &[f64; 2], cy: &[f64; 2], max_iteration: u16) ->
         let zi2 = zi * zi;
         let magnitude_sq = zr2 + zi2;
 
-        let escaped_bits = magnitude_sq.simd_gt(four).to_bitmask() as u32 & 0x3;
+        let escaped_bits = magnitude_sq.simd_gt(four).to_bitmask() & 0x3;
         let newly_escaped = escaped_bits & active_bits;
 
         if newly_escaped != 0 {
@@ -188,7 +188,7 @@ pub fn julia_simd_f32(
         let y2 = y * y;
         let magnitude_sq = x2 + y2;
 
-        let escaped_bits = magnitude_sq.simd_gt(four).to_bitmask() as u32 & 0xF;
+        let escaped_bits = magnitude_sq.simd_gt(four).to_bitmask() & 0xF;
         let newly_escaped = escaped_bits & active_bits;
 
         if newly_escaped != 0 {
@@ -240,7 +240,7 @@ pub fn julia_simd_f64(
         let y2 = y * y;
         let magnitude_sq = x2 + y2;
 
-        let escaped_bits = magnitude_sq.simd_gt(four).to_bitmask() as u32 & 0x3;
+        let escaped_bits = magnitude_sq.simd_gt(four).to_bitmask() & 0x3;
         let newly_escaped = escaped_bits & active_bits;
 
         if newly_escaped != 0 {
@@ -285,7 +285,7 @@ pub fn burning_ship_simd_f32(cx: &[f32; 4], cy: &[f32; 4], max_iteration: u16) -
         let y2 = y * y;
         let magnitude_sq = x2 + y2;
 
-        let escaped_bits = magnitude_sq.simd_gt(four).to_bitmask() as u32 & 0xF;
+        let escaped_bits = magnitude_sq.simd_gt(four).to_bitmask() & 0xF;
         let newly_escaped = escaped_bits & active_bits;
 
         if newly_escaped != 0 {
@@ -331,7 +331,7 @@ pub fn burning_ship_simd_f64(cx: &[f64; 2], cy: &[f64; 2], max_iteration: u16) -
         let y2 = y * y;
         let magnitude_sq = x2 + y2;
 
-        let escaped_bits = magnitude_sq.simd_gt(four).to_bitmask() as u32 & 0x3;
+        let escaped_bits = magnitude_sq.simd_gt(four).to_bitmask() & 0x3;
         let newly_escaped = escaped_bits & active_bits;
 
         if newly_escaped != 0 {
@@ -376,7 +376,7 @@ pub fn tricorn_simd_f32(cx: &[f32; 4], cy: &[f32; 4], max_iteration: u16) -> [u1
         let y2 = y * y;
         let magnitude_sq = x2 + y2;
 
-        let escaped_bits = magnitude_sq.simd_gt(four).to_bitmask() as u32 & 0xF;
+        let escaped_bits = magnitude_sq.simd_gt(four).to_bitmask() & 0xF;
         let newly_escaped = escaped_bits & active_bits;
 
         if newly_escaped != 0 {
@@ -422,7 +422,7 @@ pub fn tricorn_simd_f64(cx: &[f64; 2], cy: &[f64; 2], max_iteration: u16) -> [u1
         let y2 = y * y;
         let magnitude_sq = x2 + y2;
 
-        let escaped_bits = magnitude_sq.simd_gt(four).to_bitmask() as u32 & 0x3;
+        let escaped_bits = magnitude_sq.simd_gt(four).to_bitmask() & 0x3;
         let newly_escaped = escaped_bits & active_bits;
 
         if newly_escaped != 0 {
@@ -1,18 +1,91 @@
 use crate::utils::precision_mode::PrecisionMode;
 use crate::utils::point::Point;
 use crate::fractals::fractal_kernels;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
 
 /// Represents the type of fractal to be generated.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
 #[repr(u8)]
 pub enum FractalType {
+    #[default]
     Mandelbrot,
     Julia,
     BurningShip,
     Tricorn,
+    Lyapunov,
 }
 
 impl FractalType {
+    /// Returns the name of the fractal type
+    #[inline]
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Mandelbrot => "Mandelbrot Set",
+            Self::Julia => "Julia Set",
+            Self::BurningShip => "Burning Ship",
+            Self::Tricorn => "Tricorn",
+            Self::Lyapunov => "Lyapunov",
+        }
+    }
+
+    /// Returns all available fractal types.
+    #[inline]
+    #[must_use]
+    pub const fn all() -> [Self; 5] {
+        [Self::Mandelbrot, Self::Julia, Self::BurningShip, Self::Tricorn, Self::Lyapunov]
+    }
+
+    /// Returns the escape-time fractal this is, or `None` for a Lyapunov fractal, whose points
+    /// are the parameters of a logistic map rather than starting points of an orbit in the plane.
+    #[inline]
+    #[must_use]
+    pub const fn escape_time(&self) -> Option<EscapeTime> {
+        match self {
+            Self::Mandelbrot => Some(EscapeTime::Mandelbrot),
+            Self::Julia => Some(EscapeTime::Julia),
+            Self::BurningShip => Some(EscapeTime::BurningShip),
+            Self::Tricorn => Some(EscapeTime::Tricorn),
+            Self::Lyapunov => None,
+        }
+    }
+
+    /// Parses a fractal type from its name, ignoring case, spaces and a trailing "set"
+    /// (`"mandelbrot"`, `"Burning Ship"`, `"burning-ship"` all match).
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        let wanted = normalize_name(name);
+        let wanted = wanted.strip_suffix("set").unwrap_or(&wanted);
+        Self::all().into_iter().find(|fractal| {
+            let own = normalize_name(fractal.name());
+            own.strip_suffix("set").unwrap_or(&own) == wanted
+        })
+    }
+
+    /// Returns the default center point for the fractal type
+    #[inline]
+    pub const fn default_center(&self) -> Point {
+        match self {
+            Self::Mandelbrot => Point::new(-0.5, 0.0),
+            Self::Julia | Self::Tricorn => Point::new(0.0, 0.0),
+            Self::BurningShip => Point::new(-0.5, -0.5),
+            Self::Lyapunov => Point::new(2.0, 2.0),
+        }
+    }
+
+}
+
+/// The fractals iterating z ↦ z² + c, or a variant of it, until the orbit escapes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum EscapeTime {
+    Mandelbrot,
+    Julia,
+    BurningShip,
+    Tricorn,
+}
+
+impl EscapeTime {
     /// Returns the number of iterations with specified precision mode.
     /// Now using optimized direct kernel implementations for maximum performance.
     #[inline]
@@ -80,27 +153,59 @@ impl FractalType {
         }
     }
 
-    /// Returns the name of the fractal type
+    /// Returns every escape-time fractal.
     #[inline]
     #[must_use]
-    pub const fn name(&self) -> &'static str {
-        match self {
-            Self::Mandelbrot => "Mandelbrot Set",
-            Self::Julia => "Julia Set",
-            Self::BurningShip => "Burning Ship",
-            Self::Tricorn => "Tricorn",
-        }
+    pub const fn all() -> [Self; 4] {
+        [Self::Mandelbrot, Self::Julia, Self::BurningShip, Self::Tricorn]
     }
 
-    /// Returns the default center point for the fractal type
+    /// Returns the starting point z₀ and the parameter c of the orbit of a point of the plane:
+    /// Julia sets start at the point itself, the others start at 0 with c set to the point.
     #[inline]
-    pub const fn default_center(&self) -> Point {
+    pub const fn orbit_start(&self, point: Point, julia_c: Point) -> (Point, Point) {
         match self {
-            Self::Mandelbrot => Point::new(-0.5, 0.0),
-            Self::Julia | Self::Tricorn => Point::new(0.0, 0.0),
-            Self::BurningShip => Point::new(-0.5, -0.5),
+            Self::Julia => (point, julia_c),
+            Self::Mandelbrot | Self::BurningShip | Self::Tricorn => (Point::new(0.0, 0.0), point),
         }
     }
+
+    /// Applies the fractal's map once: z ↦ z² + c, with the variant's twist.
+    #[inline]
+    pub fn step(&self, z: Point, c: Point) -> Point {
+        let (x2, y2) = (z.x * z.x, z.y * z.y);
+        // Same operation order as the f64 kernels, so the orbit matches the rendered counts
+        let x = x2.mul_add(1.0, y2.mul_add(-1.0, c.x));
+        let y = match self {
+            Self::Mandelbrot | Self::Julia => (2.0 * z.x).mul_add(z.y, c.y),
+            Self::BurningShip => (2.0 * z.x.abs()).mul_add(z.y.abs(), c.y),
+            Self::Tricorn => (-2.0 * z.x).mul_add(z.y, c.y),
+        };
+        Point::new(x, y)
+    }
+}
+
+/// Fractal types are stored by display name, which stays readable in saved files.
+impl Serialize for FractalType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for FractalType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Self::from_name(&name).ok_or_else(|| de::Error::custom(format!("unknown fractal type `{name}`")))
+    }
+}
+
+/// Lowercases a display name and drops everything but letters and digits, for lenient lookups.
+#[must_use]
+pub fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
 }
 
 #[cfg(test)]
@@ -109,7 +214,7 @@ mod tests {
 
     #[test]
     fn test_mandelbrot_iterations() {
-        let iterations = FractalType::Mandelbrot.iterations(
+        let iterations = EscapeTime::Mandelbrot.iterations(
             0.0,
             0.0,
             1000,
@@ -121,7 +226,7 @@ mod tests {
 
     #[test]
     fn test_julia_iterations() {
-        let iterations = FractalType::Julia.iterations(
+        let iterations = EscapeTime::Julia.iterations(
             0.0,
             0.0,
             1000,
@@ -133,7 +238,7 @@ mod tests {
 
     #[test]
     fn test_burning_ship_iterations() {
-        let iterations = FractalType::BurningShip.iterations(
+        let iterations = EscapeTime::BurningShip.iterations(
             0.0,
             0.0,
             1000,
@@ -145,7 +250,7 @@ mod tests {
 
     #[test]
     fn test_tricorn_iterations() {
-        let iterations = FractalType::Tricorn.iterations(
+        let iterations = EscapeTime::Tricorn.iterations(
             0.0,
             0.0,
             1000,
@@ -155,12 +260,30 @@ mod tests {
         assert!(iterations > 0);
     }
 
+    #[test]
+    fn test_escape_time() {
+        let escape_times: Vec<EscapeTime> = FractalType::all().iter().filter_map(FractalType::escape_time).collect();
+        assert_eq!(escape_times, EscapeTime::all());
+        assert_eq!(FractalType::Lyapunov.escape_time(), None);
+    }
+
     #[test]
     fn test_fractal_type_name() {
         assert_eq!(FractalType::Mandelbrot.name(), "Mandelbrot Set");
         assert_eq!(FractalType::Julia.name(), "Julia Set");
         assert_eq!(FractalType::BurningShip.name(), "Burning Ship");
         assert_eq!(FractalType::Tricorn.name(), "Tricorn");
+        assert_eq!(FractalType::Lyapunov.name(), "Lyapunov");
+    }
+
+    #[test]
+    fn test_fractal_type_from_name() {
+        for fractal in FractalType::all() {
+            assert_eq!(FractalType::from_name(fractal.name()), Some(fractal));
+        }
+        assert_eq!(FractalType::from_name("mandelbrot"), Some(FractalType::Mandelbrot));
+        assert_eq!(FractalType::from_name("burning-ship"), Some(FractalType::BurningShip));
+        assert_eq!(FractalType::from_name("newton"), None);
     }
 
     #[test]
@@ -175,5 +298,6 @@ mod tests {
             Point::new(-0.5, -0.5)
         );
         assert_eq!(FractalType::Tricorn.default_center(), Point::new(0.0, 0.0));
+        assert_eq!(FractalType::Lyapunov.default_center(), Point::new(2.0, 2.0));
     }
 }
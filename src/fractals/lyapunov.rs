@@ -0,0 +1,265 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+use std::fmt;
+use std::str::FromStr;
+use wide::f64x2;
+
+/// Longest A/B sequence, one bit per letter.
+pub const MAX_SEQUENCE_LENGTH: usize = 64;
+
+/// Steps multiplied together before their logarithm is taken, which keeps the product finite.
+const LOG_INTERVAL: u16 = 16;
+
+/// Smallest derivative factor kept, so a product of `LOG_INTERVAL` of them cannot underflow to 0.
+const MIN_FACTOR: f64 = 1e-18;
+
+/// The periodic sequence of A and B that picks, at each step of the logistic map, which of the
+/// two parameters a (the plane's x) and b (the plane's y) is the growth rate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LyapunovSequence {
+    bits: u64, // Bit i is set when step i uses b
+    len: u8,
+}
+
+impl Default for LyapunovSequence {
+    /// The classic alternating sequence "AB".
+    #[inline]
+    fn default() -> Self {
+        Self { bits: 0b10, len: 2 }
+    }
+}
+
+impl LyapunovSequence {
+    /// Returns the number of letters in the sequence.
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    /// Returns `true` if the sequence has no letters, which parsing never produces.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if step `n` of the map uses b.
+    #[inline(always)]
+    #[must_use]
+    pub const fn uses_b(&self, n: u16) -> bool {
+        (self.bits >> (n as u64 % self.len as u64)) & 1 == 1
+    }
+}
+
+impl FromStr for LyapunovSequence {
+    type Err = String;
+
+    /// Parses a sequence of the letters A and B, in either case, such as `"AABAB"`.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let letters = text.trim();
+        if letters.is_empty() || letters.len() > MAX_SEQUENCE_LENGTH {
+            return Err(format!("a sequence has 1 to {MAX_SEQUENCE_LENGTH} letters, got `{letters}`"));
+        }
+        let mut bits = 0;
+        for (i, letter) in letters.chars().enumerate() {
+            match letter {
+                'A' | 'a' => {}
+                'B' | 'b' => bits |= 1 << i,
+                other => return Err(format!("a sequence only has A and B, got `{other}`")),
+            }
+        }
+        Ok(Self {
+            bits,
+            len: letters.len() as u8,
+        })
+    }
+}
+
+impl fmt::Display for LyapunovSequence {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (0..self.len).try_for_each(|i| formatter.write_str(if self.uses_b(u16::from(i)) { "B" } else { "A" }))
+    }
+}
+
+/// Sequences are stored as their letters, e.g. `"AABAB"`.
+impl Serialize for LyapunovSequence {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for LyapunovSequence {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Returns the number of steps that only settle the orbit, before the exponent is averaged.
+#[inline(always)]
+const fn warmup(max_iteration: u16) -> u16 {
+    max_iteration / 4
+}
+
+/// Computes the Lyapunov exponent λ of the logistic map xₙ₊₁ = rₙxₙ(1 − xₙ) from x₀ = ½, where rₙ
+/// is `a` or `b` as the sequence dictates: the mean of ln |rₙ(1 − 2xₙ)| over the steps after the
+/// warm-up (the first quarter of `max_iteration`). λ < 0 means the orbit settles into a stable
+/// cycle; λ > 0 means it is chaotic. Orbits that leave [0, 1] diverge, giving +∞ or NaN.
+#[must_use]
+pub fn exponent(a: f64, b: f64, sequence: LyapunovSequence, max_iteration: u16) -> f64 {
+    let mut x = 0.5;
+    for n in 0..warmup(max_iteration) {
+        let r = if sequence.uses_b(n) { b } else { a };
+        x = r * x * (1.0 - x);
+    }
+
+    let mut sum = 0.0;
+    let mut product = 1.0;
+    for n in warmup(max_iteration)..max_iteration {
+        let r = if sequence.uses_b(n) { b } else { a };
+        product *= (r * 2.0f64.mul_add(-x, 1.0)).abs().max(MIN_FACTOR);
+        x = r * x * (1.0 - x);
+        if (n - warmup(max_iteration)) % LOG_INTERVAL == LOG_INTERVAL - 1 {
+            sum += product.ln();
+            product = 1.0;
+        }
+    }
+    (sum + product.ln()) / f64::from(max_iteration - warmup(max_iteration)).max(1.0)
+}
+
+/// Computes the Lyapunov exponents of 2 points simultaneously, as [`exponent`] does.
+#[inline(always)]
+#[must_use]
+pub fn exponent_simd(a: &[f64; 2], b: &[f64; 2], sequence: LyapunovSequence, max_iteration: u16) -> [f64; 2] {
+    let (a, b) = (f64x2::from(*a), f64x2::from(*b));
+    let one = f64x2::splat(1.0);
+    let minus_two = f64x2::splat(-2.0);
+    let min_factor = f64x2::splat(MIN_FACTOR);
+    let mut x = f64x2::splat(0.5);
+    for n in 0..warmup(max_iteration) {
+        let r = if sequence.uses_b(n) { b } else { a };
+        x = r * x * (one - x);
+    }
+
+    let mut sum = f64x2::ZERO;
+    let mut product = one;
+    for n in warmup(max_iteration)..max_iteration {
+        let r = if sequence.uses_b(n) { b } else { a };
+        product *= (r * minus_two.mul_add(x, one)).abs().max(min_factor);
+        x = r * x * (one - x);
+        if (n - warmup(max_iteration)) % LOG_INTERVAL == LOG_INTERVAL - 1 {
+            sum += product.ln();
+            product = one;
+        }
+    }
+
+    // The vector logarithm is only accurate for finite input: diverged lanes are marked chaotic
+    let sum = (sum + product.ln()).to_array();
+    let product = product.to_array();
+    let steps = f64::from(max_iteration - warmup(max_iteration)).max(1.0);
+    [0, 1].map(|i| if product[i].is_finite() { sum[i] / steps } else { f64::INFINITY })
+}
+
+/// Maps an exponent to the iteration buffer, decreasing with λ from the edge of chaos (λ = 0) at
+/// the middle of `0..max_iteration`: stable points (λ < 0) rise by 1 − e^λ to superstable cycles at
+/// `max_iteration - 1`, chaotic points (λ > 0) fall by e^−λ towards 0. Divergent points get
+/// `max_iteration`, drawn like the inside of escape-time sets.
+#[inline(always)]
+#[must_use]
+pub fn exponent_to_count(lambda: f64, max_iteration: u16) -> u16 {
+    let top = f64::from(max_iteration.saturating_sub(1));
+    let edge = top / 2.0;
+    if lambda < 0.0 {
+        (edge - lambda.exp_m1() * (top - edge)).round().min(top) as u16
+    } else if lambda.is_finite() {
+        (edge * (-lambda).exp()).round() as u16
+    } else {
+        max_iteration
+    }
+}
+
+/// Returns the buffer value of the point `(a, b)`.
+#[inline]
+#[must_use]
+pub fn iterations(a: f64, b: f64, sequence: LyapunovSequence, max_iteration: u16) -> u16 {
+    exponent_to_count(exponent(a, b, sequence, max_iteration), max_iteration)
+}
+
+/// Fills `out[i]` with the buffer value of the point `point(i)` = (a, b), 2 points at a time. An odd
+/// last point is paired with itself, so every point goes through the same vector kernel.
+pub fn fill_points(
+    out: &mut [u16],
+    point: impl Fn(usize) -> (f64, f64),
+    sequence: LyapunovSequence,
+    max_iteration: u16,
+) {
+    let last = out.len().saturating_sub(1);
+    for (pair, i) in out.chunks_mut(2).zip((0..).step_by(2)) {
+        let (first, second) = (point(i), point((i + 1).min(last)));
+        let lambdas = exponent_simd(&[first.0, second.0], &[first.1, second.1], sequence, max_iteration);
+        for (value, lambda) in pair.iter_mut().zip(lambdas) {
+            *value = exponent_to_count(lambda, max_iteration);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequence_round_trip() {
+        let sequence: LyapunovSequence = "aabAB".parse().unwrap();
+        assert_eq!(sequence.to_string(), "AABAB");
+        assert_eq!(sequence.len(), 5);
+        assert!(!sequence.uses_b(0) && sequence.uses_b(2) && sequence.uses_b(7));
+        assert_eq!(LyapunovSequence::default().to_string(), "AB");
+        assert_eq!(serde_json::to_string(&sequence).unwrap(), "\"AABAB\"");
+        assert_eq!(serde_json::from_str::<LyapunovSequence>("\"BBA\"").unwrap().to_string(), "BBA");
+        assert!("".parse::<LyapunovSequence>().is_err());
+        assert!("ABC".parse::<LyapunovSequence>().is_err());
+        assert!("A".repeat(65).parse::<LyapunovSequence>().is_err());
+    }
+
+    #[test]
+    fn test_exponent_sign() {
+        let sequence = LyapunovSequence::default();
+        // r = 2.5 settles on a fixed point, r = 3.9 is chaotic
+        assert!(exponent(2.5, 2.5, sequence, 1000) < -0.5);
+        assert!(exponent(3.9, 3.9, sequence, 1000) > 0.2);
+        assert!(iterations(3.9, 3.9, sequence, 1000) < 500);
+        assert_eq!(iterations(5.0, 5.0, sequence, 1000), 1000);
+        assert!((500..1000).contains(&iterations(2.5, 2.5, sequence, 1000)));
+        assert_eq!(exponent_to_count(f64::NEG_INFINITY, 300), 299);
+        assert_eq!(exponent_to_count(0.0, 300), 150);
+        assert_eq!(exponent_to_count(f64::INFINITY, 300), 300);
+        assert_eq!(exponent_to_count(f64::NAN, 300), 300);
+        // Chaotic points keep a gradient: stronger chaos, lower count
+        assert!(exponent_to_count(0.1, 300) > exponent_to_count(0.5, 300));
+        assert!(exponent_to_count(0.5, 300) > exponent_to_count(2.0, 300));
+    }
+
+    #[test]
+    fn test_simd_matches_scalar() {
+        let sequence: LyapunovSequence = "AABAB".parse().unwrap();
+        for (a, b) in [(3.2, 3.7), (2.1, 3.9), (3.83, 3.83), (4.5, 3.0), (-0.5, 2.0)] {
+            let scalar = [exponent(a, b, sequence, 600), exponent(b, a, sequence, 600)];
+            let simd = exponent_simd(&[a, b], &[b, a], sequence, 600);
+            for (s, v) in scalar.iter().zip(simd) {
+                assert!(
+                    (s - v).abs() < 1e-9 || (s.is_nan() || *s == f64::INFINITY) && v == f64::INFINITY,
+                    "({a}, {b}): {s} vs {v}"
+                );
+            }
+        }
+
+        // An odd last point gets the value it has inside a pair
+        let point = |x: usize| ((x as f64).mul_add(0.1, 3.0), 3.4);
+        let mut row = [0u16; 7];
+        fill_points(&mut row, point, sequence, 300);
+        let mut single = [0u16; 1];
+        for (x, &value) in row.iter().enumerate() {
+            fill_points(&mut single, |_| point(x), sequence, 300);
+            assert_eq!(value, single[0], "pixel {x}");
+        }
+    }
+}
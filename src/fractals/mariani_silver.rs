@@ -0,0 +1,193 @@
+use crate::fractals::renderer::{self, RenderParams};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Side of the blocks the image is cut into, each subdivided on its own thread.
+const BLOCK_SIZE: usize = 64;
+
+/// Rectangles this narrow are computed pixel by pixel instead of subdivided further.
+const MIN_SIDE: usize = 4;
+
+/// How the primary iteration counts of a render are computed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum RenderAlgorithm {
+    #[default]
+    BruteForce,    // Every pixel is iterated, with SIMD
+    MarianiSilver, // Rectangles with a uniform border are filled without iterating their inside
+    Perturbation,  // Offsets from a reference orbit, skipping the iterations a series approximation predicts
+}
+
+impl RenderAlgorithm {
+    /// Returns the name of the algorithm.
+    #[inline]
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::BruteForce => "Brute Force",
+            Self::MarianiSilver => "Mariani–Silver",
+            Self::Perturbation => "Perturbation",
+        }
+    }
+
+    /// Returns all available algorithms.
+    #[inline]
+    #[must_use]
+    pub const fn all() -> [Self; 3] {
+        [Self::BruteForce, Self::MarianiSilver, Self::Perturbation]
+    }
+}
+
+/// One block of the image being subdivided, with the pixels computed or filled so far.
+struct Block<'a> {
+    params: &'a RenderParams,
+    x: usize,
+    y: usize,
+    width: usize,
+    values: Vec<u16>,
+    known: Vec<bool>,
+}
+
+impl Block<'_> {
+    /// Computes the pixels among `pixels` not known yet, together, with the SIMD kernels and
+    /// precision of a brute-force render, so each gets exactly its brute-force value.
+    fn compute(&mut self, pixels: impl Iterator<Item = (usize, usize)>) {
+        let mut missing = Vec::new();
+        for (x, y) in pixels {
+            let i = y * self.width + x;
+            if !self.known[i] {
+                self.known[i] = true;
+                missing.push((x, y));
+            }
+        }
+        let mut values = vec![0u16; missing.len()];
+        let (origin_x, origin_y) = (self.x, self.y);
+        renderer::fill_points(self.params, &mut values, |i| {
+            ((origin_x + missing[i].0) as f64, (origin_y + missing[i].1) as f64)
+        });
+        for ((x, y), value) in missing.into_iter().zip(values) {
+            self.values[y * self.width + x] = value;
+        }
+    }
+
+    /// Computes the border of the rectangle `x0..=x1` × `y0..=y1`; fills its inside if the whole
+    /// border has one iteration count, and splits it in two along its longer side otherwise.
+    fn subdivide(&mut self, x0: usize, y0: usize, x1: usize, y1: usize) {
+        let mut border = (x0..=x1)
+            .flat_map(move |x| [(x, y0), (x, y1)])
+            .chain((y0..=y1).flat_map(move |y| [(x0, y), (x1, y)]));
+        self.compute(border.clone());
+        let first = self.values[y0 * self.width + x0];
+        let uniform = border.all(|(x, y)| self.values[y * self.width + x] == first);
+        if x1 - x0 < 2 || y1 - y0 < 2 {
+            return;
+        }
+
+        if uniform {
+            for y in y0 + 1..y1 {
+                let row = y * self.width;
+                self.values[row + x0 + 1..row + x1].fill(first);
+                self.known[row + x0 + 1..row + x1].fill(true);
+            }
+        } else if x1 - x0 <= MIN_SIDE || y1 - y0 <= MIN_SIDE {
+            self.compute((y0 + 1..y1).flat_map(move |y| (x0 + 1..x1).map(move |x| (x, y))));
+        } else if x1 - x0 >= y1 - y0 {
+            let middle = (x0 + x1) / 2;
+            self.subdivide(x0, y0, middle, y1);
+            self.subdivide(middle, y0, x1, y1);
+        } else {
+            let middle = (y0 + y1) / 2;
+            self.subdivide(x0, y0, x1, middle);
+            self.subdivide(x0, middle, x1, y1);
+        }
+    }
+}
+
+/// Computes one sample per pixel by Mariani–Silver subdivision: the image is cut into blocks,
+/// and any rectangle whose border has a single iteration count is filled with it.
+///
+/// Filling is exact inside the (connected) Mandelbrot set and its escape bands in practice, but
+/// can miss details thinner than a pixel that cross a rectangle without touching its border.
+#[must_use]
+pub fn compute_samples(params: &RenderParams) -> Vec<u16> {
+    let (width, height) = (params.width as usize, params.height as usize);
+    let mut iterations = vec![0u16; width * height];
+    if width == 0 || height == 0 {
+        return iterations;
+    }
+
+    let origins: Vec<(usize, usize)> = (0..height)
+        .step_by(BLOCK_SIZE)
+        .flat_map(|y| (0..width).step_by(BLOCK_SIZE).map(move |x| (x, y)))
+        .collect();
+    let blocks: Vec<Block> = origins
+        .into_par_iter()
+        .map(|(x, y)| {
+            let (block_width, block_height) = (BLOCK_SIZE.min(width - x), BLOCK_SIZE.min(height - y));
+            let mut block = Block {
+                params,
+                x,
+                y,
+                width: block_width,
+                values: vec![0; block_width * block_height],
+                known: vec![false; block_width * block_height],
+            };
+            block.subdivide(0, 0, block_width - 1, block_height - 1);
+            block
+        })
+        .collect();
+
+    for block in blocks {
+        for (row, values) in block.values.chunks_exact(block.width).enumerate() {
+            let start = (block.y + row) * width + block.x;
+            iterations[start..start + block.width].copy_from_slice(values);
+        }
+    }
+    iterations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fractals::anti_aliasing::AntiAliasing;
+    use crate::fractals::fractal_type::FractalType;
+    use crate::utils::point::Point;
+    use crate::utils::precision_mode::PrecisionMode;
+
+    #[test]
+    fn test_matches_brute_force() {
+        let views = [(Point::new(-0.5, 0.0), 1.0), (Point::new(-0.745, 0.113), 60.0), (Point::new(0.28, 0.01), 40.0)];
+        for precision_mode in [PrecisionMode::Fast, PrecisionMode::High] {
+            for (center, zoom) in views {
+                let params = RenderParams {
+                    fractal_type: FractalType::Mandelbrot,
+                    center,
+                    zoom,
+                    max_iterations: 300,
+                    precision_mode,
+                    anti_aliasing: AntiAliasing::Off,
+                    width: 203,
+                    height: 141,
+                    ..RenderParams::default()
+                };
+                let subdivided = compute_samples(&params);
+                let expected = renderer::compute_samples(&params, 0.0, 0.0);
+                let differing = subdivided.iter().zip(&expected).filter(|(a, b)| a != b).count();
+                assert_eq!(differing, 0, "{differing} pixels differ at {center:?} ×{zoom} in {precision_mode:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_small_and_empty_images() {
+        for (width, height) in [(1, 1), (2, 7), (65, 3), (0, 5)] {
+            let params = RenderParams {
+                width,
+                height,
+                max_iterations: 50,
+                ..RenderParams::default()
+            };
+            assert_eq!(compute_samples(&params), renderer::compute_samples(&params, 0.0, 0.0));
+        }
+    }
+}
@@ -1,4 +1,11 @@
 pub mod fractal_float;
 pub mod fractal_type;
 pub mod fractal_kernels;
-pub mod fractal_simd;
\ No newline at end of file
+pub mod fractal_simd;
+pub mod anti_aliasing;
+pub mod renderer;
+pub mod render_cache;
+pub mod orbit;
+pub mod mariani_silver;
+pub mod perturbation;
+pub mod lyapunov;
\ No newline at end of file
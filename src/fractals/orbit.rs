@@ -0,0 +1,97 @@
+use crate::fractals::fractal_type::EscapeTime;
+use crate::utils::point::Point;
+
+/// Squared radius beyond which an orbit has escaped, as in the kernels.
+pub const ESCAPE_RADIUS_SQUARED: f64 = 4.0;
+
+/// The sequence z₀, z₁, … visited by one point of a fractal, in double precision.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Orbit {
+    pub c: Point,           // The parameter of the iteration
+    pub points: Vec<Point>, // z₀ … zₙ; the last point is outside the escape radius if the orbit escaped
+    pub iterations: u16,    // Iteration count the renderer assigns to the point
+    pub escaped: bool,
+}
+
+/// Returns whether `z` is outside the escape radius, tested exactly as the kernels do.
+#[inline]
+fn has_escaped(z: Point) -> bool {
+    z.x * z.x + z.y * z.y > ESCAPE_RADIUS_SQUARED
+}
+
+impl Orbit {
+    /// Follows the orbit of `point` for at most `max_iterations` steps.
+    #[must_use]
+    pub fn compute(fractal: EscapeTime, point: Point, max_iterations: u16, julia_c: Point) -> Self {
+        let (mut z, c) = fractal.orbit_start(point, julia_c);
+        let mut points = Vec::with_capacity(usize::from(max_iterations.min(4096)) + 1);
+        points.push(z);
+        let mut iterations = 0u16;
+        let mut escaped = false;
+
+        while iterations < max_iterations {
+            if has_escaped(z) {
+                escaped = true;
+                break;
+            }
+            z = fractal.step(z, c);
+            points.push(z);
+            iterations += 1;
+        }
+
+        Self {
+            c,
+            points,
+            iterations,
+            escaped,
+        }
+    }
+
+    /// Returns |zₙ|, the modulus of the last point.
+    #[must_use]
+    pub fn escape_modulus(&self) -> f64 {
+        self.points.last().map_or(0.0, |z| z.x.hypot(z.y))
+    }
+
+    /// Returns the continuous ("smooth") iteration count n + 1 − log₂(ln |zₙ|), or `None` if the
+    /// orbit did not escape.
+    #[must_use]
+    pub fn smooth_iterations(&self) -> Option<f64> {
+        let modulus = self.escape_modulus();
+        (self.escaped && modulus > 1.0).then(|| f64::from(self.iterations) + 1.0 - modulus.ln().log2())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::precision_mode::PrecisionMode;
+
+    #[test]
+    fn test_orbit_matches_kernels() {
+        let julia_c = Point::new(-0.8, 0.156);
+        for fractal in EscapeTime::all() {
+            for point in [Point::new(0.3, 0.5), Point::new(-1.2, 0.1), Point::new(-0.1, 0.65), Point::new(1.5, -1.5)] {
+                let orbit = Orbit::compute(fractal, point, 500, julia_c);
+                let expected = fractal.iterations(point.x, point.y, 500, &julia_c, PrecisionMode::High);
+                assert_eq!(orbit.iterations, expected, "{fractal:?} at {point:?}");
+                assert_eq!(orbit.points.len(), usize::from(orbit.iterations) + 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bounded_and_escaping_orbits() {
+        let inside = Orbit::compute(EscapeTime::Mandelbrot, Point::new(-0.1, 0.1), 200, Point::new(0.0, 0.0));
+        assert!(!inside.escaped);
+        assert_eq!(inside.iterations, 200);
+        assert_eq!(inside.smooth_iterations(), None);
+
+        let outside = Orbit::compute(EscapeTime::Mandelbrot, Point::new(1.0, 1.0), 200, Point::new(0.0, 0.0));
+        assert!(outside.escaped);
+        assert_eq!(outside.points[..2], [Point::new(0.0, 0.0), Point::new(1.0, 1.0)]);
+        assert!(outside.escape_modulus() > 2.0);
+        let smooth = outside.smooth_iterations().unwrap();
+        assert!(smooth > 0.0 && smooth < f64::from(outside.iterations) + 1.0);
+    }
+}
@@ -0,0 +1,215 @@
+use crate::fractals::fractal_type::EscapeTime;
+use crate::fractals::orbit::{ESCAPE_RADIUS_SQUARED, Orbit};
+use crate::fractals::renderer::RenderParams;
+use crate::utils::point::Point;
+use rayon::prelude::*;
+
+/// Largest estimated truncation error of the series, relative to its linear term, that is trusted.
+const SERIES_TOLERANCE: f64 = 1e-9;
+
+#[inline]
+fn add(a: Point, b: Point) -> Point {
+    Point::new(a.x + b.x, a.y + b.y)
+}
+
+#[inline]
+fn mul(a: Point, b: Point) -> Point {
+    Point::new(a.x.mul_add(b.x, -a.y * b.y), a.x.mul_add(b.y, a.y * b.x))
+}
+
+#[inline]
+fn scale(a: Point, factor: f64) -> Point {
+    Point::new(a.x * factor, a.y * factor)
+}
+
+#[inline]
+#[must_use]
+fn norm_squared(a: Point) -> f64 {
+    a.x.mul_add(a.x, a.y * a.y)
+}
+
+#[inline]
+#[must_use]
+fn modulus(a: Point) -> f64 {
+    a.x.hypot(a.y)
+}
+
+/// Truncated Taylor series δₙ ≈ A·δc + B·δc² + C·δc³ of the offset of a pixel's orbit from the
+/// reference orbit after `skipped` iterations, as a function of the pixel's offset δc from the center.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SeriesApproximation {
+    pub skipped: u16,
+    pub coefficients: [Point; 3], // A, B, C
+}
+
+impl SeriesApproximation {
+    /// Advances the coefficients along `reference` for as long as, for every δc within `radius`:
+    /// - the first omitted term, D·δc⁴, stays below `SERIES_TOLERANCE` of the linear term, and
+    /// - |Zₙ| plus a bound on |δₙ| stays within the escape radius, so no pixel escapes while skipped.
+    #[must_use]
+    pub fn new(reference: &[Point], radius: f64, max_iterations: u16) -> Self {
+        let one = Point::new(1.0, 0.0);
+        let zero = Point::new(0.0, 0.0);
+        let (mut a, mut b, mut c, mut d) = (zero, zero, zero, zero);
+        let mut series = Self {
+            skipped: 0,
+            coefficients: [zero; 3],
+        };
+
+        for (n, &z) in reference.iter().enumerate().take(usize::from(max_iterations) + 1) {
+            let error = modulus(d) * radius.powi(4);
+            let bound = modulus(a).mul_add(radius, modulus(b) * radius.powi(2)) + modulus(c) * radius.powi(3) + error;
+            if error > SERIES_TOLERANCE * modulus(a) * radius || modulus(z) + bound > 2.0 || !bound.is_finite() {
+                break;
+            }
+            series = Self {
+                skipped: n as u16,
+                coefficients: [a, b, c],
+            };
+
+            // δₙ₊₁ = 2Zₙδₙ + δₙ² + δc, collected by powers of δc
+            let two_z = scale(z, 2.0);
+            (a, b, c, d) = (
+                add(mul(two_z, a), one),
+                add(mul(two_z, b), mul(a, a)),
+                add(mul(two_z, c), scale(mul(a, b), 2.0)),
+                add(mul(two_z, d), add(scale(mul(a, c), 2.0), mul(b, b))),
+            );
+        }
+        series
+    }
+
+    /// Returns the approximate offset δₙ after the skipped iterations for a pixel at offset `dc`.
+    #[inline]
+    pub fn evaluate(&self, dc: Point) -> Point {
+        let [a, b, c] = self.coefficients;
+        // Horner's scheme: ((C·δc + B)·δc + A)·δc
+        mul(add(mul(add(mul(c, dc), b), dc), a), dc)
+    }
+}
+
+/// Iterates one pixel as the offset `delta` from the reference orbit, from reference index `start`.
+/// When the pixel's orbit comes closer to zero than its offset, or the reference runs out, the
+/// offset is rebased onto the start of the reference, which keeps it small.
+#[must_use]
+fn iterate_offset(reference: &[Point], dc: Point, mut delta: Point, start: u16, max_iterations: u16) -> u16 {
+    let mut m = usize::from(start);
+    let mut iterations = start;
+    while iterations < max_iterations {
+        let z = add(reference[m], delta);
+        if norm_squared(z) > ESCAPE_RADIUS_SQUARED {
+            break;
+        }
+        delta = add(mul(add(scale(reference[m], 2.0), delta), delta), dc);
+        m += 1;
+        iterations += 1;
+
+        let z = add(reference[m], delta);
+        if m + 1 == reference.len() || norm_squared(z) < norm_squared(delta) {
+            delta = z;
+            m = 0;
+        }
+    }
+    iterations
+}
+
+/// Computes one sample per pixel of a Mandelbrot view by perturbation: every pixel is iterated as
+/// a small offset from one reference orbit at the center, starting after the iterations the series
+/// approximation skips. Returns the samples and the number of iterations skipped.
+///
+/// Offsets are taken from the center rather than from absolute coordinates, so pixels stay
+/// distinct at zooms where the brute-force kernels run out of precision.
+#[must_use]
+pub fn compute_samples(params: &RenderParams) -> (Vec<u16>, u16) {
+    let (width, height) = (params.width as usize, params.height as usize);
+    let mut iterations = vec![0u16; width * height];
+    if width == 0 || height == 0 {
+        return (iterations, 0);
+    }
+
+    let max_iterations = params.max_iterations;
+    let reference = Orbit::compute(EscapeTime::Mandelbrot, params.center, max_iterations, params.julia_c).points;
+    let pixel = 4.0 / params.zoom / f64::from(params.height);
+    let (half_width, half_height) = (width as f64 / 2.0, height as f64 / 2.0);
+    let radius = half_width.hypot(half_height) * pixel;
+    let series = SeriesApproximation::new(&reference, radius, max_iterations);
+
+    iterations.par_chunks_mut(width).enumerate().for_each(|(y, row)| {
+        let dy = (y as f64 - half_height) * pixel;
+        for (x, value) in row.iter_mut().enumerate() {
+            let dc = Point::new((x as f64 - half_width) * pixel, dy);
+            *value = iterate_offset(&reference, dc, series.evaluate(dc), series.skipped, max_iterations);
+        }
+    });
+    (iterations, series.skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fractals::renderer;
+
+    fn params(center: Point, zoom: f64, max_iterations: u16) -> RenderParams {
+        RenderParams {
+            center,
+            zoom,
+            max_iterations,
+            width: 120,
+            height: 90,
+            ..RenderParams::default()
+        }
+    }
+
+    /// Fraction of pixels whose count differs from the brute-force renderer.
+    fn mismatch(params: &RenderParams, samples: &[u16]) -> f64 {
+        let expected = renderer::compute_samples(params, 0.0, 0.0);
+        samples.iter().zip(&expected).filter(|(a, b)| a != b).count() as f64 / samples.len() as f64
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        for (center, zoom) in [(Point::new(-0.5, 0.0), 1.0), (Point::new(-0.745, 0.113), 1e4)] {
+            let view = params(center, zoom, 500);
+            let (samples, _) = compute_samples(&view);
+            let mismatch = mismatch(&view, &samples);
+            assert!(mismatch < 0.01, "{mismatch} of the pixels differ at {center:?} ×{zoom}");
+        }
+    }
+
+    #[test]
+    fn test_skipping_keeps_counts() {
+        let view = params(Point::new(-0.743_643_887_037_151, 0.131_825_904_205_33), 1e9, 3000);
+        let (samples, skipped) = compute_samples(&view);
+        assert!(skipped > 50, "skipped only {skipped} iterations");
+
+        // Starting every pixel from the first iteration gives the same counts
+        let reference = Orbit::compute(EscapeTime::Mandelbrot, view.center, 3000, view.julia_c).points;
+        let pixel = 4.0 / view.zoom / f64::from(view.height);
+        let mut differing = 0;
+        for (i, &count) in samples.iter().enumerate() {
+            let (x, y) = ((i % 120) as f64 - 60.0, (i / 120) as f64 - 45.0);
+            let dc = Point::new(x * pixel, y * pixel);
+            assert!(count >= skipped);
+            differing += usize::from(iterate_offset(&reference, dc, dc, 1, 3000) != count);
+        }
+        assert!(differing * 100 < samples.len(), "{differing} pixels differ");
+    }
+
+    #[test]
+    fn test_series_matches_iterated_offsets() {
+        let center = Point::new(-0.743_643_887_037_151, 0.131_825_904_205_33);
+        let reference = Orbit::compute(EscapeTime::Mandelbrot, center, 3000, Point::new(0.0, 0.0)).points;
+        let radius = 1e-9;
+        let series = SeriesApproximation::new(&reference, radius, 3000);
+
+        // Offsets iterated exactly along the reference stay within the tolerance of the series
+        for dc in [Point::new(radius, 0.0), Point::new(-0.6 * radius, 0.7 * radius)] {
+            let mut delta = Point::new(0.0, 0.0);
+            for &z in &reference[..usize::from(series.skipped)] {
+                delta = add(mul(add(scale(z, 2.0), delta), delta), dc);
+            }
+            let error = modulus(add(series.evaluate(dc), scale(delta, -1.0)));
+            assert!(error <= 1e-6 * modulus(delta), "error {error:e} for δ {delta:?}");
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use crate::fractals::anti_aliasing::AntiAliasing;
+use crate::fractals::fractal_type::FractalType;
+use crate::fractals::lyapunov::LyapunovSequence;
+use crate::fractals::mariani_silver::RenderAlgorithm;
+use crate::fractals::renderer::{IterationBuffer, RenderParams};
+use crate::utils::lru::LruCache;
+use crate::utils::precision_mode::PrecisionMode;
+
+/// Memory given to cached iteration buffers unless told otherwise.
+pub const DEFAULT_CACHE_BYTES: usize = 256 * 1024 * 1024;
+
+/// The parameters of a render, with coordinates compared bit for bit.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct ViewKey {
+    fractal_type: FractalType,
+    center: (u64, u64),
+    zoom: u64,
+    julia_c: (u64, u64), // Zero for fractals that ignore the Julia constant
+    lyapunov_sequence: Option<LyapunovSequence>, // Only set for Lyapunov fractals
+    max_iterations: u16,
+    precision_mode: PrecisionMode,
+    anti_aliasing: AntiAliasing,
+    algorithm: RenderAlgorithm,
+    size: (u32, u32),
+}
+
+impl ViewKey {
+    fn new(params: &RenderParams) -> Self {
+        let julia_c = if params.fractal_type == FractalType::Julia {
+            (params.julia_c.x.to_bits(), params.julia_c.y.to_bits())
+        } else {
+            (0, 0)
+        };
+        Self {
+            fractal_type: params.fractal_type,
+            center: (params.center.x.to_bits(), params.center.y.to_bits()),
+            zoom: params.zoom.to_bits(),
+            julia_c,
+            lyapunov_sequence: (params.fractal_type == FractalType::Lyapunov).then_some(params.lyapunov_sequence),
+            max_iterations: params.max_iterations,
+            precision_mode: params.precision_mode,
+            anti_aliasing: params.anti_aliasing,
+            algorithm: params.algorithm,
+            size: (params.width, params.height),
+        }
+    }
+}
+
+/// Iteration buffers of recently rendered views, bounded by memory, so that going back to a view
+/// (undo, reset, a bookmark, the previous fractal) does not compute it again.
+pub struct RenderCache {
+    buffers: LruCache<ViewKey, IterationBuffer>,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl Default for RenderCache {
+    /// Creates an empty cache of `DEFAULT_CACHE_BYTES`.
+    #[inline]
+    fn default() -> Self {
+        Self::new(DEFAULT_CACHE_BYTES)
+    }
+}
+
+impl RenderCache {
+    /// Creates an empty cache holding at most `capacity` bytes of iteration buffers.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            buffers: LruCache::new(capacity),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Returns a copy of the buffer rendered for `params`, if it is cached.
+    pub fn get(&mut self, params: &RenderParams) -> Option<IterationBuffer> {
+        let buffer = self.buffers.get(&ViewKey::new(params)).cloned();
+        if buffer.is_some() {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+        buffer
+    }
+
+    /// Caches the buffer rendered for `params`.
+    pub fn insert(&mut self, params: &RenderParams, buffer: IterationBuffer) {
+        let size = buffer.memory_size();
+        self.buffers.insert(ViewKey::new(params), buffer, size);
+    }
+
+    /// Returns the number of cached views.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.buffers.len()
+    }
+
+    /// Returns `true` if no view is cached.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.buffers.is_empty()
+    }
+
+    /// Returns the memory used by the cached buffers, in bytes.
+    #[inline]
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        self.buffers.size()
+    }
+
+    /// Returns the memory the cache may use, in bytes.
+    #[inline]
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        self.buffers.capacity()
+    }
+
+    /// Changes the memory the cache may use, dropping the oldest views if needed.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.buffers.set_capacity(capacity);
+    }
+
+    /// Drops every cached view.
+    pub fn clear(&mut self) {
+        self.buffers.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fractals::renderer;
+    use crate::utils::point::Point;
+
+    fn params() -> RenderParams {
+        RenderParams {
+            width: 30,
+            height: 20,
+            max_iterations: 50,
+            ..RenderParams::default()
+        }
+    }
+
+    #[test]
+    fn test_cache_hits_same_view() {
+        let mut cache = RenderCache::default();
+        let buffer = renderer::compute_iterations(&params());
+        assert!(cache.get(&params()).is_none());
+        cache.insert(&params(), buffer.clone());
+        assert_eq!(cache.get(&params()), Some(buffer));
+
+        // The Julia constant only matters to Julia sets
+        let other_constant = RenderParams {
+            julia_c: Point::new(0.3, 0.5),
+            ..params()
+        };
+        assert!(cache.get(&other_constant).is_some());
+        let julia = RenderParams {
+            fractal_type: FractalType::Julia,
+            ..params()
+        };
+        assert!(cache.get(&julia).is_none());
+        // Likewise the A/B sequence only matters to Lyapunov fractals
+        let other_sequence = RenderParams {
+            lyapunov_sequence: "AABAB".parse().unwrap(),
+            ..params()
+        };
+        assert!(cache.get(&other_sequence).is_some());
+        let moved = RenderParams {
+            center: Point::new(-0.5, 1e-300),
+            ..params()
+        };
+        assert!(cache.get(&moved).is_none());
+        assert_eq!((cache.hits, cache.misses), (3, 3));
+    }
+
+    #[test]
+    fn test_cache_is_bounded_by_memory() {
+        let buffer = renderer::compute_iterations(&params());
+        let mut cache = RenderCache::new(buffer.memory_size() * 2);
+        for zoom in [1.0, 2.0, 3.0] {
+            cache.insert(&RenderParams { zoom, ..params() }, buffer.clone());
+        }
+        assert_eq!(cache.len(), 2);
+        assert!(cache.size() <= cache.capacity());
+        assert!(cache.get(&params()).is_none());
+    }
+}
@@ -0,0 +1,548 @@
+use crate::fractals::anti_aliasing::{self, AntiAliasing, Subsamples};
+use crate::fractals::fractal_simd;
+use crate::fractals::fractal_type::{EscapeTime, FractalType};
+use crate::fractals::lyapunov::{self, LyapunovSequence};
+use crate::fractals::mariani_silver::{self, RenderAlgorithm};
+use crate::fractals::perturbation;
+use crate::utils::color_space;
+use crate::utils::point::Point;
+use crate::utils::precision_mode::PrecisionMode;
+use egui::Color32;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Every parameter that influences the raw iteration counts of a render.
+/// Colouring settings are deliberately absent: changing them never requires recomputing orbits.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RenderParams {
+    pub fractal_type: FractalType,
+    pub center: Point,
+    pub zoom: f64,
+    pub julia_c: Point,
+    pub lyapunov_sequence: LyapunovSequence,
+    pub max_iterations: u16,
+    pub precision_mode: PrecisionMode,
+    pub anti_aliasing: AntiAliasing,
+    pub algorithm: RenderAlgorithm,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Raw escape-time results of a render, one primary iteration count per pixel in row-major order,
+/// plus the extra samples of supersampled pixels when anti-aliasing is enabled.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IterationBuffer {
+    pub width: usize,
+    pub height: usize,
+    pub max_iterations: u16,
+    pub iterations: Vec<u16>,
+    pub subsamples: Option<Subsamples>,
+    pub skipped_iterations: u16, // Iterations every pixel skipped by series approximation
+}
+
+impl Default for RenderParams {
+    /// The whole Mandelbrot set at 800×600, matching the viewer's start-up view.
+    #[inline]
+    fn default() -> Self {
+        Self {
+            fractal_type: FractalType::Mandelbrot,
+            center: FractalType::Mandelbrot.default_center(),
+            zoom: 1.0,
+            julia_c: Point::new(-0.7269, 0.1889),
+            lyapunov_sequence: LyapunovSequence::default(),
+            max_iterations: 300,
+            precision_mode: PrecisionMode::High,
+            anti_aliasing: AntiAliasing::Off,
+            algorithm: RenderAlgorithm::BruteForce,
+            width: 800,
+            height: 600,
+        }
+    }
+}
+
+impl IterationBuffer {
+    /// Returns the memory held by the buffer's counts and samples, in bytes.
+    #[must_use]
+    pub fn memory_size(&self) -> usize {
+        let subsamples = self.subsamples.as_ref().map_or(0, |subsamples| {
+            size_of_val(subsamples.index.as_slice()) + size_of_val(subsamples.samples.as_slice())
+        });
+        size_of_val(self.iterations.as_slice()) + subsamples
+    }
+}
+
+impl RenderParams {
+    /// Computes the scale factors and min coordinates for the fractal view.
+    #[inline(always)]
+    #[must_use]
+    pub fn compute_scale(&self) -> (f64, f64, f64, f64) {
+        let width: u32 = self.width;
+        let height: u32 = self.height;
+
+        let aspect_ratio: f64 = f64::from(width) / f64::from(height);
+        let zoom_factor: f64 = 2.0_f64 / self.zoom;
+        let x_min: f64 = zoom_factor.mul_add(-aspect_ratio, self.center.x);
+        let x_max: f64 = zoom_factor.mul_add(aspect_ratio, self.center.x);
+        let y_min: f64 = self.center.y - zoom_factor;
+        let y_max: f64 = self.center.y + zoom_factor;
+
+        let x_scale: f64 = (x_max - x_min) / f64::from(width);
+        let y_scale: f64 = (y_max - y_min) / f64::from(height);
+
+        (x_scale, y_scale, x_min, y_min)
+    }
+
+    /// Returns the parameters of the `width`×`height` pixel rectangle at `(x, y)` of this view,
+    /// sampled at the same pixel size, so pieces of a large render can be computed separately.
+    #[must_use]
+    pub fn region(&self, x: u32, y: u32, width: u32, height: u32) -> Self {
+        let (x_scale, y_scale, x_min, y_min) = self.compute_scale();
+        let center = Point::new(
+            (f64::from(x) + f64::from(width) / 2.0).mul_add(x_scale, x_min),
+            (f64::from(y) + f64::from(height) / 2.0).mul_add(y_scale, y_min),
+        );
+        Self {
+            center,
+            zoom: self.zoom * f64::from(self.height) / f64::from(height),
+            width,
+            height,
+            ..self.clone()
+        }
+    }
+}
+
+/// Computes the iteration count of every pixel described by `params` with `params.algorithm`,
+/// supersampling according to `params.anti_aliasing`.
+#[must_use]
+pub fn compute_iterations(params: &RenderParams) -> IterationBuffer {
+    let (iterations, skipped_iterations) = primary_samples(params);
+    let mut buffer = IterationBuffer {
+        width: params.width as usize,
+        height: params.height as usize,
+        max_iterations: params.max_iterations,
+        iterations,
+        subsamples: None,
+        skipped_iterations,
+    };
+    buffer.subsamples = anti_aliasing::supersample(params, &buffer);
+    buffer
+}
+
+/// Computes one sample at the origin of every pixel with the algorithm chosen in `params`, and
+/// returns them with the number of iterations skipped for every pixel.
+/// Perturbation only applies to the Mandelbrot set; other fractals are iterated brute force.
+fn primary_samples(params: &RenderParams) -> (Vec<u16>, u16) {
+    match params.algorithm {
+        RenderAlgorithm::MarianiSilver => (mariani_silver::compute_samples(params), 0),
+        RenderAlgorithm::Perturbation if params.fractal_type == FractalType::Mandelbrot => {
+            perturbation::compute_samples(params)
+        }
+        RenderAlgorithm::BruteForce | RenderAlgorithm::Perturbation => (compute_samples(params, 0.0, 0.0), 0),
+    }
+}
+
+/// Returns the whole-pixel offset `(x, y)` of the view of `params` from the view of `previous`,
+/// or `None` unless the two differ only by a pan of a whole number of pixels.
+#[must_use]
+pub fn pan_offset(previous: &RenderParams, params: &RenderParams) -> Option<(i64, i64)> {
+    const TOLERANCE: f64 = 1e-3; // Of a pixel
+    let moved_only = RenderParams {
+        center: previous.center,
+        ..params.clone()
+    };
+    if moved_only != *previous {
+        return None;
+    }
+    let (x_scale, y_scale, x_min, y_min) = params.compute_scale();
+    let (_, _, previous_x_min, previous_y_min) = previous.compute_scale();
+    let x = (x_min - previous_x_min) / x_scale;
+    let y = (y_min - previous_y_min) / y_scale;
+    let whole = |offset: f64| ((offset - offset.round()).abs() < TOLERANCE).then(|| offset.round() as i64);
+    Some((whole(x)?, whole(y)?))
+}
+
+/// Computes the buffer of `params` from `previous`, the buffer of the view `previous_params`,
+/// when the two views differ only by a whole-pixel pan: the pixels still in view are moved and
+/// only the newly exposed strips are computed. Returns `None` when nothing can be reused, or the
+/// previous buffer is supersampled.
+#[must_use]
+pub fn pan_iterations(
+    previous: &IterationBuffer,
+    previous_params: &RenderParams,
+    params: &RenderParams,
+) -> Option<IterationBuffer> {
+    let (width, height) = (params.width as usize, params.height as usize);
+    if previous.subsamples.is_some() || (previous.width, previous.height) != (width, height) {
+        return None;
+    }
+    let (dx, dy) = pan_offset(previous_params, params)?;
+    if dx.unsigned_abs() as usize >= width || dy.unsigned_abs() as usize >= height {
+        return None;
+    }
+
+    // Pixel (x, y) of the new view is pixel (x + dx, y + dy) of the previous one
+    let (shift_x, shift_y) = (dx.unsigned_abs() as u32, dy.unsigned_abs() as u32);
+    let mut iterations = vec![0u16; width * height];
+    let mut skipped_iterations = previous.skipped_iterations;
+    let kept = width - shift_x as usize;
+    let (from_x, to_x) = if dx >= 0 { (shift_x as usize, 0) } else { (0, shift_x as usize) };
+    for y in 0..height {
+        let source_y = y as i64 + dy;
+        if (0..height as i64).contains(&source_y) {
+            let source = source_y as usize * width + from_x;
+            iterations[y * width + to_x..][..kept].copy_from_slice(&previous.iterations[source..source + kept]);
+        }
+    }
+
+    // Exposed columns over the full height, then exposed rows over the remaining columns. They are
+    // sampled on the previous view's pixel grid, shifted by the pan, so they line up bit for bit
+    // with the moved pixels; perturbation renders them as views of their own around its reference
+    let column_x = if dx >= 0 { params.width - shift_x } else { 0 };
+    let row_y = if dy >= 0 { params.height - shift_y } else { 0 };
+    let strips = [
+        (column_x, 0, shift_x, params.height),
+        (to_x as u32, row_y, kept as u32, shift_y),
+    ];
+    for (x, y, strip_width, strip_height) in strips {
+        if strip_width == 0 || strip_height == 0 {
+            continue;
+        }
+        let (strip, skipped) = match params.algorithm {
+            RenderAlgorithm::Perturbation if params.fractal_type == FractalType::Mandelbrot => {
+                primary_samples(&params.region(x, y, strip_width, strip_height))
+            }
+            RenderAlgorithm::BruteForce | RenderAlgorithm::MarianiSilver | RenderAlgorithm::Perturbation => {
+                let mut strip = vec![0u16; strip_width as usize * strip_height as usize];
+                strip.par_chunks_mut(strip_width as usize).enumerate().for_each(|(row, values)| {
+                    let source_y = (i64::from(y) + row as i64 + dy) as f64;
+                    fill_row(previous_params, values, (i64::from(x) + dx) as f64, source_y);
+                });
+                (strip, 0)
+            }
+        };
+        skipped_iterations = skipped_iterations.min(skipped);
+        for (row, values) in strip.chunks_exact(strip_width as usize).enumerate() {
+            let start = (y as usize + row) * width + x as usize;
+            iterations[start..start + values.len()].copy_from_slice(values);
+        }
+    }
+
+    Some(IterationBuffer {
+        width,
+        height,
+        max_iterations: params.max_iterations,
+        iterations,
+        subsamples: None,
+        skipped_iterations,
+    })
+}
+
+/// Evaluates a single sample at fractional pixel coordinates `(x, y)` of the view.
+#[inline]
+#[must_use]
+pub fn sample_point(params: &RenderParams, x: f64, y: f64) -> u16 {
+    let (x_scale, y_scale, x_min, y_min) = params.compute_scale();
+    let Some(fractal) = params.fractal_type.escape_time() else {
+        return lyapunov::iterations(
+            x.mul_add(x_scale, x_min),
+            y.mul_add(y_scale, y_min),
+            params.lyapunov_sequence,
+            params.max_iterations,
+        );
+    };
+    fractal.iterations(
+        x.mul_add(x_scale, x_min),
+        y.mul_add(y_scale, y_min),
+        params.max_iterations,
+        &params.julia_c,
+        params.precision_mode,
+    )
+}
+
+/// Computes one sample per pixel, shifted by `(offset_x, offset_y)` pixels from the pixel origin,
+/// with row-based parallelization and SIMD rows (see [`fill_points`]).
+#[must_use]
+pub fn compute_samples(params: &RenderParams, offset_x: f64, offset_y: f64) -> Vec<u16> {
+    let width = params.width as usize;
+    let height = params.height as usize;
+    let mut iterations = vec![0u16; width * height];
+
+    if width == 0 || height == 0 {
+        return iterations;
+    }
+
+    // Row-based parallelization with SIMD optimization
+    iterations
+        .par_chunks_mut(width)
+        .enumerate()
+        .for_each(|(y, row)| fill_row(params, row, offset_x, y as f64 + offset_y));
+
+    iterations
+}
+
+/// Computes the samples at fractional pixel coordinates `(x + i, y)` of the view into `row[i]`.
+#[inline]
+pub fn fill_row(params: &RenderParams, row: &mut [u16], x: f64, y: f64) {
+    fill_points(params, row, |i| (x + i as f64, y));
+}
+
+/// Computes the samples at fractional pixel coordinates `pixel(i)` of the view into `out[i]`.
+/// Highly optimized with:
+/// - SIMD vectorization (4x f32 or 2x f64 pixels per operation)
+/// - FMA operations and loop unrolling
+///
+/// Every coordinate is mapped to the plane on its own and the last SIMD group is padded with the
+/// last pixel, so a pixel gets the same value whether it is computed in a row, a column or alone.
+pub fn fill_points(params: &RenderParams, out: &mut [u16], pixel: impl Fn(usize) -> (f64, f64)) {
+    let Some(last) = out.len().checked_sub(1) else {
+        return;
+    };
+    let (x_scale, y_scale, x_min, y_min) = params.compute_scale();
+    let point = |i: usize| {
+        let (x, y) = pixel(i.min(last));
+        (x.mul_add(x_scale, x_min), y.mul_add(y_scale, y_min))
+    };
+
+    // Cache hot fields as local copies — avoids pointer dereferences through `params`
+    // inside the inner loop
+    let max_iterations = params.max_iterations;
+    let julia_cx = params.julia_c.x;
+    let julia_cy = params.julia_c.y;
+
+    // Lyapunov exponents are computed in f64 whatever the precision mode, 2 pixels at a time
+    let Some(fractal) = params.fractal_type.escape_time() else {
+        lyapunov::fill_points(out, point, params.lyapunov_sequence, max_iterations);
+        return;
+    };
+
+    match params.precision_mode {
+        PrecisionMode::Fast => {
+            let (julia_cx_f32, julia_cy_f32) = (julia_cx as f32, julia_cy as f32);
+            // Process in groups of 4 with SIMD
+            for (group, start) in out.chunks_mut(4).zip((0..).step_by(4)) {
+                let points: [(f64, f64); 4] = std::array::from_fn(|lane| point(start + lane));
+                let cx_arr = points.map(|(cx, _)| cx as f32);
+                let cy_arr = points.map(|(_, cy)| cy as f32);
+
+                let lanes = match fractal {
+                    EscapeTime::Mandelbrot => {
+                        fractal_simd::mandelbrot_simd_f32(&cx_arr, &cy_arr, max_iterations)
+                    }
+                    EscapeTime::Julia => fractal_simd::julia_simd_f32(
+                        &cx_arr,
+                        &cy_arr,
+                        julia_cx_f32,
+                        julia_cy_f32,
+                        max_iterations,
+                    ),
+                    EscapeTime::BurningShip => {
+                        fractal_simd::burning_ship_simd_f32(&cx_arr, &cy_arr, max_iterations)
+                    }
+                    EscapeTime::Tricorn => {
+                        fractal_simd::tricorn_simd_f32(&cx_arr, &cy_arr, max_iterations)
+                    }
+                };
+
+                group.copy_from_slice(&lanes[..group.len()]);
+            }
+        }
+        PrecisionMode::High => {
+            // Process in groups of 2 with SIMD
+            for (group, start) in out.chunks_mut(2).zip((0..).step_by(2)) {
+                let points: [(f64, f64); 2] = std::array::from_fn(|lane| point(start + lane));
+                let cx_arr = points.map(|(cx, _)| cx);
+                let cy_arr = points.map(|(_, cy)| cy);
+
+                let lanes = match fractal {
+                    EscapeTime::Mandelbrot => {
+                        fractal_simd::mandelbrot_simd_f64(&cx_arr, &cy_arr, max_iterations)
+                    }
+                    EscapeTime::Julia => fractal_simd::julia_simd_f64(
+                        &cx_arr,
+                        &cy_arr,
+                        julia_cx,
+                        julia_cy,
+                        max_iterations,
+                    ),
+                    EscapeTime::BurningShip => {
+                        fractal_simd::burning_ship_simd_f64(&cx_arr, &cy_arr, max_iterations)
+                    }
+                    EscapeTime::Tricorn => {
+                        fractal_simd::tricorn_simd_f64(&cx_arr, &cy_arr, max_iterations)
+                    }
+                };
+
+                group.copy_from_slice(&lanes[..group.len()]);
+            }
+        }
+        #[cfg(feature = "f128")]
+        PrecisionMode::UltraHigh => {
+            for (i, value) in out.iter_mut().enumerate() {
+                let (cx, cy) = point(i);
+                *value = fractal.iterations(cx, cy, max_iterations, &params.julia_c, PrecisionMode::UltraHigh);
+            }
+        }
+    }
+}
+
+/// Maps an iteration buffer through a pre-computed palette into a displayable image.
+/// `palette` must hold `max_iterations + 1` entries, as built by `ColorScheme::build_palette`.
+#[must_use]
+pub fn colorize(buffer: &IterationBuffer, palette: &[Color32]) -> egui::ColorImage {
+    if buffer.width == 0 || buffer.height == 0 {
+        return egui::ColorImage::new([1, 1], vec![Color32::BLACK; 1]);
+    }
+
+    let pixels: Vec<Color32> = match &buffer.subsamples {
+        None => buffer
+            .iterations
+            .par_iter()
+            .map(|&iterations| palette[iterations as usize])
+            .collect(),
+        Some(subsamples) => {
+            // Average supersampled pixels in linear light: sRGB averages come out too dark
+            let linear_palette: Vec<[f32; 3]> = palette.iter().map(|&color| color_space::to_linear_rgb(color)).collect();
+            let weight = 1.0 / subsamples.count as f32;
+
+            buffer
+                .iterations
+                .par_iter()
+                .zip(subsamples.index.par_iter())
+                .map(|(&iterations, &index)| {
+                    if index == 0 {
+                        return palette[iterations as usize];
+                    }
+                    let start = (index as usize - 1) * subsamples.count;
+                    let mut sum = [0.0f32; 3];
+                    for &sample in &subsamples.samples[start..start + subsamples.count] {
+                        let rgb = linear_palette[sample as usize];
+                        sum[0] += rgb[0];
+                        sum[1] += rgb[1];
+                        sum[2] += rgb[2];
+                    }
+                    color_space::from_linear_rgb([sum[0] * weight, sum[1] * weight, sum[2] * weight])
+                })
+                .collect()
+        }
+    };
+
+    // Construct ColorImage directly from Vec<Color32> — no extra allocation/copy
+    egui::ColorImage {
+        size: [buffer.width, buffer.height],
+        source_size: egui::Vec2::new(buffer.width as f32, buffer.height as f32),
+        pixels,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::color_scheme::ColorScheme;
+
+    fn params() -> RenderParams {
+        RenderParams {
+            fractal_type: FractalType::Mandelbrot,
+            center: Point::new(-0.5, 0.0),
+            zoom: 1.0,
+            julia_c: Point::new(-0.7269, 0.1889),
+            lyapunov_sequence: LyapunovSequence::default(),
+            max_iterations: 100,
+            precision_mode: PrecisionMode::Fast,
+            anti_aliasing: AntiAliasing::Off,
+            algorithm: RenderAlgorithm::BruteForce,
+            width: 37,
+            height: 21,
+        }
+    }
+
+    #[test]
+    fn test_compute_iterations_size() {
+        let buffer = compute_iterations(&params());
+        assert_eq!(buffer.width, 37);
+        assert_eq!(buffer.height, 21);
+        assert_eq!(buffer.iterations.len(), 37 * 21);
+        assert!(buffer.iterations.iter().all(|&i| i <= 100));
+    }
+
+    #[test]
+    fn test_region_keeps_pixel_positions() {
+        let params = params();
+        let region = params.region(7, 12, 20, 5);
+        let (x_scale, y_scale, x_min, y_min) = params.compute_scale();
+        let (region_x_scale, region_y_scale, region_x_min, region_y_min) = region.compute_scale();
+        for (x, y) in [(0.0f64, 0.0f64), (3.5, 2.0), (19.0, 4.0)] {
+            let expected = ((x + 7.0).mul_add(x_scale, x_min), (y + 12.0).mul_add(y_scale, y_min));
+            let actual = (x.mul_add(region_x_scale, region_x_min), y.mul_add(region_y_scale, region_y_min));
+            assert!((expected.0 - actual.0).abs() < 1e-12 && (expected.1 - actual.1).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_pan_matches_full_render() {
+        let previous_params = params();
+        let previous = compute_iterations(&previous_params);
+        let (x_scale, y_scale, _, _) = previous_params.compute_scale();
+        for (dx, dy) in [(3.0f64, -2.0f64), (-5.0, 0.0), (0.0, 4.0), (-1.0, -1.0)] {
+            let params = RenderParams {
+                center: Point::new(dx.mul_add(x_scale, previous_params.center.x), dy.mul_add(y_scale, previous_params.center.y)),
+                ..previous_params.clone()
+            };
+            assert_eq!(pan_offset(&previous_params, &params), Some((dx as i64, dy as i64)));
+            let panned = pan_iterations(&previous, &previous_params, &params).unwrap();
+            // The panned view stays on the previous view's pixel grid, moved by whole pixels
+            let full = compute_samples(&previous_params, dx, dy);
+            assert_eq!(panned.iterations, full, "pan by ({dx}, {dy})");
+        }
+
+        // Half a pixel, a zoom or a pan past the edge cannot reuse anything
+        let half = RenderParams {
+            center: Point::new(0.5f64.mul_add(x_scale, previous_params.center.x), previous_params.center.y),
+            ..previous_params.clone()
+        };
+        assert!(pan_iterations(&previous, &previous_params, &half).is_none());
+        let zoomed = RenderParams {
+            zoom: 2.0,
+            ..previous_params.clone()
+        };
+        assert!(pan_iterations(&previous, &previous_params, &zoomed).is_none());
+        let far = RenderParams {
+            center: Point::new(40.0f64.mul_add(x_scale, previous_params.center.x), previous_params.center.y),
+            ..previous_params.clone()
+        };
+        assert!(pan_iterations(&previous, &previous_params, &far).is_none());
+    }
+
+    #[test]
+    fn test_colorize_matches_palette() {
+        let buffer = compute_iterations(&params());
+        let palette = ColorScheme::Hot.build_palette(buffer.max_iterations);
+        let image = colorize(&buffer, &palette);
+        assert_eq!(image.size, [37, 21]);
+        for (pixel, &iterations) in image.pixels.iter().zip(&buffer.iterations) {
+            assert_eq!(*pixel, palette[iterations as usize]);
+        }
+    }
+
+    #[test]
+    fn test_colorize_averages_subsamples() {
+        let buffer = IterationBuffer {
+            width: 2,
+            height: 1,
+            max_iterations: 2,
+            iterations: vec![0, 0],
+            subsamples: Some(Subsamples {
+                count: 2,
+                index: vec![0, 1],
+                samples: vec![0, 2],
+            }),
+            skipped_iterations: 0,
+        };
+        let palette = [Color32::WHITE, Color32::RED, Color32::BLACK];
+        let image = colorize(&buffer, &palette);
+
+        // Unrefined pixel keeps its primary color; refined one is the linear-light mean
+        assert_eq!(image.pixels[0], Color32::WHITE);
+        assert_eq!(image.pixels[1], Color32::from_gray(188));
+    }
+}
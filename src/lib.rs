@@ -1,4 +1,8 @@
 pub mod constant;
 pub mod utils;
 pub mod fractals;
+pub mod animation;
+pub mod cli;
 pub mod ui;
+pub mod server;
+pub mod density;
@@ -1,11 +1,28 @@
 use eframe::{NativeOptions, egui};
 use egui::IconData;
+use fractals_rs::cli::{self, Command};
 use fractals_rs::constant::{HEIGHT, WIDTH};
 use fractals_rs::ui::fractal_app::FractalApp;
 
 const APP_ICON: &[u8] = include_bytes!("../assets/fractale.png");
 
 fn main() -> Result<(), eframe::Error> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match cli::parse(&args) {
+        Ok(Command::Gui) => {}
+        Ok(command) => {
+            if let Err(error) = cli::run(&command) {
+                eprintln!("error: {error}");
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        Err(error) => {
+            eprintln!("error: {error}\n\n{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    }
+
     let icon_data: Result<IconData, eframe::Error> = load_icon();
 
     let options: NativeOptions = if let Ok(icon_data) = icon_data {
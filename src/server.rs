@@ -0,0 +1,402 @@
+use crate::fractals::fractal_type::FractalType;
+use crate::fractals::renderer::RenderParams;
+use crate::utils::color_mapping::ColorSettings;
+use crate::utils::color_scheme::ColorScheme;
+use crate::utils::export;
+use crate::utils::lru::LruCache;
+use crate::utils::point::Point;
+use crate::utils::precision_mode::PrecisionMode;
+use crate::utils::tiles;
+use std::io::{self, BufRead, BufReader, Cursor, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Side of every served tile, in pixels.
+pub const TILE_SIZE: u32 = 256;
+
+/// Deepest zoom level served; beyond it neighbouring pixels are closer than `f64` can tell apart.
+pub const MAX_ZOOM: u32 = 44;
+
+/// Iteration limit of tiles that do not ask for one.
+pub const DEFAULT_ITERATIONS: u16 = 500;
+
+/// Memory given to cached tiles unless told otherwise.
+pub const DEFAULT_CACHE_BYTES: usize = 256 * 1024 * 1024;
+
+/// Most bytes read of a request line and its headers; longer requests are refused.
+const MAX_REQUEST_BYTES: u64 = 16 * 1024;
+
+/// Connections answered at once; further clients wait to be accepted.
+const WORKERS: usize = 32;
+
+/// How long a client may take to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Page served at `/`: a Leaflet map over the tiles, forwarding its own query string
+/// (`?fractal=julia&palette=viridis&iter=1000`) to the tile URLs.
+const INDEX_PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Fractals-rs</title>
+<link rel="stylesheet" href="https://unpkg.com/leaflet@1.9.4/dist/leaflet.css">
+<script src="https://unpkg.com/leaflet@1.9.4/dist/leaflet.js"></script>
+<style>html, body, #map { height: 100%; margin: 0; background: #000; }</style>
+</head>
+<body>
+<div id="map"></div>
+<script>
+const query = new URLSearchParams(location.search);
+const fractal = query.get("fractal") || "mandelbrot";
+query.delete("fractal");
+const map = L.map("map", { crs: L.CRS.Simple, minZoom: 0, maxZoom: 44 }).setView([-128, 128], 1);
+L.tileLayer(`/tiles/${fractal}/{z}/{x}/{y}.png?${query}`, {
+  tileSize: 256, noWrap: true, maxZoom: 44, bounds: [[-256, 0], [0, 256]],
+}).addTo(map);
+</script>
+</body>
+</html>
+"#;
+
+/// One tile of the XYZ pyramid of a fractal, as asked for by
+/// `/tiles/{fractal}/{z}/{x}/{y}.png?palette=…&iter=…&julia=…`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TileRequest {
+    pub fractal_type: FractalType,
+    pub z: u32,
+    pub x: u32,
+    pub y: u32,
+    pub scheme: ColorScheme,
+    pub max_iterations: u16,
+    pub julia_c: Point,
+}
+
+/// An HTTP response.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Response {
+    pub status: &'static str, // Status code and reason, e.g. "200 OK"
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// Creates a plain-text error response.
+    fn error(status: &'static str, message: &str) -> Self {
+        Self {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: format!("{message}\n").into_bytes(),
+        }
+    }
+}
+
+/// Decodes `%XX` escapes and `+` (a space) in a query string value.
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if let Some(byte) = bytes
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()) =>
+            {
+                decoded.push(byte);
+                i += 2;
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+impl TileRequest {
+    /// Parses the target of a tile request. Missing query parameters take the defaults: the
+    /// Classic palette, `DEFAULT_ITERATIONS` iterations and the viewer's Julia constant.
+    pub fn parse(target: &str) -> Result<Self, String> {
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let parts: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+        let ["tiles", fractal, z, x, y] = parts.as_slice() else {
+            return Err(String::from("expected /tiles/{fractal}/{z}/{x}/{y}.png"));
+        };
+        let y = y.strip_suffix(".png").ok_or("tiles are served as .png")?;
+        let number = |text: &str| text.parse::<u32>().map_err(|_| format!("`{text}` is not a tile coordinate"));
+        let (z, x, y) = (number(z)?, number(x)?, number(y)?);
+        if z > MAX_ZOOM {
+            return Err(format!("zoom levels go up to {MAX_ZOOM}"));
+        }
+        if u64::from(x) >> z != 0 || u64::from(y) >> z != 0 {
+            return Err(format!("tile {x}/{y} is outside zoom level {z}"));
+        }
+
+        let mut request = Self {
+            fractal_type: FractalType::from_name(fractal).ok_or_else(|| format!("unknown fractal `{fractal}`"))?,
+            z,
+            x,
+            y,
+            scheme: ColorScheme::default(),
+            max_iterations: DEFAULT_ITERATIONS,
+            julia_c: RenderParams::default().julia_c,
+        };
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = percent_decode(value);
+            match key {
+                "palette" => {
+                    request.scheme = ColorScheme::from_name(&value).ok_or_else(|| format!("unknown palette `{value}`"))?;
+                }
+                "iter" => {
+                    request.max_iterations = value
+                        .parse()
+                        .ok()
+                        .filter(|&iterations| iterations > 0)
+                        .ok_or_else(|| format!("`{value}` is not a valid iteration count"))?;
+                }
+                "julia" => {
+                    let parsed = value
+                        .split_once(',')
+                        .and_then(|(x, y)| Some(Point::new(x.trim().parse().ok()?, y.trim().parse().ok()?)));
+                    request.julia_c = parsed.ok_or_else(|| format!("expected `julia=CX,CY`, got `{value}`"))?;
+                }
+                // Cache busters and the like
+                _ => {}
+            }
+        }
+        Ok(request)
+    }
+
+    /// Returns the render parameters of the tile. Zoom level 0 is a single tile showing the
+    /// fractal's home view, 4 units across.
+    #[must_use]
+    pub fn params(&self) -> RenderParams {
+        let world = RenderParams {
+            fractal_type: self.fractal_type,
+            center: self.fractal_type.default_center(),
+            zoom: 1.0,
+            julia_c: self.julia_c,
+            max_iterations: self.max_iterations,
+            precision_mode: PrecisionMode::High,
+            width: TILE_SIZE,
+            height: TILE_SIZE,
+            ..RenderParams::default()
+        };
+        tiles::xyz_tile(&world, self.z, self.x, self.y)
+    }
+
+    /// Renders the tile as PNG bytes.
+    pub fn render(&self) -> io::Result<Vec<u8>> {
+        let colors = ColorSettings {
+            scheme: self.scheme,
+            ..ColorSettings::default()
+        };
+        let image = export::render_image(&self.params(), &colors);
+        let mut bytes = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::Png)
+            .map_err(io::Error::other)?;
+        Ok(bytes)
+    }
+}
+
+/// Renders tiles on demand, keeping recent ones in a memory-bounded cache.
+pub struct TileServer {
+    cache: Mutex<LruCache<String, Vec<u8>>>,
+}
+
+impl TileServer {
+    /// Creates a server whose tile cache holds at most `cache_bytes` of PNG data.
+    #[must_use]
+    pub fn new(cache_bytes: usize) -> Self {
+        Self {
+            cache: Mutex::new(LruCache::new(cache_bytes)),
+        }
+    }
+
+    /// Returns the number of cached tiles.
+    #[must_use]
+    pub fn cached_tiles(&self) -> usize {
+        self.cache.lock().map_or(0, |cache| cache.len())
+    }
+
+    /// Answers a `GET` of `target`.
+    pub fn respond(&self, target: &str) -> Response {
+        let path = target.split('?').next().unwrap_or_default();
+        if path == "/" || path == "/index.html" {
+            return Response {
+                status: "200 OK",
+                content_type: "text/html; charset=utf-8",
+                body: INDEX_PAGE.as_bytes().to_vec(),
+            };
+        }
+        if !path.starts_with("/tiles/") {
+            return Response::error("404 Not Found", "not found");
+        }
+
+        let request = match TileRequest::parse(target) {
+            Ok(request) => request,
+            Err(error) => return Response::error("400 Bad Request", &error),
+        };
+        // Equal requests render equal tiles, whatever the order or spelling of the query
+        let key = format!("{request:?}");
+        let cached = self.cache.lock().ok().and_then(|mut cache| cache.get(&key).cloned());
+        let body = match cached {
+            Some(body) => body,
+            None => match request.render() {
+                Ok(body) => {
+                    if let Ok(mut cache) = self.cache.lock() {
+                        cache.insert(key, body.clone(), body.len());
+                    }
+                    body
+                }
+                Err(error) => return Response::error("500 Internal Server Error", &error.to_string()),
+            },
+        };
+        Response {
+            status: "200 OK",
+            content_type: "image/png",
+            body,
+        }
+    }
+}
+
+/// Reads one request from `stream` and writes the response. Only `GET` and `HEAD` are served,
+/// and every connection is closed after its response. At most `MAX_REQUEST_BYTES` of the request
+/// are read, each read waiting at most `READ_TIMEOUT`.
+fn handle_connection(stream: TcpStream, server: &TileServer) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new((&stream).take(MAX_REQUEST_BYTES));
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // The headers are not needed, but must be read before answering
+    let mut header = String::new();
+    let mut complete = false;
+    while !complete && reader.read_line(&mut header)? > 0 {
+        complete = header.trim_end().is_empty();
+        header.clear();
+    }
+
+    let mut fields = request_line.split_whitespace();
+    let (method, target) = (fields.next().unwrap_or_default(), fields.next().unwrap_or("/"));
+    let response = match method {
+        _ if !complete => Response::error("431 Request Header Fields Too Large", "request too large"),
+        "GET" | "HEAD" => server.respond(target),
+        _ => Response::error("405 Method Not Allowed", "only GET is supported"),
+    };
+
+    let mut writer = io::BufWriter::new(&stream);
+    write!(
+        writer,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\nCache-Control: public, max-age=86400\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.content_type,
+        response.body.len()
+    )?;
+    if method != "HEAD" {
+        writer.write_all(&response.body)?;
+    }
+    writer.flush()
+}
+
+/// Answers requests on `listener` until the process ends, with `WORKERS` threads (this one
+/// included) that each accept and answer one connection at a time. Never returns.
+pub fn serve(listener: &TcpListener, server: &TileServer) -> ! {
+    let worker = || loop {
+        // Connections that fail before they are accepted are skipped
+        if let Ok((stream, _)) = listener.accept() {
+            // A client that hangs up early is not the server's problem
+            let _ = handle_connection(stream, server);
+        }
+    };
+    std::thread::scope(|scope| {
+        for _ in 1..WORKERS {
+            scope.spawn(worker);
+        }
+        worker()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tile_request() {
+        let request = TileRequest::parse("/tiles/burning-ship/3/5/2.png?palette=deep%20ocean&iter=1200&v=2").unwrap();
+        assert_eq!(request.fractal_type, FractalType::BurningShip);
+        assert_eq!((request.z, request.x, request.y), (3, 5, 2));
+        assert_eq!(request.scheme, ColorScheme::from_name("deep-ocean").unwrap());
+        assert_eq!(request.max_iterations, 1200);
+
+        let julia = TileRequest::parse("/tiles/julia/0/0/0.png?julia=-0.8,0.156").unwrap();
+        assert_eq!(julia.julia_c, Point::new(-0.8, 0.156));
+        assert_eq!(julia.params().center, Point::new(0.0, 0.0));
+
+        for bad in [
+            "/tiles/mandelbrot/1/2/0.png",
+            "/tiles/mandelbrot/0/0/0.jpg",
+            "/tiles/koch/0/0/0.png",
+            "/tiles/mandelbrot/0/0/0.png?iter=0",
+            "/tiles/mandelbrot/0/0/0.png?palette=nope",
+            "/tiles/mandelbrot/45/0/0.png",
+        ] {
+            assert!(TileRequest::parse(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_responses_are_cached() {
+        let server = TileServer::new(DEFAULT_CACHE_BYTES);
+        let response = server.respond("/tiles/mandelbrot/1/0/1.png?iter=50");
+        assert_eq!((response.status, response.content_type), ("200 OK", "image/png"));
+        assert!(response.body.starts_with(b"\x89PNG"));
+        assert_eq!(server.cached_tiles(), 1);
+
+        // Same tile spelled differently: served from the cache
+        assert_eq!(server.respond("/tiles/Mandelbrot/1/0/1.png?iter=50&palette=classic"), response);
+        assert_eq!(server.cached_tiles(), 1);
+
+        assert_eq!(server.respond("/tiles/mandelbrot/1/9/9.png").status, "400 Bad Request");
+        assert_eq!(server.respond("/favicon.ico").status, "404 Not Found");
+        assert_eq!(server.respond("/").content_type, "text/html; charset=utf-8");
+    }
+
+    #[test]
+    fn test_serves_http() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || serve(&listener, &TileServer::new(1024 * 1024)));
+
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream
+            .write_all(b"GET /tiles/tricorn/2/1/1.png?iter=40 HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).unwrap();
+
+        let header_end = response.windows(4).position(|window| window == b"\r\n\r\n").unwrap();
+        let header = String::from_utf8_lossy(&response[..header_end]);
+        assert!(header.starts_with("HTTP/1.1 200 OK"));
+        assert!(header.contains("Content-Type: image/png"));
+        let image = image::load_from_memory(&response[header_end + 4..]).unwrap();
+        assert_eq!((image.width(), image.height()), (TILE_SIZE, TILE_SIZE));
+    }
+
+    #[test]
+    fn test_refuses_oversized_requests() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || serve(&listener, &TileServer::new(1024 * 1024)));
+
+        let mut stream = TcpStream::connect(address).unwrap();
+        // Exactly the limit, without the blank line ending the headers
+        let mut request = b"GET / HTTP/1.1\r\nX-Padding: ".to_vec();
+        request.resize(MAX_REQUEST_BYTES as usize, b'x');
+        stream.write_all(&request).unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).unwrap();
+        assert!(response.starts_with(b"HTTP/1.1 431"));
+    }
+}
@@ -0,0 +1,329 @@
+use crate::animation::keyframe::{Animation, Keyframe, Timeline};
+use crate::animation::sequence::{self, SequenceOutput, SequenceSummary};
+use crate::fractals::anti_aliasing::AntiAliasing;
+use crate::fractals::renderer::RenderParams;
+use crate::ui::fractal_app::FractalApp;
+use crate::utils::color_mapping::ColorSettings;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::time::Duration;
+
+/// An image sequence rendering on a background thread.
+pub struct SequenceJob {
+    progress: Arc<AtomicU32>,
+    cancel: Arc<AtomicBool>,
+    result: Receiver<Result<SequenceSummary, String>>,
+}
+
+impl SequenceJob {
+    /// Starts rendering every frame of `timeline` to `output`.
+    pub fn spawn(
+        timeline: impl Timeline + Send + 'static,
+        base: RenderParams,
+        colors: ColorSettings,
+        output: SequenceOutput,
+    ) -> Self {
+        let progress = Arc::new(AtomicU32::new(0));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+        {
+            let (progress, cancel) = (Arc::clone(&progress), Arc::clone(&cancel));
+            std::thread::spawn(move || {
+                let result = sequence::render_sequence(&timeline, &base, &colors, &output, &cancel, |done| {
+                    progress.store(done, Ordering::Relaxed);
+                })
+                .map_err(|error| error.to_string());
+                let _ = sender.send(result);
+            });
+        }
+
+        Self {
+            progress,
+            cancel,
+            result: receiver,
+        }
+    }
+
+    /// Returns the finished job's status message, or `None` while it is still running.
+    pub fn poll(&self, ctx: &egui::Context) -> Option<String> {
+        match self.result.try_recv() {
+            Ok(Ok(summary)) => Some(format!(
+                "✅ Rendered {} frame(s), {} already on disk",
+                summary.rendered, summary.skipped
+            )),
+            Ok(Err(error)) => Some(format!("❌ {error}")),
+            Err(TryRecvError::Empty) => {
+                ctx.request_repaint_after(Duration::from_millis(100));
+                None
+            }
+            Err(TryRecvError::Disconnected) => Some(String::from("❌ Render thread stopped unexpectedly")),
+        }
+    }
+
+    /// Shows a progress bar and a cancel button.
+    pub fn progress_ui(&self, ui: &mut egui::Ui, frames: u32) {
+        let done = self.progress.load(Ordering::Relaxed);
+        ui.add(
+            egui::ProgressBar::new(done as f32 / frames.max(1) as f32)
+                .text(format!("{done}/{frames}"))
+                .desired_width(200.0),
+        );
+        if ui.button("⏹ Cancel").clicked() {
+            self.cancel.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Frame count, size and destinations of a rendered image sequence.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SequenceSettings {
+    pub frames: u32,
+    pub fps: u32,
+    pub width: u32,
+    pub height: u32,
+    pub anti_aliasing: AntiAliasing,
+    pub directory: String,
+    pub write_png: bool,
+    pub write_y4m: bool,
+    pub y4m_path: String,
+}
+
+impl Default for SequenceSettings {
+    /// Creates settings for a 4-second 720p sequence of PNG frames in `frames/`.
+    #[inline]
+    fn default() -> Self {
+        Self {
+            frames: 120,
+            fps: 30,
+            width: 1280,
+            height: 720,
+            anti_aliasing: AntiAliasing::Off,
+            directory: String::from("frames"),
+            write_png: true,
+            write_y4m: false,
+            y4m_path: String::from("animation.y4m"),
+        }
+    }
+}
+
+impl SequenceSettings {
+    /// Returns `true` when at least one output is enabled.
+    #[inline]
+    #[must_use]
+    pub const fn has_output(&self) -> bool {
+        self.write_png || self.write_y4m
+    }
+
+    /// Returns where the frames are written.
+    #[must_use]
+    pub fn output(&self) -> SequenceOutput {
+        SequenceOutput {
+            directory: PathBuf::from(self.directory.trim()),
+            write_png: self.write_png,
+            y4m: self.write_y4m.then(|| PathBuf::from(self.y4m_path.trim())),
+        }
+    }
+
+    /// Returns `base` at the sequence's size and anti-aliasing.
+    #[must_use]
+    pub fn params(&self, base: RenderParams) -> RenderParams {
+        RenderParams {
+            width: self.width,
+            height: self.height,
+            anti_aliasing: self.anti_aliasing,
+            ..base
+        }
+    }
+
+    /// Shows the editors for every setting.
+    pub fn ui(&mut self, ui: &mut egui::Ui, id_salt: &str) {
+        ui.horizontal(|ui| {
+            ui.label("Frames:");
+            ui.add(egui::DragValue::new(&mut self.frames).range(1..=100_000));
+            ui.label("FPS:");
+            ui.add(egui::DragValue::new(&mut self.fps).range(1..=240));
+            ui.label(egui::RichText::new(format!("{:.1} s", f64::from(self.frames) / f64::from(self.fps))).weak());
+        });
+        ui.horizontal(|ui| {
+            ui.label("W:");
+            ui.add(
+                egui::DragValue::new(&mut self.width)
+                    .range(16..=8192)
+                    .suffix(" px")
+                    .speed(10.0),
+            );
+            ui.label("H:");
+            ui.add(
+                egui::DragValue::new(&mut self.height)
+                    .range(16..=8192)
+                    .suffix(" px")
+                    .speed(10.0),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label("Anti-aliasing:");
+            egui::ComboBox::from_id_salt(id_salt)
+                .selected_text(self.anti_aliasing.name())
+                .show_ui(ui, |ui| {
+                    for anti_aliasing in AntiAliasing::all() {
+                        ui.selectable_value(&mut self.anti_aliasing, anti_aliasing, anti_aliasing.name());
+                    }
+                });
+        });
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.write_png, "PNG frames in");
+            ui.text_edit_singleline(&mut self.directory);
+        });
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.write_y4m, "Y4M video");
+            ui.add_enabled(self.write_y4m, egui::TextEdit::singleline(&mut self.y4m_path));
+        });
+    }
+}
+
+/// State of the "Animation" window: the keyframes, output settings and running render.
+#[derive(Default)]
+pub struct AnimationDialog {
+    pub open: bool,
+    pub keyframes: Vec<Keyframe>,
+    pub settings: SequenceSettings,
+    pub preview: f64,
+    pub status: Option<String>,
+    job: Option<SequenceJob>,
+}
+
+impl AnimationDialog {
+    /// Returns `true` while an animation is being rendered.
+    #[inline]
+    #[must_use]
+    pub const fn is_running(&self) -> bool {
+        self.job.is_some()
+    }
+
+    /// Returns the animation described by the dialog.
+    #[inline]
+    #[must_use]
+    pub fn animation(&self) -> Animation {
+        Animation {
+            keyframes: self.keyframes.clone(),
+            frames: self.settings.frames,
+            fps: self.settings.fps,
+        }
+    }
+}
+
+impl FractalApp {
+    /// Returns the current view as an animation keyframe.
+    #[inline]
+    #[must_use]
+    pub const fn current_keyframe(&self) -> Keyframe {
+        Keyframe {
+            center: self.center,
+            zoom: self.zoom,
+            julia_c: self.julia_c,
+            palette_offset: self.palette_offset,
+        }
+    }
+
+    /// Moves the view to a keyframe.
+    pub fn apply_keyframe(&mut self, keyframe: &Keyframe) {
+        self.center = keyframe.center;
+        self.zoom = keyframe.zoom;
+        self.julia_c = keyframe.julia_c;
+        self.palette_offset = keyframe.palette_offset.rem_euclid(1.0);
+        self.needs_update = true;
+    }
+
+    /// Shows the animation window and tracks a running render.
+    pub fn animation_window(&mut self, ctx: &egui::Context) {
+        if let Some(status) = self.animation.job.as_ref().and_then(|job| job.poll(ctx)) {
+            self.animation.status = Some(status);
+            self.animation.job = None;
+        }
+
+        let mut open = self.animation.open;
+        let mut start = false;
+        let mut go_to: Option<Keyframe> = None;
+
+        egui::Window::new("🎬 Animation")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new("Keyframes").strong());
+                let mut remove = None;
+                for (index, keyframe) in self.animation.keyframes.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.monospace(format!(
+                            "{}. ({:.6}, {:.6}) {:.2e}×",
+                            index + 1,
+                            keyframe.center.x,
+                            keyframe.center.y,
+                            keyframe.zoom
+                        ));
+                        if ui.small_button("Go").clicked() {
+                            go_to = Some(*keyframe);
+                        }
+                        if ui.small_button("🗑").clicked() {
+                            remove = Some(index);
+                        }
+                    });
+                }
+                if let Some(index) = remove {
+                    self.animation.keyframes.remove(index);
+                }
+                if ui.button("➕ Add current view").clicked() {
+                    self.animation.keyframes.push(self.current_keyframe());
+                }
+
+                if self.animation.keyframes.len() >= 2 {
+                    ui.horizontal(|ui| {
+                        ui.label("Preview:");
+                        if ui.add(egui::Slider::new(&mut self.animation.preview, 0.0..=1.0)).changed() {
+                            go_to = Some(self.animation.animation().at(self.animation.preview));
+                        }
+                    });
+                }
+
+                ui.separator();
+                self.animation.settings.ui(ui, "animation_anti_aliasing");
+
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    let ready = self.animation.keyframes.len() >= 2 && self.animation.settings.has_output();
+                    if let Some(job) = &self.animation.job {
+                        job.progress_ui(ui, self.animation.settings.frames);
+                    } else if ui.add_enabled(ready, egui::Button::new("🎬 Render")).clicked() {
+                        start = true;
+                    }
+                });
+                ui.label(egui::RichText::new("Frames already on disk are kept, so a render can be resumed.").weak());
+
+                if let Some(status) = &self.animation.status {
+                    ui.label(status);
+                }
+            });
+
+        self.animation.open = open;
+        if let Some(keyframe) = go_to {
+            self.apply_keyframe(&keyframe);
+        }
+        if start {
+            self.start_animation();
+        }
+    }
+
+    /// Renders the animation with the current fractal and colors on a background thread.
+    fn start_animation(&mut self) {
+        let settings = &self.animation.settings;
+        self.animation.job = Some(SequenceJob::spawn(
+            self.animation.animation(),
+            settings.params(self.render_params()),
+            self.color_settings(),
+            settings.output(),
+        ));
+        self.animation.status = None;
+    }
+}
@@ -0,0 +1,206 @@
+use crate::fractals::anti_aliasing::AntiAliasing;
+use crate::ui::fractal_app::FractalApp;
+use crate::utils::bookmarks::{Bookmark, BookmarkStore, THUMBNAIL_SIZE};
+use crate::utils::config;
+use crate::utils::export;
+use std::collections::HashMap;
+
+/// State of the bookmarks side panel. The store is read from disk the first time the panel opens.
+#[derive(Default)]
+pub struct BookmarksPanel {
+    pub open: bool,
+    pub store: Option<BookmarkStore>,
+    pub new_name: String,
+    pub status: Option<String>,
+    load_failed: bool, // Reading the store failed; not retried until the panel is opened again
+    thumbnails: HashMap<String, Option<egui::TextureHandle>>, // Thumbnail textures by file name, `None` if unreadable
+}
+
+impl BookmarksPanel {
+    /// Shows or hides the panel; opening it retries reading a store that could not be read.
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        if self.open {
+            self.load_failed = false;
+        }
+    }
+
+    /// Loads the bookmarks from the configuration directory unless already loaded, or unless
+    /// loading them already failed.
+    pub fn ensure_loaded(&mut self) {
+        if self.store.is_some() || self.load_failed {
+            return;
+        }
+        match config::config_dir() {
+            Some(dir) => match BookmarkStore::open(dir.join("bookmarks")) {
+                Ok(store) => self.store = Some(store),
+                Err(error) => self.status = Some(format!("❌ Could not read bookmarks: {error}")),
+            },
+            None => self.status = Some(String::from("❌ No configuration directory to keep bookmarks in")),
+        }
+        self.load_failed = self.store.is_none();
+    }
+
+    /// Returns the texture of a bookmark's thumbnail, loading it on first use. A missing or
+    /// unreadable file is remembered, so it is not opened again every frame.
+    fn thumbnail(&mut self, ctx: &egui::Context, bookmark: &Bookmark) -> Option<egui::TextureHandle> {
+        let name = bookmark.thumbnail.as_ref()?;
+        if let Some(texture) = self.thumbnails.get(name) {
+            return texture.clone();
+        }
+
+        let path = self.store.as_ref()?.thumbnail_path(bookmark);
+        let texture = path.and_then(|path| image::open(path).ok()).map(|image| {
+            let image = image.into_rgba8();
+            let size = [image.width() as usize, image.height() as usize];
+            ctx.load_texture(
+                format!("bookmark_{name}"),
+                egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw()),
+                egui::TextureOptions::default(),
+            )
+        });
+        self.thumbnails.insert(name.clone(), texture.clone());
+        texture
+    }
+}
+
+impl FractalApp {
+    /// Returns the current location as a bookmark called `name` (without a thumbnail yet).
+    #[must_use]
+    pub fn current_bookmark(&self, name: &str) -> Bookmark {
+        Bookmark {
+            name: String::from(name),
+            fractal_type: self.fractal_type,
+            center: self.center,
+            zoom: self.zoom,
+            max_iterations: self.max_iterations,
+            precision_mode: self.precision_mode,
+            color_scheme: self.color_scheme,
+            julia_c: self.julia_c,
+            lyapunov_sequence: self.lyapunov_sequence,
+            thumbnail: None,
+        }
+    }
+
+    /// Restores the location saved in a bookmark.
+    pub fn apply_bookmark(&mut self, bookmark: &Bookmark) {
+        self.fractal_type = bookmark.fractal_type;
+        self.center = bookmark.center;
+        self.zoom = bookmark.zoom;
+        self.max_iterations = bookmark.max_iterations;
+        self.precision_mode = bookmark.precision_mode;
+        if self.color_scheme != bookmark.color_scheme || self.use_custom_gradient {
+            self.color_scheme = bookmark.color_scheme;
+            self.use_custom_gradient = false;
+        }
+        self.julia_c = bookmark.julia_c;
+        self.lyapunov_sequence = bookmark.lyapunov_sequence;
+        self.lyapunov_input = bookmark.lyapunov_sequence.to_string();
+        self.needs_update = true;
+    }
+
+    /// Saves the current location, with a freshly rendered thumbnail.
+    fn add_bookmark(&mut self) {
+        let name = match self.bookmarks.new_name.trim() {
+            "" => format!("{} @ {:.1e}×", self.fractal_type.name(), self.zoom),
+            name => String::from(name),
+        };
+        let mut params = self.render_params();
+        (params.width, params.height) = THUMBNAIL_SIZE;
+        params.anti_aliasing = AntiAliasing::Grid2x2;
+        let thumbnail = export::render_image(&params, &self.color_settings());
+        let bookmark = self.current_bookmark(&name);
+
+        if let Some(store) = &mut self.bookmarks.store {
+            match store.add(bookmark, Some(&thumbnail)) {
+                Ok(()) => {
+                    self.bookmarks.new_name.clear();
+                    self.bookmarks.status = None;
+                }
+                Err(error) => self.bookmarks.status = Some(format!("❌ Could not save bookmark: {error}")),
+            }
+        }
+    }
+
+    /// Shows the bookmarks side panel.
+    pub fn bookmarks_panel(&mut self, ctx: &egui::Context) {
+        self.bookmarks.ensure_loaded();
+        let mut add = false;
+        let mut restore: Option<Bookmark> = None;
+        let mut remove: Option<usize> = None;
+
+        egui::SidePanel::right("bookmarks_panel")
+            .resizable(true)
+            .default_width(220.0)
+            .show(ctx, |ui| {
+                ui.add_space(8.0);
+                ui.heading(egui::RichText::new("⭐ Bookmarks").size(16.0).strong());
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut self.bookmarks.new_name).hint_text("Name"));
+                    if ui
+                        .add_enabled(self.bookmarks.store.is_some(), egui::Button::new("➕ Save"))
+                        .on_hover_text("Bookmark the current view")
+                        .clicked()
+                    {
+                        add = true;
+                    }
+                });
+                if let Some(status) = &self.bookmarks.status {
+                    ui.label(status);
+                }
+                ui.separator();
+
+                let bookmarks = self.bookmarks.store.as_ref().map(|store| store.bookmarks.clone()).unwrap_or_default();
+                if bookmarks.is_empty() {
+                    ui.label(egui::RichText::new("No bookmarks yet").weak());
+                }
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for (index, bookmark) in bookmarks.iter().enumerate() {
+                        let thumbnail = self.bookmarks.thumbnail(ctx, bookmark);
+                        ui.horizontal(|ui| {
+                            let clicked = match &thumbnail {
+                                Some(texture) => ui
+                                    .add(egui::Button::image((texture.id(), egui::vec2(80.0, 50.0))))
+                                    .on_hover_text("Go to this location")
+                                    .clicked(),
+                                None => ui.button("Go").clicked(),
+                            };
+                            if clicked {
+                                restore = Some(bookmark.clone());
+                            }
+                            ui.vertical(|ui| {
+                                ui.label(egui::RichText::new(&bookmark.name).strong());
+                                ui.label(
+                                    egui::RichText::new(format!(
+                                        "{} · {:.2e}×",
+                                        bookmark.fractal_type.name(),
+                                        bookmark.zoom
+                                    ))
+                                    .small()
+                                    .weak(),
+                                );
+                                if ui.small_button("🗑").on_hover_text("Delete").clicked() {
+                                    remove = Some(index);
+                                }
+                            });
+                        });
+                        ui.add_space(4.0);
+                    }
+                });
+            });
+
+        if add {
+            self.add_bookmark();
+        }
+        if let Some(bookmark) = restore {
+            self.apply_bookmark(&bookmark);
+        }
+        if let Some(index) = remove
+            && let Some(store) = &mut self.bookmarks.store
+            && let Err(error) = store.remove(index)
+        {
+            self.bookmarks.status = Some(format!("❌ Could not delete bookmark: {error}"));
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use crate::density::buddhabrot::{self, BuddhabrotSettings, OrbitDensity};
+use crate::density::density_map::{DensityMap, ToneMapping};
+use crate::ui::fractal_app::FractalApp;
+use egui::TextureOptions;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Samples taken per pass of the background accumulation.
+const SAMPLES_PER_PASS: u64 = 250_000;
+
+/// How often the plot on the canvas is redrawn while samples accumulate.
+const REDRAW_INTERVAL: Duration = Duration::from_millis(250);
+
+/// An orbit density plot accumulating on a background thread until dropped.
+struct DensityJob {
+    settings: BuddhabrotSettings,
+    map: Arc<DensityMap>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl Drop for DensityJob {
+    /// Stops the background thread.
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// State of the "Orbit Density" window and of the plot it draws on the canvas.
+pub struct DensityDialog {
+    pub open: bool,
+    pub kind: OrbitDensity,
+    pub limits: [u32; 3],
+    pub tone: ToneMapping,
+    pub exposure: f32,
+    pub path: String,
+    pub status: Option<String>,
+    job: Option<DensityJob>,
+    texture: Option<egui::TextureHandle>,
+    redrawn: Option<Instant>, // When the texture was last made from the counts
+}
+
+impl Default for DensityDialog {
+    /// Creates a closed dialog for a square-root tone mapped Buddhabrot.
+    #[inline]
+    fn default() -> Self {
+        let settings = BuddhabrotSettings::default();
+        Self {
+            open: false,
+            kind: settings.kind,
+            limits: settings.limits,
+            tone: ToneMapping::default(),
+            exposure: 1.0,
+            path: String::from("buddhabrot.png"),
+            status: None,
+            job: None,
+            texture: None,
+            redrawn: None,
+        }
+    }
+}
+
+impl DensityDialog {
+    /// Returns `true` while a plot is accumulating and shown on the canvas.
+    #[inline]
+    #[must_use]
+    pub const fn is_running(&self) -> bool {
+        self.job.is_some()
+    }
+
+    /// Stops the plot, if one is running.
+    #[inline]
+    pub fn stop(&mut self) {
+        self.job = None;
+    }
+}
+
+impl FractalApp {
+    /// Returns the plot settings for the current view and dialog options.
+    #[must_use]
+    pub fn density_settings(&self) -> BuddhabrotSettings {
+        BuddhabrotSettings {
+            kind: self.density.kind,
+            center: self.center,
+            zoom: self.zoom,
+            width: self.image_size.0,
+            height: self.image_size.1,
+            limits: self.density.limits,
+            seed: 0,
+        }
+    }
+
+    /// Starts accumulating the plot of the current view, replacing any running plot on the canvas.
+    fn start_density(&mut self) {
+        self.stop_ifs();
+        let settings = self.density_settings();
+        let map = Arc::new(settings.new_map());
+        let cancel = Arc::new(AtomicBool::new(false));
+        {
+            let (settings, map, cancel) = (settings.clone(), Arc::clone(&map), Arc::clone(&cancel));
+            std::thread::spawn(move || {
+                let mut pass = 0;
+                while !cancel.load(Ordering::Relaxed) {
+                    buddhabrot::accumulate(&settings, &map, pass, SAMPLES_PER_PASS, &cancel);
+                    pass += 1;
+                }
+            });
+        }
+        self.density.job = Some(DensityJob { settings, map, cancel });
+        self.density.redrawn = None;
+    }
+
+    /// Returns the plot to draw in place of the fractal while one is running, restarting the
+    /// accumulation once the view or the plot settings change.
+    pub fn density_texture(&mut self, ctx: &egui::Context) -> Option<egui::TextureId> {
+        let changed = self.density.job.as_ref()?.settings != self.density_settings();
+        if changed && !self.is_dragging {
+            self.start_density();
+        }
+
+        let job = self.density.job.as_ref()?;
+        if self.density.redrawn.is_none_or(|redrawn| redrawn.elapsed() >= REDRAW_INTERVAL) {
+            let image = job.map.tone_map(self.density.tone, f64::from(self.density.exposure));
+            let size = [image.width() as usize, image.height() as usize];
+            let image = egui::ColorImage::from_rgb(size, image.as_raw());
+            self.density.texture = Some(ctx.load_texture("density", image, TextureOptions::default()));
+            self.density.redrawn = Some(Instant::now());
+        }
+        ctx.request_repaint_after(REDRAW_INTERVAL);
+        self.density.texture.as_ref().map(egui::TextureHandle::id)
+    }
+
+    /// Shows the orbit density window; closing it removes the plot from the canvas.
+    pub fn density_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.density.open;
+        let mut start = false;
+        let mut save = false;
+
+        egui::Window::new("✨ Orbit Density")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let density = &mut self.density;
+                ui.horizontal(|ui| {
+                    ui.label("Plot:");
+                    egui::ComboBox::from_id_salt("density_kind")
+                        .selected_text(density.kind.name())
+                        .show_ui(ui, |ui| {
+                            for kind in OrbitDensity::all() {
+                                ui.selectable_value(&mut density.kind, kind, kind.name());
+                            }
+                        });
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Iterations:");
+                    if density.kind == OrbitDensity::Nebulabrot {
+                        for (limit, channel) in density.limits.iter_mut().zip(["R ", "G ", "B "]) {
+                            ui.add(egui::DragValue::new(limit).range(10..=100_000).prefix(channel).speed(10.0));
+                        }
+                    } else {
+                        ui.add(egui::DragValue::new(&mut density.limits[0]).range(10..=100_000).speed(10.0));
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Tone:");
+                    egui::ComboBox::from_id_salt("density_tone")
+                        .selected_text(density.tone.name())
+                        .show_ui(ui, |ui| {
+                            for tone in ToneMapping::all() {
+                                if ui.selectable_value(&mut density.tone, tone, tone.name()).changed() {
+                                    density.redrawn = None;
+                                }
+                            }
+                        });
+                    if ui
+                        .add(egui::Slider::new(&mut density.exposure, 0.1..=10.0).text("Exposure").logarithmic(true))
+                        .changed()
+                    {
+                        density.redrawn = None;
+                    }
+                });
+
+                ui.label(
+                    egui::RichText::new("Drawn over the current view; moving the view starts a new plot")
+                        .small()
+                        .weak(),
+                );
+
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    if let Some(job) = &density.job {
+                        if ui.button("⏹ Stop").clicked() {
+                            density.job = None;
+                        } else {
+                            ui.spinner();
+                            ui.label(format!("{:.1} M samples", job.map.samples() as f64 / 1e6));
+                        }
+                    } else if ui.button("▶ Start").clicked() {
+                        start = true;
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("File:");
+                    ui.text_edit_singleline(&mut density.path);
+                    if ui
+                        .add_enabled(density.job.is_some() && !density.path.trim().is_empty(), egui::Button::new("💾 Save PNG"))
+                        .clicked()
+                    {
+                        save = true;
+                    }
+                });
+
+                if let Some(status) = &density.status {
+                    ui.label(status);
+                }
+            });
+
+        self.density.open = open;
+        if !open {
+            self.density.job = None;
+        }
+        if start {
+            self.density.status = None;
+            self.start_density();
+        }
+        if save && let Some(job) = &self.density.job {
+            let path = self.density.path.trim();
+            let image = job.map.tone_map(self.density.tone, f64::from(self.density.exposure));
+            self.density.status = Some(match image.save(path) {
+                Ok(()) => format!("✅ Saved {path} ({:.1} M samples)", job.map.samples() as f64 / 1e6),
+                Err(error) => format!("❌ {error}"),
+            });
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use crate::fractals::anti_aliasing::AntiAliasing;
+use crate::ui::fractal_app::FractalApp;
+use crate::utils::export;
+use crate::utils::poster;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::time::Duration;
+
+/// Largest export side, in pixels; sizes above `poster::DIRECT_RENDER_PIXELS` are rendered in bands.
+pub const MAX_EXPORT_SIZE: u32 = 100_000;
+
+/// An export rendering on a background thread.
+struct ExportJob {
+    bands: u32,              // Number of bands, or 0 when the image is rendered in one piece
+    progress: Arc<AtomicU32>, // Finished bands
+    cancel: Arc<AtomicBool>,
+    result: Receiver<Result<String, String>>,
+}
+
+/// State of the "Export PNG" window and of the export running in the background.
+pub struct ExportDialog {
+    pub open: bool,
+    pub width: u32,
+    pub height: u32,
+    pub anti_aliasing: AntiAliasing,
+    pub path: String,
+    pub status: Option<String>,
+    job: Option<ExportJob>,
+}
+
+impl Default for ExportDialog {
+    /// Creates a closed dialog targeting an adaptively anti-aliased Full HD `fractal.png`.
+    #[inline]
+    fn default() -> Self {
+        Self {
+            open: false,
+            width: 1920,
+            height: 1080,
+            anti_aliasing: AntiAliasing::Adaptive,
+            path: String::from("fractal.png"),
+            status: None,
+            job: None,
+        }
+    }
+}
+
+impl ExportDialog {
+    /// Returns `true` while an export is being rendered.
+    #[inline]
+    #[must_use]
+    pub const fn is_running(&self) -> bool {
+        self.job.is_some()
+    }
+}
+
+impl FractalApp {
+    /// Shows the export window and collects the result of a running export.
+    pub fn export_window(&mut self, ctx: &egui::Context) {
+        if let Some(job) = &self.export.job {
+            match job.result.try_recv() {
+                Ok(result) => {
+                    self.export.status = Some(result.unwrap_or_else(|error| format!("❌ {error}")));
+                    self.export.job = None;
+                }
+                Err(TryRecvError::Empty) => ctx.request_repaint_after(Duration::from_millis(100)),
+                Err(TryRecvError::Disconnected) => {
+                    self.export.status = Some(String::from("❌ Export thread stopped unexpectedly"));
+                    self.export.job = None;
+                }
+            }
+        }
+
+        let mut open = self.export.open;
+        let mut start = false;
+
+        egui::Window::new("🖼 Export PNG")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("W:");
+                    ui.add(
+                        egui::DragValue::new(&mut self.export.width)
+                            .range(16..=MAX_EXPORT_SIZE)
+                            .suffix(" px")
+                            .speed(10.0),
+                    );
+                    ui.label("H:");
+                    ui.add(
+                        egui::DragValue::new(&mut self.export.height)
+                            .range(16..=MAX_EXPORT_SIZE)
+                            .suffix(" px")
+                            .speed(10.0),
+                    );
+                    if ui.button("Match view").clicked() {
+                        (self.export.width, self.export.height) = self.image_size;
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Anti-aliasing:");
+                    egui::ComboBox::from_id_salt("export_anti_aliasing")
+                        .selected_text(self.export.anti_aliasing.name())
+                        .show_ui(ui, |ui| {
+                            for anti_aliasing in AntiAliasing::all() {
+                                ui.selectable_value(&mut self.export.anti_aliasing, anti_aliasing, anti_aliasing.name());
+                            }
+                        });
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("File:");
+                    ui.text_edit_singleline(&mut self.export.path);
+                });
+
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{} · {} mapping",
+                        if self.use_custom_gradient { "Custom gradient" } else { self.color_scheme.name() },
+                        self.color_mapping.name()
+                    ))
+                    .weak(),
+                );
+
+                if poster::needs_bands(self.export.width, self.export.height) {
+                    ui.label(
+                        egui::RichText::new(
+                            "Rendered in bands to keep memory bounded; an interrupted export resumes when started again",
+                        )
+                        .small()
+                        .weak(),
+                    );
+                }
+
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    let running = self.export.is_running();
+                    if ui
+                        .add_enabled(!running && !self.export.path.trim().is_empty(), egui::Button::new("💾 Export"))
+                        .clicked()
+                    {
+                        start = true;
+                    }
+                    match &self.export.job {
+                        Some(job) if job.bands > 0 => {
+                            let done = job.progress.load(Ordering::Relaxed);
+                            ui.add(
+                                egui::ProgressBar::new(done as f32 / job.bands as f32)
+                                    .text(format!("Band {done}/{}", job.bands))
+                                    .desired_width(200.0),
+                            );
+                            if ui.button("⏹ Cancel").clicked() {
+                                job.cancel.store(true, Ordering::Relaxed);
+                            }
+                        }
+                        Some(_) => {
+                            ui.spinner();
+                            ui.label("Rendering...");
+                        }
+                        None => {}
+                    }
+                });
+
+                if let Some(status) = &self.export.status {
+                    ui.label(status);
+                }
+            });
+
+        self.export.open = open;
+        if start {
+            self.start_export();
+        }
+    }
+
+    /// Renders the current view at the export resolution on a background thread.
+    fn start_export(&mut self) {
+        let mut params = self.render_params();
+        params.width = self.export.width;
+        params.height = self.export.height;
+        params.anti_aliasing = self.export.anti_aliasing;
+        let colors = self.color_settings();
+        let path = PathBuf::from(self.export.path.trim());
+
+        let band_height = poster::default_band_height(&params);
+        let bands = if poster::needs_bands(params.width, params.height) {
+            poster::band_count(params.height, band_height)
+        } else {
+            0
+        };
+        let progress = Arc::new(AtomicU32::new(0));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+        {
+            let (progress, cancel) = (Arc::clone(&progress), Arc::clone(&cancel));
+            std::thread::spawn(move || {
+                let result = if bands == 0 {
+                    export::render_png(&params, &colors, &path).map(|()| format!("✅ Saved {}", path.display()))
+                } else {
+                    poster::render_poster(&params, &colors, &path, band_height, &cancel, |done| {
+                        progress.store(done, Ordering::Relaxed);
+                    })
+                    .map(|summary| {
+                        if summary.complete {
+                            format!("✅ Saved {}", path.display())
+                        } else {
+                            String::from("⏹ Export cancelled; finished bands are kept for resuming")
+                        }
+                    })
+                };
+                let _ = sender.send(result.map_err(|error| error.to_string()));
+            });
+        }
+
+        self.export.job = Some(ExportJob {
+            bands,
+            progress,
+            cancel,
+            result: receiver,
+        });
+        self.export.status = None;
+    }
+}
@@ -1,10 +1,28 @@
 use crate::utils::precision_mode::PrecisionMode;
 use crate::fractals::fractal_type::FractalType;
-use crate::fractals::fractal_simd;
+use crate::fractals::anti_aliasing::AntiAliasing;
+use crate::fractals::lyapunov::LyapunovSequence;
+use crate::fractals::mariani_silver::RenderAlgorithm;
+use crate::fractals::render_cache::RenderCache;
+use crate::fractals::renderer::{self, IterationBuffer, RenderParams};
+use crate::ui::animation_dialog::AnimationDialog;
+use crate::ui::bookmarks_panel::BookmarksPanel;
+use crate::ui::density_dialog::DensityDialog;
+use crate::ui::export_dialog::ExportDialog;
+use crate::ui::goto_dialog::GoToDialog;
+use crate::ui::ifs_dialog::IfsDialog;
+use crate::ui::history::History;
+use crate::ui::keymap::Keyboard;
+use crate::ui::navigation::Navigation;
+use crate::ui::orbit_inspector::OrbitInspector;
+use crate::ui::overlays::Overlays;
+use crate::ui::julia_path_dialog::JuliaPathDialog;
+use crate::ui::scene_dialog::SceneDialog;
+use crate::utils::color_mapping::{ColorMapping, ColorSettings};
 use crate::utils::color_scheme::ColorScheme;
+use crate::utils::gradient::Gradient;
 use crate::utils::point::Point;
-use egui::{Color32, Vec2};
-use rayon::prelude::*;
+use egui::{TextureOptions, Vec2};
 
 /// The main application state for the fractal viewer.
 pub struct FractalApp {
@@ -13,13 +31,40 @@ pub struct FractalApp {
     pub center: Point,
     pub zoom: f64,
     pub julia_c: Point,
+    pub lyapunov_sequence: LyapunovSequence,
+    pub lyapunov_input: String, // Sequence being typed, applied once it parses
     pub needs_update: bool,
+    pub needs_recolor: bool,
+    pub iteration_buffer: Option<IterationBuffer>,
+    pub buffer_params: Option<RenderParams>, // View that `iteration_buffer` was computed for
+    pub render_cache: RenderCache,
     pub texture: Option<egui::TextureHandle>,
     pub image_size: (u32, u32),
     pub is_dragging: bool,
+    pub adjusting: bool, // The iteration or Julia constant controls are being dragged
     pub show_settings: bool,
     pub precision_mode: PrecisionMode,
+    pub anti_aliasing: AntiAliasing,
+    pub algorithm: RenderAlgorithm,
     pub color_scheme: ColorScheme,
+    pub color_mapping: ColorMapping,
+    pub palette_offset: f32,
+    pub custom_gradient: Gradient,
+    pub use_custom_gradient: bool,
+    pub show_gradient_editor: bool,
+    pub export: ExportDialog,
+    pub animation: AnimationDialog,
+    pub julia_path: JuliaPathDialog,
+    pub density: DensityDialog,
+    pub ifs: IfsDialog,
+    pub bookmarks: BookmarksPanel,
+    pub scene_dialog: SceneDialog,
+    pub history: History,
+    pub keyboard: Keyboard,
+    pub navigation: Navigation,
+    pub go_to: GoToDialog,
+    pub orbit_inspector: OrbitInspector,
+    pub overlays: Overlays,
 }
 
 impl Default for FractalApp {
@@ -32,220 +77,170 @@ impl Default for FractalApp {
             center: Point::new(-0.5, 0.0),
             zoom: 1.0,
             julia_c: Point::new(-0.7269, 0.1889),
+            lyapunov_sequence: LyapunovSequence::default(),
+            lyapunov_input: LyapunovSequence::default().to_string(),
             needs_update: true,
+            needs_recolor: false,
+            iteration_buffer: None,
+            buffer_params: None,
+            render_cache: RenderCache::default(),
             texture: None,
             image_size: (800, 600),
             is_dragging: false,
+            adjusting: false,
             show_settings: false,
             precision_mode: PrecisionMode::Fast,
+            anti_aliasing: AntiAliasing::Off,
+            algorithm: RenderAlgorithm::BruteForce,
             color_scheme: ColorScheme::default(),
+            color_mapping: ColorMapping::default(),
+            palette_offset: 0.0,
+            custom_gradient: Gradient::default(),
+            use_custom_gradient: false,
+            show_gradient_editor: false,
+            export: ExportDialog::default(),
+            animation: AnimationDialog::default(),
+            julia_path: JuliaPathDialog::default(),
+            density: DensityDialog::default(),
+            ifs: IfsDialog::default(),
+            bookmarks: BookmarksPanel::default(),
+            scene_dialog: SceneDialog::default(),
+            history: History::default(),
+            keyboard: Keyboard::default(),
+            navigation: Navigation::default(),
+            go_to: GoToDialog::default(),
+            orbit_inspector: OrbitInspector::default(),
+            overlays: Overlays::default(),
         }
     }
 }
 
 impl FractalApp {
+    /// Returns the parameters that determine the raw iteration counts of the current view.
+    #[inline]
+    #[must_use]
+    pub fn render_params(&self) -> RenderParams {
+        RenderParams {
+            fractal_type: self.fractal_type,
+            center: self.center,
+            zoom: self.zoom,
+            julia_c: self.julia_c,
+            lyapunov_sequence: self.lyapunov_sequence,
+            max_iterations: self.max_iterations,
+            precision_mode: self.precision_mode,
+            // Supersampling is skipped while dragging or animating to keep the view responsive
+            anti_aliasing: if self.is_dragging || self.navigation.is_animating() {
+                AntiAliasing::Off
+            } else {
+                self.anti_aliasing
+            },
+            algorithm: self.algorithm,
+            width: self.image_size.0,
+            height: self.image_size.1,
+        }
+    }
+
     /// Generates a fractal image based on the current settings.
-    /// Highly optimized with:
-    /// - Row-based parallelization
-    /// - SIMD vectorization (4x f32 or 2x f64 pixels per operation)
-    /// - Direct RGBA buffer generation
-    /// - FMA operations and loop unrolling
     #[inline]
     #[must_use]
     pub fn generate_fractal_image(&self) -> egui::ColorImage {
-        let width = self.image_size.0 as usize;
-        let height = self.image_size.1 as usize;
+        self.colorize(&renderer::compute_iterations(&self.render_params()))
+    }
 
-        if width == 0 || height == 0 {
-            return egui::ColorImage::new([1, 1], vec![Color32::BLACK; 1]);
+    /// Colors an iteration buffer with the current color settings, without touching any orbit.
+    #[inline]
+    #[must_use]
+    pub fn colorize(&self, buffer: &IterationBuffer) -> egui::ColorImage {
+        renderer::colorize(buffer, &self.build_palette(buffer))
+    }
+
+    /// Returns the color settings of the current view.
+    #[must_use]
+    pub fn color_settings(&self) -> ColorSettings {
+        // The editor keeps stops in the user's order; sampling needs them sorted
+        let gradient = self.use_custom_gradient.then(|| {
+            let mut gradient = self.custom_gradient.clone();
+            gradient.sort_stops();
+            gradient
+        });
+        ColorSettings {
+            scheme: self.color_scheme,
+            gradient,
+            mapping: self.color_mapping,
+            offset: self.palette_offset,
         }
+    }
+
+    /// Pre-computes the color palette once — avoids heavy trig per pixel.
+    #[must_use]
+    pub fn build_palette(&self, buffer: &IterationBuffer) -> Vec<egui::Color32> {
+        self.color_settings().build_palette(buffer)
+    }
 
-        let (x_scale, y_scale, x_min, y_min) = self.compute_scale();
-        let total_pixels = width * height;
-
-        // Pre-compute color palette once — avoids heavy trig per pixel
-        let palette = self.color_scheme.build_palette(self.max_iterations);
-
-        // Build Vec<Color32> directly — skips the rgba_buffer allocation and
-        // the from_rgba_unmultiplied copy at the end (saves ~1 full image copy)
-        let mut pixels = vec![Color32::BLACK; total_pixels];
-
-        // Cache hot fields as local copies — avoids pointer dereferences through `self`
-        // inside the parallel inner loop (120K+ iterations for a typical render)
-        let x_scale_f32 = x_scale as f32;
-        let x_min_f32 = x_min as f32;
-        let max_iterations = self.max_iterations;
-        let fractal_type = self.fractal_type;
-        let precision_mode = self.precision_mode;
-        let julia_cx_f32 = self.julia_c.x as f32;
-        let julia_cy_f32 = self.julia_c.y as f32;
-        let julia_cx = self.julia_c.x;
-        let julia_cy = self.julia_c.y;
-        let julia_c = &self.julia_c;
-
-        // Row-based parallelization with SIMD optimization
-        pixels
-            .par_chunks_mut(width)
-            .enumerate()
-            .for_each(|(y, row)| {
-                let cy = (y as f64).mul_add(y_scale, y_min);
-
-                match precision_mode {
-                    PrecisionMode::Fast => {
-                        // Incremental x-coordinate in f32 — avoids 4 f64 mul_add + cast per group
-                        let cy_f32 = cy as f32;
-                        let dx = x_scale_f32;
-                        let dx4 = dx * 4.0;
-                        let mut cx_base = x_min_f32;
-                        let mut x = 0;
-
-                        // Process in chunks of 4 with SIMD
-                        while x + 4 <= width {
-                            let cx_arr = [cx_base, cx_base + dx, cx_base + dx + dx, cx_base + dx + dx + dx];
-                            let cy_arr = [cy_f32; 4];
-
-                            let iterations = match fractal_type {
-                                FractalType::Mandelbrot => {
-                                    fractal_simd::mandelbrot_simd_f32(&cx_arr, &cy_arr, max_iterations)
-                                }
-                                FractalType::Julia => {
-                                    fractal_simd::julia_simd_f32(
-                                        &cx_arr,
-                                        &cy_arr,
-                                        julia_cx_f32,
-                                        julia_cy_f32,
-                                        max_iterations,
-                                    )
-                                }
-                                FractalType::BurningShip => {
-                                    fractal_simd::burning_ship_simd_f32(&cx_arr, &cy_arr, max_iterations)
-                                }
-                                FractalType::Tricorn => {
-                                    fractal_simd::tricorn_simd_f32(&cx_arr, &cy_arr, max_iterations)
-                                }
-                            };
-
-                            // Write 4 Color32 directly — no byte unpacking
-                            row[x]     = palette[iterations[0] as usize];
-                            row[x + 1] = palette[iterations[1] as usize];
-                            row[x + 2] = palette[iterations[2] as usize];
-                            row[x + 3] = palette[iterations[3] as usize];
-
-                            cx_base += dx4;
-                            x += 4;
-                        }
-
-                        // Handle remaining pixels (< 4)
-                        while x < width {
-                            let iterations = fractal_type.iterations(
-                                cx_base as f64,
-                                cy,
-                                max_iterations,
-                                &julia_c,
-                                precision_mode,
-                            );
-                            row[x] = palette[iterations as usize];
-                            cx_base += dx;
-                            x += 1;
-                        }
-                    }
-                    PrecisionMode::High => {
-                        // Incremental x-coordinate in f64
-                        let dx = x_scale;
-                        let dx2 = dx * 2.0;
-                        let mut cx_base = x_min;
-                        let mut x = 0;
-
-                        // Process in chunks of 2 with SIMD
-                        while x + 2 <= width {
-                            let cx_arr = [cx_base, cx_base + dx];
-                            let cy_arr = [cy; 2];
-
-                            let iterations = match fractal_type {
-                                FractalType::Mandelbrot => {
-                                    fractal_simd::mandelbrot_simd_f64(&cx_arr, &cy_arr, max_iterations)
-                                }
-                                FractalType::Julia => {
-                                    fractal_simd::julia_simd_f64(
-                                        &cx_arr,
-                                        &cy_arr,
-                                        julia_cx,
-                                        julia_cy,
-                                        max_iterations,
-                                    )
-                                }
-                                FractalType::BurningShip => {
-                                    fractal_simd::burning_ship_simd_f64(&cx_arr, &cy_arr, max_iterations)
-                                }
-                                FractalType::Tricorn => {
-                                    fractal_simd::tricorn_simd_f64(&cx_arr, &cy_arr, max_iterations)
-                                }
-                            };
-
-                            row[x]     = palette[iterations[0] as usize];
-                            row[x + 1] = palette[iterations[1] as usize];
-
-                            cx_base += dx2;
-                            x += 2;
-                        }
-
-                        // Handle remaining pixel
-                        while x < width {
-                            let iterations = fractal_type.iterations(
-                                cx_base,
-                                cy,
-                                max_iterations,
-                                &julia_c,
-                                precision_mode,
-                            );
-                            row[x] = palette[iterations as usize];
-                            cx_base += dx;
-                            x += 1;
-                        }
-                    }
-                    #[cfg(feature = "f128")]
-                    PrecisionMode::UltraHigh => {
-                        let mut cx = x_min;
-                        for pixel in row.iter_mut() {
-                            let iterations = fractal_type.iterations(
-                                cx,
-                                cy,
-                                max_iterations,
-                                &julia_c,
-                                precision_mode,
-                            );
-                            *pixel = palette[iterations as usize];
-                            cx += x_scale;
-                        }
-                    }
+    /// Brings the displayed texture up to date: orbits are recomputed only when the view
+    /// changed (`needs_update`) to one missing from the render cache, and only for the newly
+    /// exposed strips after a whole-pixel pan. A palette change (`needs_recolor`) reuses the
+    /// retained buffer.
+    pub fn refresh_texture(&mut self, ctx: &egui::Context) {
+        if self.needs_update {
+            let params = self.render_params();
+            // The previous frame is only shifted when the view is not cached
+            let buffer = self.render_cache.get(&params).or_else(|| match (&self.iteration_buffer, &self.buffer_params) {
+                (Some(previous), Some(previous_params)) => renderer::pan_iterations(previous, previous_params, &params),
+                _ => None,
+            });
+            let buffer = buffer.unwrap_or_else(|| {
+                let buffer = renderer::compute_iterations(&params);
+                // Views passed through while dragging, animating or adjusting a control would only
+                // push out useful ones
+                let passing = self.is_dragging
+                    || self.adjusting
+                    || self.navigation.is_animating()
+                    || self.keyboard.navigating
+                    || self.julia_path.playing;
+                if !passing {
+                    self.render_cache.insert(&params, buffer.clone());
                 }
+                buffer
             });
+            self.iteration_buffer = Some(buffer);
+            self.buffer_params = Some(params);
+            self.needs_update = false;
+            self.needs_recolor = true;
+        }
 
-        // Construct ColorImage directly from Vec<Color32> — no extra allocation/copy
-        egui::ColorImage {
-            size: [width, height],
-            source_size: egui::Vec2::new(width as f32, height as f32),
-            pixels,
+        if self.needs_recolor
+            && let Some(buffer) = &self.iteration_buffer
+        {
+            let image = self.colorize(buffer);
+            self.texture = Some(ctx.load_texture("fractal", image, TextureOptions::default()));
+            self.needs_recolor = false;
         }
     }
 
-    /// Computes the scale factors and min/max coordinates for the fractal view.
-    #[inline(always)]
-    fn compute_scale(&self) -> (f64, f64, f64, f64) {
-        let width: u32 = self.image_size.0;
-        let height: u32 = self.image_size.1;
-
-        let aspect_ratio: f64 = f64::from(width) / f64::from(height);
-        let zoom_factor: f64 = 2.0_f64 / self.zoom;
-        let x_min: f64 = zoom_factor.mul_add(-aspect_ratio, self.center.x);
-        let x_max: f64 = zoom_factor.mul_add(aspect_ratio, self.center.x);
-        let y_min: f64 = self.center.y - zoom_factor;
-        let y_max: f64 = self.center.y + zoom_factor;
-
-        let x_scale: f64 = (x_max - x_min) / f64::from(width);
-        let y_scale: f64 = (y_max - y_min) / f64::from(height);
+    /// Converts a screen position inside `image_rect` to a point of the complex plane.
+    pub fn screen_to_complex(&self, position: egui::Pos2, image_rect: egui::Rect) -> Point {
+        let aspect_ratio = f64::from(image_rect.width()) / f64::from(image_rect.height());
+        let extent = 4.0 / self.zoom;
+        let norm_x = f64::from((position.x - image_rect.min.x) / image_rect.width()) - 0.5;
+        let norm_y = f64::from((position.y - image_rect.min.y) / image_rect.height()) - 0.5;
+        Point::new(
+            (norm_x * aspect_ratio).mul_add(extent, self.center.x),
+            norm_y.mul_add(extent, self.center.y),
+        )
+    }
 
-        (x_scale, y_scale, x_min, y_min)
+    /// Converts a point of the complex plane to a screen position relative to `image_rect`.
+    #[must_use]
+    pub fn complex_to_screen(&self, point: Point, image_rect: egui::Rect) -> egui::Pos2 {
+        let aspect_ratio = f64::from(image_rect.width()) / f64::from(image_rect.height());
+        let extent = 4.0 / self.zoom;
+        let norm_x = (point.x - self.center.x) / (extent * aspect_ratio) + 0.5;
+        let norm_y = (point.y - self.center.y) / extent + 0.5;
+        egui::pos2(
+            (norm_x as f32).mul_add(image_rect.width(), image_rect.min.x),
+            (norm_y as f32).mul_add(image_rect.height(), image_rect.min.y),
+        )
     }
 
     /// Handles mouse input for zooming and panning the fractal view.
@@ -256,6 +251,7 @@ impl FractalApp {
         if response.hovered() {
             let scroll_delta = response.ctx.input(|i| i.smooth_scroll_delta.y);
             if scroll_delta != 0.0 {
+                self.navigation.transition = None;
                 let zoom_factor = if scroll_delta > 0.0 {
                     ZOOM_STEP
                 } else {
@@ -294,51 +290,48 @@ impl FractalApp {
             }
         }
 
+        // Shift/ctrl-drag draws a zoom box instead of panning
+        let boxing = self.handle_box_zoom(response, image_rect);
+
         // Handle panning with mouse drag
-        if response.dragged() {
+        if response.dragged() && !boxing {
+            self.navigation.transition = None;
             let drag_delta = response.drag_delta();
             if response.drag_delta() != Vec2::ZERO {
                 self.is_dragging = true;
 
-                // Convert pixel drag to complex plane movement
-                let aspect_ratio = f64::from(image_rect.width()) / f64::from(image_rect.height());
-                let zoom_extent = 2.0 / self.zoom;
-
-                let dx = -(f64::from(drag_delta.x) / f64::from(image_rect.width()))
-                    * zoom_extent
-                    * aspect_ratio
-                    * 2.0;
-                let dy =
-                    -(f64::from(drag_delta.y) / f64::from(image_rect.height())) * zoom_extent * 2.0;
-
-                self.center.x += dx;
-                self.center.y += dy;
-                self.needs_update = true;
+                // Move by whole pixels of the image, carrying the fraction over to the next
+                // frame, so the previous frame can be shifted instead of computed again
+                let (x_scale, y_scale, _, _) = self.render_params().compute_scale();
+                let pixels_per_point = egui::vec2(
+                    self.image_size.0 as f32 / image_rect.width(),
+                    self.image_size.1 as f32 / image_rect.height(),
+                );
+                let pixels = drag_delta * pixels_per_point + self.navigation.pan_remainder;
+                let whole = pixels.round();
+                self.navigation.pan_remainder = pixels - whole;
+
+                if whole != Vec2::ZERO {
+                    self.center.x -= f64::from(whole.x) * x_scale;
+                    self.center.y -= f64::from(whole.y) * y_scale;
+                    self.needs_update = true;
+                }
             }
         } else {
+            // Drag frames skip supersampling: render the final view once at full quality
+            if self.is_dragging && self.anti_aliasing != AntiAliasing::Off {
+                self.needs_update = true;
+            }
             self.is_dragging = false;
+            self.navigation.pan_remainder = Vec2::ZERO;
         }
 
         // Handle double-click to zoom in
         if response.double_clicked()
             && let Some(click_pos) = response.interact_pointer_pos()
         {
-            let rel_pos = click_pos - image_rect.min;
-            let norm_x = rel_pos.x / image_rect.width();
-            let norm_y = rel_pos.y / image_rect.height();
-
-            // Convert to complex coordinates
-            let aspect_ratio = f64::from(image_rect.width()) / f64::from(image_rect.height());
-            let zoom_extent = 2.0_f64 / self.zoom;
-
-            let new_center_x = ((f64::from(norm_x) - 0.5_f64) * zoom_extent * aspect_ratio)
-                .mul_add(2.0_f64, self.center.x);
-            let new_center_y =
-                ((f64::from(norm_y) - 0.5_f64) * zoom_extent).mul_add(2.0_f64, self.center.y);
-
-            self.center = Point::new(new_center_x, new_center_y);
-            self.zoom *= 2.0_f64;
-            self.needs_update = true;
+            let center = self.screen_to_complex(click_pos, image_rect);
+            self.go_to_view(center, self.zoom * self.navigation.double_click_zoom);
         }
     }
 }
@@ -346,6 +339,8 @@ impl FractalApp {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::animation::keyframe::Keyframe;
+    use crate::utils::scene::Scene;
 
     #[test]
     fn test_default_fractal_app() {
@@ -355,12 +350,205 @@ mod tests {
         assert_eq!(app.center, Point::new(-0.5, 0.0));
         assert_eq!(app.zoom, 1.0);
         assert_eq!(app.julia_c, Point::new(-0.7269, 0.1889));
+        assert_eq!(app.lyapunov_sequence, LyapunovSequence::default());
+        assert_eq!(app.lyapunov_input, "AB");
         assert!(app.needs_update);
+        assert!(!app.needs_recolor);
+        assert!(app.iteration_buffer.is_none());
+        assert!(app.render_cache.is_empty());
+        assert!(app.buffer_params.is_none());
         assert!(app.texture.is_none());
         assert_eq!(app.image_size, (800, 600));
         assert!(!app.is_dragging);
+        assert!(!app.adjusting);
         assert!(!app.show_settings);
         assert_eq!(app.precision_mode, PrecisionMode::Fast);
+        assert_eq!(app.anti_aliasing, AntiAliasing::Off);
+        assert_eq!(app.algorithm, RenderAlgorithm::BruteForce);
         assert_eq!(app.color_scheme, ColorScheme::default());
+        assert_eq!(app.color_mapping, ColorMapping::Linear);
+        assert_eq!(app.palette_offset, 0.0);
+        assert!(!app.use_custom_gradient);
+        assert!(!app.show_gradient_editor);
+        assert!(!app.export.open);
+        assert!(!app.export.is_running());
+        assert!(!app.density.open);
+        assert!(!app.density.is_running());
+        assert!(!app.ifs.open);
+        assert!(!app.ifs.is_running());
+        assert!(app.animation.keyframes.is_empty());
+        assert!(!app.animation.is_running());
+        assert!(!app.bookmarks.open);
+        assert!(app.bookmarks.store.is_none());
+        assert!(app.scene_dialog.action.is_none());
+        assert!(app.history.entries().is_empty());
+        assert!(!app.keyboard.show_help);
+        assert_eq!(app.navigation.double_click_zoom, 2.0);
+        assert!(!app.go_to.open);
+        assert!(app.orbit_inspector.orbit.is_none());
+        assert!(!app.overlays.grid);
+    }
+
+    #[test]
+    fn test_revisited_views_come_from_the_cache() {
+        let ctx = egui::Context::default();
+        let mut app = FractalApp {
+            image_size: (40, 30),
+            ..FractalApp::default()
+        };
+        app.refresh_texture(&ctx);
+        let mandelbrot = app.iteration_buffer.clone();
+
+        app.fractal_type = FractalType::Tricorn;
+        app.needs_update = true;
+        app.refresh_texture(&ctx);
+        app.fractal_type = FractalType::Mandelbrot;
+        app.needs_update = true;
+        app.refresh_texture(&ctx);
+
+        assert_eq!(app.iteration_buffer, mandelbrot);
+        assert_eq!((app.render_cache.hits, app.render_cache.misses), (1, 2));
+        assert_eq!(app.render_cache.len(), 2);
+    }
+
+    #[test]
+    fn test_passing_views_are_not_cached() {
+        let ctx = egui::Context::default();
+        let mut app = FractalApp {
+            image_size: (40, 30),
+            fractal_type: FractalType::Julia,
+            ..FractalApp::default()
+        };
+        app.julia_path.playing = true;
+        app.refresh_texture(&ctx);
+        app.julia_path.playing = false;
+        app.adjusting = true;
+        app.julia_c.x += 0.01;
+        app.needs_update = true;
+        app.refresh_texture(&ctx);
+        assert!(app.render_cache.is_empty());
+    }
+
+    #[test]
+    fn test_colorize_reuses_iteration_buffer() {
+        let mut app = FractalApp {
+            image_size: (40, 30),
+            ..FractalApp::default()
+        };
+        let buffer = renderer::compute_iterations(&app.render_params());
+
+        app.color_scheme = ColorScheme::Hot;
+        let hot = app.colorize(&buffer);
+        app.color_scheme = ColorScheme::Cool;
+        let cool = app.colorize(&buffer);
+
+        assert_eq!(hot.size, [40, 30]);
+        assert_ne!(hot.pixels, cool.pixels);
+        assert_eq!(cool.pixels, app.generate_fractal_image().pixels);
+    }
+
+    #[test]
+    fn test_keyframe_round_trip() {
+        let mut app = FractalApp::default();
+        let keyframe = Keyframe {
+            center: Point::new(0.25, -0.1),
+            zoom: 42.0,
+            julia_c: Point::new(0.3, 0.5),
+            palette_offset: 1.25,
+        };
+        app.needs_update = false;
+        app.apply_keyframe(&keyframe);
+        assert!(app.needs_update);
+        assert_eq!(app.current_keyframe(), Keyframe { palette_offset: 0.25, ..keyframe });
+    }
+
+    #[test]
+    fn test_bookmark_round_trip() {
+        let source = FractalApp {
+            fractal_type: FractalType::BurningShip,
+            center: Point::new(-1.76, -0.03),
+            zoom: 120.0,
+            max_iterations: 900,
+            precision_mode: PrecisionMode::High,
+            color_scheme: ColorScheme::Inferno,
+            lyapunov_sequence: "AABAB".parse().unwrap(),
+            ..FractalApp::default()
+        };
+        let bookmark = source.current_bookmark("Ship");
+
+        let mut app = FractalApp {
+            use_custom_gradient: true,
+            ..FractalApp::default()
+        };
+        app.apply_bookmark(&bookmark);
+        assert_eq!(app.current_bookmark("Ship"), bookmark);
+        assert_eq!(app.lyapunov_input, "AABAB");
+        assert!(!app.use_custom_gradient);
+        assert!(app.needs_update);
+    }
+
+    #[test]
+    fn test_scene_round_trip() {
+        let source = FractalApp {
+            fractal_type: FractalType::Julia,
+            center: Point::new(0.123_456_789_012_345_67, -0.3),
+            zoom: 5.5e7,
+            julia_c: Point::new(-0.8, 0.156),
+            anti_aliasing: AntiAliasing::Adaptive,
+            algorithm: RenderAlgorithm::MarianiSilver,
+            color_mapping: ColorMapping::Histogram,
+            palette_offset: 0.4,
+            use_custom_gradient: true,
+            ..FractalApp::default()
+        };
+        let scene = Scene::from_json(&source.scene().to_json()).unwrap();
+
+        let mut app = FractalApp::default();
+        app.apply_scene(&scene);
+        assert_eq!(app.scene(), source.scene());
+        assert!(app.use_custom_gradient);
+    }
+
+    #[test]
+    fn test_lyapunov_scene_round_trip() {
+        let source = FractalApp {
+            fractal_type: FractalType::Lyapunov,
+            center: Point::new(3.4, 3.0),
+            lyapunov_sequence: "BBBBBBAAAAAA".parse().unwrap(),
+            ..FractalApp::default()
+        };
+        let scene = Scene::from_json(&source.scene().to_json()).unwrap();
+
+        let mut app = FractalApp::default();
+        app.apply_scene(&scene);
+        assert_eq!(app.scene(), source.scene());
+        assert_eq!(app.lyapunov_input, "BBBBBBAAAAAA");
+    }
+
+    #[test]
+    fn test_screen_complex_round_trip() {
+        let app = FractalApp {
+            center: Point::new(-0.75, 0.1),
+            zoom: 8.0,
+            ..FractalApp::default()
+        };
+        let rect = egui::Rect::from_min_size(egui::pos2(10.0, 20.0), egui::vec2(400.0, 300.0));
+        assert_eq!(app.screen_to_complex(rect.center(), rect), app.center);
+
+        let corner = app.screen_to_complex(rect.min, rect);
+        assert!((corner.y - (0.1 - 0.25)).abs() < 1e-9);
+        let back = app.complex_to_screen(corner, rect);
+        assert!((back - rect.min).length() < 1e-3);
+    }
+
+    #[test]
+    fn test_render_params_skip_anti_aliasing_while_dragging() {
+        let mut app = FractalApp {
+            anti_aliasing: AntiAliasing::Grid3x3,
+            ..FractalApp::default()
+        };
+        assert_eq!(app.render_params().anti_aliasing, AntiAliasing::Grid3x3);
+        app.is_dragging = true;
+        assert_eq!(app.render_params().anti_aliasing, AntiAliasing::Off);
     }
 }
@@ -1,11 +1,15 @@
 use crate::utils::precision_mode::PrecisionMode;
+use crate::fractals::anti_aliasing::AntiAliasing;
 use crate::fractals::fractal_type::FractalType;
+use crate::fractals::lyapunov::LyapunovSequence;
+use crate::fractals::mariani_silver::RenderAlgorithm;
+use crate::utils::color_mapping::ColorMapping;
 use crate::utils::color_scheme::ColorScheme;
 use crate::utils::point::Point;
 use crate::ui::fractal_app::FractalApp;
+use crate::ui::scene_dialog::SceneAction;
 use eframe::emath::{Pos2, Rect, Vec2};
 use eframe::epaint::Color32;
-use egui::{ColorImage, TextureOptions};
 
 impl eframe::App for FractalApp {
     /// Called to update the UI and handle events.
@@ -14,12 +18,35 @@ impl eframe::App for FractalApp {
         // Top menu bar
         egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
             egui::MenuBar::new().ui(ui, |ui| {
+                ui.menu_button("File", |ui| {
+                    if ui.button("📂 Open Scene...").clicked() {
+                        self.scene_dialog.action = Some(SceneAction::Open);
+                        self.scene_dialog.status = None;
+                        ui.close();
+                    }
+                    if ui.button("💾 Save Scene...").clicked() {
+                        self.scene_dialog.action = Some(SceneAction::Save);
+                        self.scene_dialog.status = None;
+                        ui.close();
+                    }
+                    ui.separator();
+                    if ui.button("🖼 Export PNG...").clicked() {
+                        self.export.open = true;
+                        ui.close();
+                    }
+                    if ui.button("🎬 Animation...").clicked() {
+                        self.animation.open = true;
+                        ui.close();
+                    }
+                });
+
                 ui.menu_button("Fractal", |ui| {
                     for fractal_type in [
                         FractalType::Mandelbrot,
                         FractalType::Julia,
                         FractalType::BurningShip,
                         FractalType::Tricorn,
+                        FractalType::Lyapunov,
                     ] {
                         if ui
                             .selectable_label(
@@ -37,13 +64,26 @@ impl eframe::App for FractalApp {
                             ui.close();
                         }
                     }
+
+                    ui.separator();
+
+                    if ui.button("🌀 Julia Path...").clicked() {
+                        self.julia_path.open = true;
+                        ui.close();
+                    }
+                    if ui.button("✨ Orbit Density...").clicked() {
+                        self.density.open = true;
+                        ui.close();
+                    }
+                    if ui.button("🌿 IFS & Flames...").clicked() {
+                        self.ifs.open = true;
+                        ui.close();
+                    }
                 });
 
                 ui.menu_button("Color", |ui| {
                     if ui.button("Reset View").clicked() {
-                        self.center = self.fractal_type.default_center();
-                        self.zoom = 1.0;
-                        self.needs_update = true;
+                        self.go_to_view(self.fractal_type.default_center(), 1.0);
                         ui.close();
                     }
 
@@ -58,10 +98,51 @@ impl eframe::App for FractalApp {
                             .clicked()
                         {
                             self.color_scheme = color_scheme;
-                            self.needs_update = true;
+                            self.use_custom_gradient = false;
+                            self.needs_recolor = true;
                             ui.close();
                         }
                     }
+
+                    ui.separator();
+
+                    if ui
+                        .selectable_label(self.use_custom_gradient, "🎨 Custom Gradient...")
+                        .clicked()
+                    {
+                        self.show_gradient_editor = true;
+                        ui.close();
+                    }
+
+                    ui.menu_button("Mapping", |ui| {
+                        for color_mapping in ColorMapping::all() {
+                            if ui
+                                .selectable_label(
+                                    self.color_mapping == color_mapping,
+                                    color_mapping.name(),
+                                )
+                                .clicked()
+                            {
+                                self.color_mapping = color_mapping;
+                                self.needs_recolor = true;
+                                ui.close();
+                            }
+                        }
+                    });
+                });
+
+                ui.menu_button("View", |ui| {
+                    self.overlay_menu(ui);
+                });
+
+                ui.menu_button("Bookmarks", |ui| {
+                    if ui
+                        .selectable_label(self.bookmarks.open, "⭐ Show Bookmarks")
+                        .clicked()
+                    {
+                        self.bookmarks.toggle();
+                        ui.close();
+                    }
                 });
 
                 ui.menu_button("Settings", |ui| {
@@ -69,8 +150,21 @@ impl eframe::App for FractalApp {
                         self.show_settings = !self.show_settings;
                         ui.close();
                     }
+                    if ui
+                        .selectable_label(self.orbit_inspector.open, "🔍 Orbit Inspector")
+                        .clicked()
+                    {
+                        self.orbit_inspector.open = !self.orbit_inspector.open;
+                        ui.close();
+                    }
+                    if ui.button("⌨ Keyboard Shortcuts...").clicked() {
+                        self.keyboard.show_help = true;
+                        ui.close();
+                    }
                 });
 
+                ui.separator();
+                self.history_buttons(ui);
                 ui.separator();
 
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
@@ -83,6 +177,7 @@ impl eframe::App for FractalApp {
             });
         });
 
+        self.adjusting = false;
         if self.show_settings {
             egui::SidePanel::left("settings_panel")
                 .resizable(true)
@@ -106,14 +201,13 @@ impl eframe::App for FractalApp {
                         ui.add_space(6.0);
 
                         ui.label(egui::RichText::new("Quality").size(12.0));
-                        if ui
-                            .add(
-                                egui::Slider::new(&mut self.max_iterations, 10..=3000)
-                                    .text("Iterations")
-                                    .logarithmic(true),
-                            )
-                            .changed()
-                        {
+                        let response = ui.add(
+                            egui::Slider::new(&mut self.max_iterations, 10..=3000)
+                                .text("Iterations")
+                                .logarithmic(true),
+                        );
+                        self.adjusting |= response.dragged();
+                        if response.changed() {
                             self.needs_update = true;
                         }
 
@@ -185,6 +279,92 @@ impl eframe::App for FractalApp {
                             }
                         });
 
+                        ui.add_space(6.0);
+                        ui.label(egui::RichText::new("Anti-aliasing").size(12.0));
+                        egui::ComboBox::from_id_salt("anti_aliasing")
+                            .selected_text(self.anti_aliasing.name())
+                            .show_ui(ui, |ui| {
+                                for anti_aliasing in AntiAliasing::all() {
+                                    if ui
+                                        .selectable_value(&mut self.anti_aliasing, anti_aliasing, anti_aliasing.name())
+                                        .changed()
+                                    {
+                                        self.needs_update = true;
+                                    }
+                                }
+                            });
+
+                        ui.add_space(6.0);
+                        ui.label(egui::RichText::new("Algorithm").size(12.0));
+                        egui::ComboBox::from_id_salt("algorithm")
+                            .selected_text(self.algorithm.name())
+                            .show_ui(ui, |ui| {
+                                for algorithm in RenderAlgorithm::all() {
+                                    if ui
+                                        .selectable_value(&mut self.algorithm, algorithm, algorithm.name())
+                                        .on_hover_text(match algorithm {
+                                            RenderAlgorithm::BruteForce => "Iterate every pixel",
+                                            RenderAlgorithm::MarianiSilver => {
+                                                "Fill rectangles with a uniform border - faster on large interior regions"
+                                            }
+                                            RenderAlgorithm::Perturbation => {
+                                                "Iterate offsets from a reference orbit, skipping shared iterations - deep Mandelbrot zooms; other fractals fall back to brute force"
+                                            }
+                                        })
+                                        .changed()
+                                    {
+                                        self.needs_update = true;
+                                    }
+                                }
+                            });
+                        if self.algorithm == RenderAlgorithm::Perturbation && self.fractal_type != FractalType::Mandelbrot {
+                            ui.label(egui::RichText::new("Brute force for this fractal").weak());
+                        }
+
+                        ui.add_space(6.0);
+                        ui.label(egui::RichText::new("Render Cache").size(12.0));
+                        let mut capacity_mib = self.render_cache.capacity() / (1024 * 1024);
+                        if ui
+                            .add(egui::Slider::new(&mut capacity_mib, 0..=2048).text("MiB").logarithmic(true))
+                            .on_hover_text("Memory kept for recently rendered views, so going back to one is instant")
+                            .changed()
+                        {
+                            self.render_cache.set_capacity(capacity_mib * 1024 * 1024);
+                        }
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "{} view(s), {:.1} MiB · {} hit(s)",
+                                    self.render_cache.len(),
+                                    self.render_cache.size() as f64 / (1024.0 * 1024.0),
+                                    self.render_cache.hits
+                                ))
+                                .small()
+                                .weak(),
+                            );
+                            if ui.small_button("Clear").clicked() {
+                                self.render_cache.clear();
+                            }
+                        });
+
+                        ui.add_space(6.0);
+                        ui.label(egui::RichText::new("Color Mapping").size(12.0));
+                        ui.horizontal(|ui| {
+                            for color_mapping in ColorMapping::all() {
+                                if ui
+                                    .selectable_value(&mut self.color_mapping, color_mapping, color_mapping.name())
+                                    .clicked()
+                                {
+                                    self.needs_recolor = true;
+                                }
+                            }
+                        });
+                        if ui
+                            .add(egui::Slider::new(&mut self.palette_offset, 0.0..=0.99).text("Palette Offset"))
+                            .changed()
+                        {
+                            self.needs_recolor = true;
+                        }
                     });
 
                     if self.fractal_type == FractalType::Julia {
@@ -200,28 +380,26 @@ impl eframe::App for FractalApp {
                             ui.label(egui::RichText::new("Constant (c)").size(12.0));
                             ui.horizontal(|ui| {
                                 ui.label("Re:");
-                                if ui
-                                    .add(
-                                        egui::DragValue::new(&mut self.julia_c.x)
-                                            .speed(0.001)
-                                            .range(-2.0..=2.0),
-                                    )
-                                    .changed()
-                                {
+                                let response = ui.add(
+                                    egui::DragValue::new(&mut self.julia_c.x)
+                                        .speed(0.001)
+                                        .range(-2.0..=2.0),
+                                );
+                                self.adjusting |= response.dragged();
+                                if response.changed() {
                                     self.needs_update = true;
                                 }
                             });
 
                             ui.horizontal(|ui| {
                                 ui.label("Im:");
-                                if ui
-                                    .add(
-                                        egui::DragValue::new(&mut self.julia_c.y)
-                                            .speed(0.001)
-                                            .range(-2.0..=2.0),
-                                    )
-                                    .changed()
-                                {
+                                let response = ui.add(
+                                    egui::DragValue::new(&mut self.julia_c.y)
+                                        .speed(0.001)
+                                        .range(-2.0..=2.0),
+                                );
+                                self.adjusting |= response.dragged();
+                                if response.changed() {
                                     self.needs_update = true;
                                 }
                             });
@@ -248,6 +426,52 @@ impl eframe::App for FractalApp {
                         });
                     }
 
+                    if self.fractal_type == FractalType::Lyapunov {
+                        ui.add_space(8.0);
+                        egui::Frame::NONE
+                            .fill(ui.visuals().extreme_bg_color)
+                            .inner_margin(10.0)
+                            .corner_radius(6.0)
+                            .show(ui, |ui| {
+                            ui.label(egui::RichText::new("📈 Lyapunov Sequence").size(14.0).strong());
+                            ui.add_space(6.0);
+
+                            ui.label(
+                                egui::RichText::new("A uses the x axis, B the y axis as the growth rate")
+                                    .small()
+                                    .weak(),
+                            );
+                            ui.text_edit_singleline(&mut self.lyapunov_input);
+                            match self.lyapunov_input.parse::<LyapunovSequence>() {
+                                Ok(sequence) if sequence != self.lyapunov_sequence => {
+                                    self.lyapunov_sequence = sequence;
+                                    self.needs_update = true;
+                                }
+                                Ok(_) => {}
+                                Err(error) => {
+                                    ui.colored_label(ui.visuals().error_fg_color, error);
+                                }
+                            }
+
+                            ui.add_space(6.0);
+                            ui.label(egui::RichText::new("Presets").size(12.0));
+                            ui.horizontal_wrapped(|ui| {
+                                for preset in ["AB", "AABAB", "AABB", "BBBBBBAAAAAA"] {
+                                    if ui.button(preset).clicked() {
+                                        self.lyapunov_input = preset.to_owned();
+                                        self.lyapunov_sequence = preset.parse().unwrap_or_default();
+                                        self.needs_update = true;
+                                    }
+                                }
+                            });
+                            ui.label(
+                                egui::RichText::new("Shaded by λ from chaos (λ > 0) through the edge of chaos to stable cycles (λ < 0)")
+                                    .small()
+                                    .weak(),
+                            );
+                        });
+                    }
+
                     ui.add_space(8.0);
 
                     egui::Frame::new()
@@ -258,11 +482,30 @@ impl eframe::App for FractalApp {
                         ui.label(egui::RichText::new("🗺️ Navigation").size(14.0).strong());
                         ui.add_space(6.0);
 
-                        if ui.button(egui::RichText::new("🏠 Reset View").size(13.0)).clicked() {
-                            self.center = self.fractal_type.default_center();
-                            self.zoom = 1.0;
-                            self.needs_update = true;
-                        }
+                        ui.horizontal(|ui| {
+                            if ui.button(egui::RichText::new("🏠 Reset View").size(13.0)).clicked() {
+                                self.go_to_view(self.fractal_type.default_center(), 1.0);
+                            }
+                            if ui.button(egui::RichText::new("📍 Go To...").size(13.0)).clicked() {
+                                self.open_go_to();
+                            }
+                            if ui
+                                .button(egui::RichText::new("📋").size(13.0))
+                                .on_hover_text("Copy the current location")
+                                .clicked()
+                            {
+                                ui.ctx().copy_text(self.location().to_text());
+                            }
+                        });
+
+                        ui.add_space(6.0);
+                        ui.add(
+                            egui::Slider::new(&mut self.navigation.double_click_zoom, 1.25..=16.0)
+                                .logarithmic(true)
+                                .text("Double-click Zoom")
+                                .suffix("×"),
+                        );
+                        ui.checkbox(&mut self.navigation.animate, "Animate zoom transitions");
 
                         ui.add_space(6.0);
                         ui.separator();
@@ -301,11 +544,61 @@ impl eframe::App for FractalApp {
                         ui.label("🖱️ Drag to pan view");
                         ui.label("🔍 Scroll to zoom in/out");
                         ui.label("🖱️ Double-click to zoom to point");
+                        ui.label("⇧ Shift-drag a box to zoom into it");
+                        ui.label("⌃ Ctrl-drag a box to zoom out into it");
                         ui.label("🖱️ Right-click for context menu");
+                        ui.label("↩ Ctrl+Z / Ctrl+Shift+Z to undo / redo");
+                        ui.label("⌨ F1 lists every keyboard shortcut");
                     });
                 });
         }
 
+        if self.bookmarks.open {
+            self.bookmarks_panel(ctx);
+        }
+
+        self.handle_dropped_files(ctx);
+        self.handle_keyboard_input(ctx);
+        if self.keyboard.show_help {
+            self.keymap_help_window(ctx);
+        }
+        if self.go_to.open {
+            self.go_to_window(ctx);
+        }
+        if self.orbit_inspector.open {
+            self.orbit_inspector_window(ctx);
+        }
+        if self.history.open {
+            self.history_window(ctx);
+        }
+        self.scene_window(ctx);
+
+        if self.show_gradient_editor {
+            self.gradient_editor_window(ctx);
+        }
+
+        if self.export.open || self.export.is_running() {
+            self.export_window(ctx);
+        }
+
+        if self.animation.open || self.animation.is_running() {
+            self.animation_window(ctx);
+        }
+
+        if self.julia_path.open || self.julia_path.is_running() {
+            self.julia_path_window(ctx);
+        }
+        if self.density.open || self.density.is_running() {
+            self.density_window(ctx);
+        }
+        if self.ifs.open || self.ifs.is_running() {
+            self.ifs_window(ctx);
+        }
+        self.advance_julia_playback(ctx);
+        self.advance_transition(ctx);
+
+        self.status_bar(ctx);
+
         // Main fractal display area
         egui::CentralPanel::default().show(ctx, |ui| {
             let available_size = ui.available_size();
@@ -314,17 +607,12 @@ impl eframe::App for FractalApp {
                 available_size.y as u32,
             );
 
-            if self.needs_update && self.image_size.0 > 0 && self.image_size.1 > 0 {
-                let image: ColorImage = self.generate_fractal_image();
-                self.texture = Some(ui.ctx().load_texture(
-                    "fractal",
-                    image,
-                    TextureOptions::default(),
-                ));
-                self.needs_update = false;
+            if self.image_size.0 > 0 && self.image_size.1 > 0 {
+                self.refresh_texture(ui.ctx());
             }
 
-            if let Some(texture) = &self.texture {
+            let plot = self.density_texture(ui.ctx()).or_else(|| self.ifs_texture(ui.ctx()));
+            if let Some(texture_id) = plot.or_else(|| self.texture.as_ref().map(egui::TextureHandle::id)) {
                 let image_rect = Rect::from_min_size(
                     ui.next_widget_position(),
                     Vec2::new(self.image_size.0 as f32, self.image_size.1 as f32),
@@ -335,9 +623,7 @@ impl eframe::App for FractalApp {
                 // Right-click context menu
                 response.context_menu(|ui| {
                     if ui.button("Reset View").clicked() {
-                        self.center = self.fractal_type.default_center();
-                        self.zoom = 1.0;
-                        self.needs_update = true;
+                        self.go_to_view(self.fractal_type.default_center(), 1.0);
                         ui.close();
                     }
 
@@ -346,6 +632,16 @@ impl eframe::App for FractalApp {
                         ui.close();
                     }
 
+                    if ui.button("📍 Go To...").clicked() {
+                        self.open_go_to();
+                        ui.close();
+                    }
+
+                    if ui.button("📋 Copy Location").clicked() {
+                        ui.ctx().copy_text(self.location().to_text());
+                        ui.close();
+                    }
+
                     ui.separator();
                     ui.label(format!(
                         "Position: ({:.6}, {:.6})",
@@ -355,13 +651,20 @@ impl eframe::App for FractalApp {
                 });
 
                 ui.painter().image(
-                    texture.id(),
+                    texture_id,
                     image_rect,
                     Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
                     Color32::WHITE,
                 );
 
                 self.handle_mouse_input(&response, image_rect);
+                self.draw_overlays(&response, image_rect);
+                if self.julia_path.open {
+                    self.julia_path_overlay(&response, image_rect);
+                }
+                if self.orbit_inspector.open {
+                    self.orbit_overlay(&response, image_rect);
+                }
             } else {
                 ui.centered_and_justified(|ui| {
                     ui.spinner();
@@ -370,6 +673,8 @@ impl eframe::App for FractalApp {
             }
         });
 
+        self.record_history(ctx);
+
         if self.is_dragging {
             ctx.request_repaint();
         }
@@ -0,0 +1,204 @@
+use crate::ui::fractal_app::FractalApp;
+use crate::utils::location::Location;
+
+/// How the size of the view is entered in the "Go to" window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum ViewScale {
+    #[default]
+    Zoom,  // Magnification, 1 shows the whole set
+    Width, // Width of the view along the real axis
+}
+
+/// State of the "Go to" window.
+#[derive(Default)]
+pub struct GoToDialog {
+    pub open: bool,
+    pub re: String,
+    pub im: String,
+    pub scale: String,
+    pub scale_kind: ViewScale,
+    pub iterations: Option<u16>, // From pasted parameters, applied with the location
+    pub paste: String,
+    pub status: Option<String>,
+    arrived: Option<Location>, // The last location gone to, with all of its digits
+}
+
+impl FractalApp {
+    /// Returns the current location. Digits beyond `f64` precision are kept while the view
+    /// stays where the "Go to" window put it.
+    #[must_use]
+    pub fn location(&self) -> Location {
+        match &self.go_to.arrived {
+            Some(location) if location.center() == self.center && location.zoom == self.zoom => Location {
+                iterations: Some(self.max_iterations),
+                ..location.clone()
+            },
+            _ => Location::from_view(self.center, self.zoom, self.max_iterations),
+        }
+    }
+
+    /// Returns the width of the view along the real axis at `zoom`.
+    #[must_use]
+    pub fn view_width(&self, zoom: f64) -> f64 {
+        4.0 / zoom * f64::from(self.image_size.0) / f64::from(self.image_size.1.max(1))
+    }
+
+    /// Opens the "Go to" window filled in with the current location.
+    pub fn open_go_to(&mut self) {
+        self.fill_go_to(self.location());
+        self.go_to.iterations = None;
+        self.go_to.paste.clear();
+        self.go_to.open = true;
+    }
+
+    /// Fills the fields from a location.
+    fn fill_go_to(&mut self, location: Location) {
+        let scale = match self.go_to.scale_kind {
+            ViewScale::Zoom => location.zoom,
+            ViewScale::Width => self.view_width(location.zoom),
+        };
+        let dialog = &mut self.go_to;
+        dialog.re = location.re;
+        dialog.im = location.im;
+        dialog.scale = format!("{scale:e}");
+        dialog.iterations = location.iterations;
+        dialog.status = None;
+    }
+
+    /// Reads the fields back into a location.
+    fn entered_location(&self) -> Result<Location, String> {
+        let dialog = &self.go_to;
+        let scale: f64 = dialog
+            .scale
+            .trim()
+            .parse()
+            .ok()
+            .filter(|scale: &f64| scale.is_finite() && *scale > 0.0)
+            .ok_or_else(|| format!("'{}' is not a positive number", dialog.scale.trim()))?;
+        let zoom = match dialog.scale_kind {
+            ViewScale::Zoom => scale,
+            ViewScale::Width => self.view_width(1.0) / scale,
+        };
+        let mut location = Location::new(&dialog.re, &dialog.im, zoom)?;
+        location.iterations = dialog.iterations;
+        Ok(location)
+    }
+
+    /// Shows the "Go to" window.
+    pub fn go_to_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.go_to.open;
+        let mut pasted: Option<String> = None;
+        let mut go = false;
+        let mut copy = false;
+        let mut scale_changed = false;
+
+        egui::Window::new("📍 Go To")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("go_to_grid").num_columns(2).spacing([8.0, 6.0]).show(ui, |ui| {
+                    ui.label("Real:");
+                    ui.add(egui::TextEdit::singleline(&mut self.go_to.re).font(egui::TextStyle::Monospace).desired_width(320.0));
+                    ui.end_row();
+
+                    ui.label("Imaginary:");
+                    ui.add(egui::TextEdit::singleline(&mut self.go_to.im).font(egui::TextStyle::Monospace).desired_width(320.0));
+                    ui.end_row();
+
+                    ui.horizontal(|ui| {
+                        for (kind, label) in [(ViewScale::Zoom, "Zoom"), (ViewScale::Width, "Width")] {
+                            if ui.selectable_value(&mut self.go_to.scale_kind, kind, label).changed() {
+                                scale_changed = true;
+                            }
+                        }
+                    });
+                    ui.add(egui::TextEdit::singleline(&mut self.go_to.scale).font(egui::TextStyle::Monospace).desired_width(320.0));
+                    ui.end_row();
+                });
+                if let Some(iterations) = self.go_to.iterations {
+                    ui.label(egui::RichText::new(format!("Iterations from the pasted parameters: {iterations}")).small().weak());
+                }
+
+                ui.add_space(4.0);
+                let paste = ui.add(
+                    egui::TextEdit::multiline(&mut self.go_to.paste)
+                        .hint_text("Paste a location: Re/Im/Zoom lines (Kalles Fraktaler), (view ...) (XaoS) or 'RE, IM'")
+                        .desired_rows(2)
+                        .desired_width(f32::INFINITY),
+                );
+                if paste.changed() && !self.go_to.paste.trim().is_empty() {
+                    pasted = Some(self.go_to.paste.clone());
+                }
+
+                ui.horizontal(|ui| {
+                    if ui.button("Go").clicked() {
+                        go = true;
+                    }
+                    if ui.button("📋 Copy Location").on_hover_text("Copy the current location to the clipboard").clicked() {
+                        copy = true;
+                    }
+                });
+                if let Some(status) = &self.go_to.status {
+                    ui.label(status);
+                }
+            });
+
+        // Convert the entered value instead of discarding it; the conversion is its own inverse
+        if scale_changed
+            && let Ok(value) = self.go_to.scale.trim().parse::<f64>()
+            && value > 0.0
+        {
+            self.go_to.scale = format!("{:e}", self.view_width(1.0) / value);
+        }
+        if let Some(text) = pasted {
+            match Location::parse(&text) {
+                Ok(location) => self.fill_go_to(location),
+                Err(error) => self.go_to.status = Some(format!("❌ {error}")),
+            }
+        }
+        if copy {
+            ctx.copy_text(self.location().to_text());
+            self.go_to.status = Some(String::from("Copied the current location"));
+        }
+        if go {
+            match self.entered_location() {
+                Ok(location) => {
+                    self.center = location.center();
+                    self.zoom = location.zoom;
+                    if let Some(iterations) = location.iterations {
+                        self.max_iterations = iterations.max(10);
+                    }
+                    self.navigation.transition = None;
+                    self.needs_update = true;
+                    self.go_to.status = None;
+                    self.go_to.arrived = Some(location);
+                }
+                Err(error) => self.go_to.status = Some(format!("❌ {error}")),
+            }
+        }
+        self.go_to.open = open;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::point::Point;
+
+    #[test]
+    fn test_width_and_zoom_agree() {
+        let mut app = FractalApp {
+            image_size: (800, 400),
+            zoom: 8.0,
+            ..FractalApp::default()
+        };
+        app.go_to.scale_kind = ViewScale::Width;
+        app.fill_go_to(Location::from_view(Point::new(0.25, -0.5), 8.0, 300));
+        assert_eq!(app.go_to.scale, "1e0");
+
+        let location = app.entered_location().unwrap();
+        assert_eq!(location.zoom, 8.0);
+        assert_eq!(location.center(), Point::new(0.25, -0.5));
+    }
+}
@@ -0,0 +1,86 @@
+use crate::ui::fractal_app::FractalApp;
+use crate::utils::color_space::InterpolationSpace;
+use crate::utils::gradient::GradientStop;
+use eframe::emath::{Rect, Vec2};
+
+impl FractalApp {
+    /// Shows the custom gradient editor window.
+    /// Edits only trigger a recolor of the retained iteration buffer, never a full render.
+    pub fn gradient_editor_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_gradient_editor;
+        let mut changed = false;
+
+        egui::Window::new("🎨 Custom Gradient")
+            .open(&mut open)
+            .resizable(false)
+            .default_width(320.0)
+            .show(ctx, |ui| {
+                changed |= ui
+                    .checkbox(&mut self.use_custom_gradient, "Use custom gradient")
+                    .changed();
+
+                ui.horizontal(|ui| {
+                    ui.label("Blend in:");
+                    egui::ComboBox::from_id_salt("gradient_space")
+                        .selected_text(self.custom_gradient.space.name())
+                        .show_ui(ui, |ui| {
+                            for space in InterpolationSpace::all() {
+                                changed |= ui
+                                    .selectable_value(&mut self.custom_gradient.space, space, space.name())
+                                    .changed();
+                            }
+                        });
+                });
+
+                // Preview strip, sampled exactly like the palette
+                let mut preview = self.custom_gradient.clone();
+                preview.sort_stops();
+                let (rect, _) = ui.allocate_exact_size(Vec2::new(ui.available_width(), 24.0), egui::Sense::hover());
+                const SEGMENTS: usize = 96;
+                let segment_width = rect.width() / SEGMENTS as f32;
+                for i in 0..SEGMENTS {
+                    let t = (i as f32 + 0.5) / SEGMENTS as f32;
+                    let min = rect.min + Vec2::new(i as f32 * segment_width, 0.0);
+                    ui.painter().rect_filled(
+                        Rect::from_min_size(min, Vec2::new(segment_width + 0.5, rect.height())),
+                        0.0,
+                        preview.sample(t),
+                    );
+                }
+
+                ui.add_space(6.0);
+                ui.label(egui::RichText::new("Stops").size(12.0));
+
+                let can_remove = self.custom_gradient.stops.len() > 2;
+                let mut remove = None;
+                for (index, stop) in self.custom_gradient.stops.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        changed |= ui.color_edit_button_srgba(&mut stop.color).changed();
+                        changed |= ui
+                            .add(egui::Slider::new(&mut stop.position, 0.0..=1.0).fixed_decimals(2))
+                            .changed();
+                        if can_remove && ui.small_button("🗑").on_hover_text("Remove stop").clicked() {
+                            remove = Some(index);
+                        }
+                    });
+                }
+
+                if let Some(index) = remove {
+                    self.custom_gradient.stops.remove(index);
+                    changed = true;
+                }
+
+                if ui.button("➕ Add Stop").clicked() {
+                    self.custom_gradient
+                        .stops
+                        .push(GradientStop::new(1.0, preview.sample(1.0)));
+                    changed = true;
+                }
+            });
+
+        self.show_gradient_editor = open;
+        if changed {
+            self.needs_recolor = true;
+        }
+    }
+}
@@ -0,0 +1,322 @@
+use crate::ui::fractal_app::FractalApp;
+use crate::ui::keymap::Action;
+use crate::utils::scene::Scene;
+use std::time::{Duration, Instant};
+
+/// Number of steps kept; the oldest steps are dropped first.
+pub const HISTORY_LIMIT: usize = 200;
+
+/// Continuous changes of the same kind this close together are merged into one step.
+pub const COALESCE_WINDOW: Duration = Duration::from_millis(600);
+
+/// What changed between two consecutive history steps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ChangeKind {
+    Start,      // The state the viewer started in
+    Fractal,    // Fractal type
+    Zoom,       // Zoom level, usually with the center
+    Pan,        // Center only
+    Julia,      // Julia constant
+    Iterations, // Iteration limit
+    Quality,    // Precision or anti-aliasing
+    Palette,    // Any color setting
+}
+
+impl ChangeKind {
+    /// Returns the label shown in the history list.
+    #[inline]
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Start => "Start",
+            Self::Fractal => "Fractal",
+            Self::Zoom => "Zoom",
+            Self::Pan => "Pan",
+            Self::Julia => "Julia Constant",
+            Self::Iterations => "Iterations",
+            Self::Quality => "Quality",
+            Self::Palette => "Palette",
+        }
+    }
+
+    /// Returns the most significant change from `old` to `new`, or `None` if the view is the same.
+    /// The render size is ignored: resizing the window is not a step.
+    #[must_use]
+    pub fn between(old: &Scene, new: &Scene) -> Option<Self> {
+        let (a, b) = (&old.render, &new.render);
+        if a.fractal_type != b.fractal_type || a.lyapunov_sequence != b.lyapunov_sequence {
+            Some(Self::Fractal)
+        } else if a.zoom != b.zoom {
+            Some(Self::Zoom)
+        } else if a.center != b.center {
+            Some(Self::Pan)
+        } else if a.julia_c != b.julia_c {
+            Some(Self::Julia)
+        } else if a.max_iterations != b.max_iterations {
+            Some(Self::Iterations)
+        } else if a.precision_mode != b.precision_mode || a.anti_aliasing != b.anti_aliasing {
+            Some(Self::Quality)
+        } else if old.colors != new.colors {
+            Some(Self::Palette)
+        } else {
+            None
+        }
+    }
+}
+
+/// One step of the history.
+#[derive(Clone, Debug)]
+pub struct HistoryEntry {
+    pub kind: ChangeKind,
+    pub scene: Scene,
+    open_since: Option<Instant>, // Last merged change while later changes may still be merged in
+}
+
+/// Undo/redo stack of visited views. Going back and then changing the view drops the steps ahead.
+#[derive(Clone, Debug, Default)]
+pub struct History {
+    pub open: bool, // Whether the history window is shown
+    entries: Vec<HistoryEntry>,
+    position: usize,
+}
+
+impl History {
+    /// Returns the recorded steps, oldest first.
+    #[inline]
+    #[must_use]
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    /// Returns the index of the step currently shown.
+    #[inline]
+    #[must_use]
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns whether there is a step to go back to.
+    #[inline]
+    #[must_use]
+    pub const fn can_undo(&self) -> bool {
+        self.position > 0
+    }
+
+    /// Returns whether there is a step to go forward to.
+    #[inline]
+    #[must_use]
+    pub const fn can_redo(&self) -> bool {
+        self.position + 1 < self.entries.len()
+    }
+
+    /// Records the current state. A `continuous` change (scrolling, dragging, a held slider) is
+    /// merged into the current step if that step is of the same kind and was changed recently.
+    pub fn record(&mut self, scene: &Scene, continuous: bool, now: Instant) {
+        let Some(current) = self.entries.get_mut(self.position) else {
+            self.entries.push(HistoryEntry {
+                kind: ChangeKind::Start,
+                scene: scene.clone(),
+                open_since: None,
+            });
+            self.position = 0;
+            return;
+        };
+        let Some(kind) = ChangeKind::between(&current.scene, scene) else {
+            return;
+        };
+
+        let mergeable = continuous
+            && current.kind == kind
+            && current
+                .open_since
+                .is_some_and(|since| now.duration_since(since) <= COALESCE_WINDOW);
+        self.entries.truncate(self.position + 1);
+        if mergeable {
+            let current = &mut self.entries[self.position];
+            current.scene = scene.clone();
+            current.open_since = Some(now);
+            return;
+        }
+
+        self.entries.push(HistoryEntry {
+            kind,
+            scene: scene.clone(),
+            open_since: continuous.then_some(now),
+        });
+        if self.entries.len() > HISTORY_LIMIT {
+            self.entries.remove(0);
+        }
+        self.position = self.entries.len() - 1;
+    }
+
+    /// Moves to step `index` and returns its scene, or `None` if there is no such step.
+    pub fn go_to(&mut self, index: usize) -> Option<&Scene> {
+        // Coming back to a step never reopens it for merging
+        self.entries.get_mut(self.position)?.open_since = None;
+        let entry = self.entries.get(index)?;
+        self.position = index;
+        Some(&entry.scene)
+    }
+
+    /// Steps back and returns the scene to restore.
+    pub fn undo(&mut self) -> Option<&Scene> {
+        let index = self.position.checked_sub(1)?;
+        self.go_to(index)
+    }
+
+    /// Steps forward and returns the scene to restore.
+    pub fn redo(&mut self) -> Option<&Scene> {
+        self.go_to(self.position + 1)
+    }
+}
+
+impl FractalApp {
+    /// Records the view in the history; called once per frame after all input was handled.
+    pub fn record_history(&mut self, ctx: &egui::Context) {
+        let continuous = self.is_dragging
+            || self.keyboard.navigating
+            || self.navigation.is_animating()
+            || self.julia_path.playing
+            || ctx.input(|i| i.smooth_scroll_delta != egui::Vec2::ZERO || i.pointer.any_down());
+        self.history.record(&self.scene(), continuous, Instant::now());
+    }
+
+    /// Goes back one step.
+    pub fn undo(&mut self) {
+        if let Some(scene) = self.history.undo().cloned() {
+            self.apply_scene(&scene);
+        }
+    }
+
+    /// Goes forward one step.
+    pub fn redo(&mut self) {
+        if let Some(scene) = self.history.redo().cloned() {
+            self.apply_scene(&scene);
+        }
+    }
+
+    /// Shows the back/forward buttons and the history toggle in the menu bar.
+    pub fn history_buttons(&mut self, ui: &mut egui::Ui) {
+        if ui
+            .add_enabled(self.history.can_undo(), egui::Button::new("⬅"))
+            .on_hover_text(format!("Back ({})", self.keyboard.keymap.describe(Action::Undo)))
+            .clicked()
+        {
+            self.undo();
+        }
+        if ui
+            .add_enabled(self.history.can_redo(), egui::Button::new("➡"))
+            .on_hover_text(format!("Forward ({})", self.keyboard.keymap.describe(Action::Redo)))
+            .clicked()
+        {
+            self.redo();
+        }
+        if ui.selectable_label(self.history.open, "🕘").on_hover_text("History").clicked() {
+            self.history.open = !self.history.open;
+        }
+    }
+
+    /// Shows the history list; clicking a step returns to it.
+    pub fn history_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.history.open;
+        let mut target: Option<usize> = None;
+
+        egui::Window::new("🕘 History")
+            .open(&mut open)
+            .default_width(240.0)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    // Newest first, like a browser's history
+                    for (index, entry) in self.history.entries().iter().enumerate().rev() {
+                        let render = &entry.scene.render;
+                        let label = format!(
+                            "{} · {} · {:.2e}×",
+                            entry.kind.name(),
+                            render.fractal_type.name(),
+                            render.zoom
+                        );
+                        let response = ui
+                            .selectable_label(index == self.history.position(), label)
+                            .on_hover_text(format!("({:.6}, {:.6})", render.center.x, render.center.y));
+                        if response.clicked() {
+                            target = Some(index);
+                        }
+                    }
+                });
+            });
+
+        if let Some(index) = target
+            && let Some(scene) = self.history.go_to(index).cloned()
+        {
+            self.apply_scene(&scene);
+        }
+        self.history.open = open;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fractals::fractal_type::FractalType;
+
+    fn zoomed(zoom: f64) -> Scene {
+        let mut scene = Scene::default();
+        scene.render.zoom = zoom;
+        scene
+    }
+
+    #[test]
+    fn test_continuous_scroll_is_one_step() {
+        let mut history = History::default();
+        let start = Instant::now();
+        history.record(&Scene::default(), false, start);
+        for tick in 1..=20 {
+            history.record(&zoomed(1.1_f64.powi(tick)), true, start + Duration::from_millis(tick as u64 * 50));
+        }
+        assert_eq!(history.entries().len(), 2);
+        assert_eq!(history.entries()[1].kind, ChangeKind::Zoom);
+
+        // A pause ends the step, and a window resize is not a step at all
+        history.record(&zoomed(50.0), true, start + Duration::from_secs(5));
+        let mut resized = zoomed(50.0);
+        resized.render.width = 1234;
+        history.record(&resized, false, start + Duration::from_secs(6));
+        assert_eq!(history.entries().len(), 3);
+    }
+
+    #[test]
+    fn test_undo_redo_and_branching() {
+        let mut history = History::default();
+        let now = Instant::now();
+        history.record(&Scene::default(), false, now);
+        history.record(&zoomed(2.0), false, now);
+        history.record(&zoomed(4.0), false, now);
+
+        assert_eq!(history.undo().unwrap().render.zoom, 2.0);
+        assert_eq!(history.undo().unwrap().render.zoom, 1.0);
+        assert!(history.undo().is_none());
+        assert_eq!(history.redo().unwrap().render.zoom, 2.0);
+        assert!(history.can_redo());
+
+        // Changing the view after going back drops the steps ahead
+        let mut julia = zoomed(2.0);
+        julia.render.fractal_type = FractalType::Julia;
+        history.record(&julia, false, now);
+        assert!(!history.can_redo());
+        assert_eq!(history.entries().len(), 3);
+        assert_eq!(history.entries()[2].kind, ChangeKind::Fractal);
+        assert_eq!(history.go_to(0).unwrap(), &Scene::default());
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut history = History::default();
+        let now = Instant::now();
+        for step in 0..HISTORY_LIMIT + 50 {
+            history.record(&zoomed(step as f64 + 1.0), false, now);
+        }
+        assert_eq!(history.entries().len(), HISTORY_LIMIT);
+        assert_eq!(history.position(), HISTORY_LIMIT - 1);
+    }
+}
@@ -0,0 +1,344 @@
+use crate::density::density_map::DensityMap;
+use crate::density::ifs::{self, IfsPreset, IfsSettings, IfsSystem, Transform, Variation};
+use crate::ui::fractal_app::FractalApp;
+use crate::utils::color_scheme::ColorScheme;
+use crate::utils::point::Point;
+use egui::TextureOptions;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Points plotted per pass of the background accumulation.
+const SAMPLES_PER_PASS: u64 = 1_000_000;
+
+/// Points plotted before the accumulation stops; the plot has long stopped changing by then.
+const MAX_SAMPLES: u64 = 2_000_000_000;
+
+/// How often the plot on the canvas is redrawn while points accumulate.
+const REDRAW_INTERVAL: Duration = Duration::from_millis(250);
+
+/// An IFS plot accumulating on a background thread until dropped.
+struct IfsJob {
+    settings: IfsSettings,
+    map: Arc<DensityMap>,
+    cancel: Arc<AtomicBool>,
+    finished: Arc<AtomicBool>, // Set by the thread once it stops accumulating
+}
+
+impl Drop for IfsJob {
+    /// Stops the background thread.
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// State of the "IFS & Flames" window and of the plot it draws on the canvas.
+pub struct IfsDialog {
+    pub open: bool,
+    pub preset: IfsPreset,
+    pub system: IfsSystem,
+    pub scheme: ColorScheme,
+    pub exposure: f32,
+    pub path: String,
+    pub status: Option<String>,
+    job: Option<IfsJob>,
+    texture: Option<egui::TextureHandle>,
+    redrawn: Option<Instant>,          // When the texture was last made from the counts
+    return_view: Option<(Point, f64)>, // View of the fractal to go back to once the plot stops
+}
+
+impl Default for IfsDialog {
+    /// Creates a closed dialog for the Sierpinski triangle.
+    #[inline]
+    fn default() -> Self {
+        let preset = IfsPreset::default();
+        Self {
+            open: false,
+            preset,
+            system: preset.system(),
+            scheme: ColorScheme::default(),
+            exposure: 1.0,
+            path: String::from("ifs.png"),
+            status: None,
+            job: None,
+            texture: None,
+            redrawn: None,
+            return_view: None,
+        }
+    }
+}
+
+impl IfsDialog {
+    /// Returns `true` while a plot is accumulating and shown on the canvas.
+    #[inline]
+    #[must_use]
+    pub const fn is_running(&self) -> bool {
+        self.job.is_some()
+    }
+}
+
+impl FractalApp {
+    /// Returns the plot settings for the current view and the dialog's system.
+    #[must_use]
+    pub fn ifs_settings(&self) -> IfsSettings {
+        IfsSettings {
+            system: self.ifs.system.clone(),
+            center: self.center,
+            zoom: self.zoom,
+            width: self.image_size.0,
+            height: self.image_size.1,
+            seed: 0,
+        }
+    }
+
+    /// Moves the view to frame the attractor of the dialog's system.
+    fn fit_ifs_view(&mut self) {
+        (self.center, self.zoom) = self.ifs.system.fit(self.image_size.0, self.image_size.1);
+        self.needs_update = true;
+    }
+
+    /// Starts accumulating the plot of the current view, replacing any running plot on the canvas.
+    fn start_ifs(&mut self) {
+        self.density.stop();
+        let settings = self.ifs_settings();
+        let map = Arc::new(settings.new_map());
+        let cancel = Arc::new(AtomicBool::new(false));
+        let finished = Arc::new(AtomicBool::new(false));
+        {
+            let (settings, map, cancel) = (settings.clone(), Arc::clone(&map), Arc::clone(&cancel));
+            let finished = Arc::clone(&finished);
+            std::thread::spawn(move || {
+                let mut pass = 0;
+                while !cancel.load(Ordering::Relaxed) && map.samples() < MAX_SAMPLES {
+                    // A system without an attractor plots nothing: stop rather than retry forever
+                    if ifs::accumulate(&settings, &map, pass, SAMPLES_PER_PASS, &cancel) == 0 {
+                        break;
+                    }
+                    pass += 1;
+                }
+                finished.store(true, Ordering::Relaxed);
+            });
+        }
+        self.ifs.job = Some(IfsJob {
+            settings,
+            map,
+            cancel,
+            finished,
+        });
+        self.ifs.redrawn = None;
+    }
+
+    /// Stops the plot, if one is running, and returns to the fractal's view.
+    pub fn stop_ifs(&mut self) {
+        self.ifs.job = None;
+        if let Some((center, zoom)) = self.ifs.return_view.take() {
+            (self.center, self.zoom) = (center, zoom);
+            self.needs_update = true;
+        }
+    }
+
+    /// Returns the plot to draw in place of the fractal while one is running, restarting the
+    /// accumulation once the view or the system changes.
+    pub fn ifs_texture(&mut self, ctx: &egui::Context) -> Option<egui::TextureId> {
+        let changed = self.ifs.job.as_ref()?.settings != self.ifs_settings();
+        if changed && !self.is_dragging {
+            self.start_ifs();
+        }
+
+        let job = self.ifs.job.as_ref()?;
+        // Checked before redrawing, so the last redraw shows every point once the plot is complete
+        let complete = job.finished.load(Ordering::Relaxed);
+        let redraw = self.ifs.redrawn.is_none_or(|redrawn| redrawn.elapsed() >= REDRAW_INTERVAL);
+        if redraw {
+            let image = ifs::render(&job.map, self.ifs.scheme, f64::from(self.ifs.exposure));
+            let size = [image.width() as usize, image.height() as usize];
+            let image = egui::ColorImage::from_rgb(size, image.as_raw());
+            self.ifs.texture = Some(ctx.load_texture("ifs", image, TextureOptions::default()));
+            self.ifs.redrawn = Some(Instant::now());
+        }
+        if !(complete && redraw) {
+            ctx.request_repaint_after(REDRAW_INTERVAL);
+        }
+        self.ifs.texture.as_ref().map(egui::TextureHandle::id)
+    }
+
+    /// Shows the IFS window; closing it removes the plot from the canvas.
+    pub fn ifs_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.ifs.open;
+        let mut start = false;
+        let mut stop = false;
+        let mut fit = false;
+        let mut save = false;
+
+        egui::Window::new("🌿 IFS & Flames")
+            .open(&mut open)
+            .default_width(320.0)
+            .show(ctx, |ui| {
+                let dialog = &mut self.ifs;
+                ui.horizontal(|ui| {
+                    ui.label("Preset:");
+                    egui::ComboBox::from_id_salt("ifs_preset")
+                        .selected_text(dialog.preset.name())
+                        .show_ui(ui, |ui| {
+                            for preset in IfsPreset::all() {
+                                if ui.selectable_value(&mut dialog.preset, preset, preset.name()).clicked() {
+                                    dialog.system = preset.system();
+                                    fit = true;
+                                }
+                            }
+                        });
+                    if ui.button("Fit View").clicked() {
+                        fit = true;
+                    }
+                });
+
+                egui::ScrollArea::vertical().max_height(260.0).show(ui, |ui| {
+                    transforms_editor(ui, &mut dialog.system);
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Palette:");
+                    egui::ComboBox::from_id_salt("ifs_palette")
+                        .selected_text(dialog.scheme.name())
+                        .show_ui(ui, |ui| {
+                            for scheme in ColorScheme::all() {
+                                if ui.selectable_value(&mut dialog.scheme, scheme, scheme.name()).changed() {
+                                    dialog.redrawn = None;
+                                }
+                            }
+                        });
+                    if ui
+                        .add(egui::Slider::new(&mut dialog.exposure, 0.1..=10.0).text("Exposure").logarithmic(true))
+                        .changed()
+                    {
+                        dialog.redrawn = None;
+                    }
+                });
+
+                ui.label(
+                    egui::RichText::new("Brightness follows the log of the point density; moving the view starts a new plot")
+                        .small()
+                        .weak(),
+                );
+
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    if let Some(job) = &dialog.job {
+                        if ui.button("⏹ Stop").clicked() {
+                            stop = true;
+                        } else {
+                            let finished = job.finished.load(Ordering::Relaxed);
+                            if !finished {
+                                ui.spinner();
+                            }
+                            if finished && job.map.samples() == 0 {
+                                ui.label("No attractor: every point escapes");
+                            } else {
+                                ui.label(format!("{:.1} M points", job.map.samples() as f64 / 1e6));
+                            }
+                        }
+                    } else if ui.button("▶ Start").clicked() {
+                        start = true;
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("File:");
+                    ui.text_edit_singleline(&mut dialog.path);
+                    if ui
+                        .add_enabled(dialog.job.is_some() && !dialog.path.trim().is_empty(), egui::Button::new("💾 Save PNG"))
+                        .clicked()
+                    {
+                        save = true;
+                    }
+                });
+
+                if let Some(status) = &dialog.status {
+                    ui.label(status);
+                }
+            });
+
+        self.ifs.open = open;
+        if !open || stop {
+            self.stop_ifs();
+        }
+        if (start || fit) && self.ifs.return_view.is_none() {
+            self.ifs.return_view = Some((self.center, self.zoom));
+        }
+        if fit {
+            self.fit_ifs_view();
+        }
+        if start {
+            self.ifs.status = None;
+            self.start_ifs();
+        }
+        if save && let Some(job) = &self.ifs.job {
+            let path = self.ifs.path.trim();
+            let image = ifs::render(&job.map, self.ifs.scheme, f64::from(self.ifs.exposure));
+            self.ifs.status = Some(match image.save(path) {
+                Ok(()) => format!("✅ Saved {path} ({:.1} M points)", job.map.samples() as f64 / 1e6),
+                Err(error) => format!("❌ {error}"),
+            });
+        }
+    }
+}
+
+/// Edits the transforms of `system`: affine coefficients, weight, color and variations.
+fn transforms_editor(ui: &mut egui::Ui, system: &mut IfsSystem) {
+    let mut removed = None;
+    let removable = system.transforms.len() > 1;
+    for (index, transform) in system.transforms.iter_mut().enumerate() {
+        egui::CollapsingHeader::new(format!("Transform {}", index + 1))
+            .id_salt(("ifs_transform", index))
+            .show(ui, |ui| {
+                ui.label(egui::RichText::new("x' = a·x + b·y + c,  y' = d·x + e·y + f").small().weak());
+                egui::Grid::new(("ifs_affine", index)).show(ui, |ui| {
+                    for (row, names) in transform.affine.chunks_exact_mut(3).zip([["a", "b", "c"], ["d", "e", "f"]]) {
+                        for (value, name) in row.iter_mut().zip(names) {
+                            ui.add(egui::DragValue::new(value).speed(0.005).prefix(format!("{name} ")));
+                        }
+                        ui.end_row();
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut transform.weight).speed(0.01).range(0.0..=100.0).prefix("weight "));
+                    ui.add(egui::Slider::new(&mut transform.color, 0.0..=1.0).text("color"));
+                });
+
+                let mut removed_variation = None;
+                for (variation_index, (variation, weight)) in transform.variations.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        egui::ComboBox::from_id_salt(("ifs_variation", index, variation_index))
+                            .selected_text(variation.name())
+                            .show_ui(ui, |ui| {
+                                for option in Variation::all() {
+                                    ui.selectable_value(variation, option, option.name());
+                                }
+                            });
+                        ui.add(egui::DragValue::new(weight).speed(0.01).range(-2.0..=2.0));
+                        if ui.small_button("🗑").on_hover_text("Remove the variation").clicked() {
+                            removed_variation = Some(variation_index);
+                        }
+                    });
+                }
+                if let Some(variation_index) = removed_variation {
+                    transform.variations.remove(variation_index);
+                }
+
+                ui.horizontal(|ui| {
+                    if ui.small_button("+ Variation").clicked() {
+                        transform.variations.push((Variation::Swirl, 0.5));
+                    }
+                    if ui.add_enabled(removable, egui::Button::new("Remove").small()).clicked() {
+                        removed = Some(index);
+                    }
+                });
+            });
+    }
+    if let Some(index) = removed {
+        system.transforms.remove(index);
+    }
+    if ui.button("+ Transform").clicked() {
+        system.transforms.push(Transform::default());
+    }
+}
@@ -0,0 +1,284 @@
+use crate::animation::keyframe::Keyframe;
+use crate::animation::julia_path::{JuliaPath, JuliaPathAnimation, JuliaPathShape};
+use crate::fractals::fractal_type::FractalType;
+use crate::ui::animation_dialog::{SequenceJob, SequenceSettings};
+use crate::ui::fractal_app::FractalApp;
+use crate::utils::point::Point;
+use eframe::epaint::{Color32, Stroke};
+
+/// State of the "Julia Path" window: the path, real-time playback and sequence export.
+pub struct JuliaPathDialog {
+    pub open: bool,
+    pub path: JuliaPath,
+    pub playing: bool,
+    pub phase: f64,
+    pub loop_seconds: f64,
+    pub picking: bool, // Clicks on the Mandelbrot view add spline points
+    pub settings: SequenceSettings,
+    pub status: Option<String>,
+    job: Option<SequenceJob>,
+}
+
+impl Default for JuliaPathDialog {
+    /// Creates a closed, paused dialog looping the default circle every 20 seconds.
+    #[inline]
+    fn default() -> Self {
+        Self {
+            open: false,
+            path: JuliaPath::default(),
+            playing: false,
+            phase: 0.0,
+            loop_seconds: 20.0,
+            picking: false,
+            settings: SequenceSettings {
+                directory: String::from("julia_frames"),
+                y4m_path: String::from("julia.y4m"),
+                ..SequenceSettings::default()
+            },
+            status: None,
+            job: None,
+        }
+    }
+}
+
+impl JuliaPathDialog {
+    /// Returns `true` while a sequence is being rendered.
+    #[inline]
+    #[must_use]
+    pub const fn is_running(&self) -> bool {
+        self.job.is_some()
+    }
+}
+
+impl FractalApp {
+    /// Moves the Julia constant to `phase` along the path, switching to the Julia set if needed.
+    pub fn set_julia_phase(&mut self, phase: f64) {
+        self.julia_path.phase = phase.rem_euclid(1.0);
+        self.julia_c = self.julia_path.path.point_at(self.julia_path.phase);
+        if self.fractal_type != FractalType::Julia {
+            self.fractal_type = FractalType::Julia;
+            self.center = FractalType::Julia.default_center();
+            self.zoom = 1.0;
+        }
+        self.needs_update = true;
+    }
+
+    /// Advances real-time playback by the duration of the last frame; closing the window stops it.
+    pub fn advance_julia_playback(&mut self, ctx: &egui::Context) {
+        if !self.julia_path.open {
+            self.julia_path.playing = false;
+        }
+        if !self.julia_path.playing {
+            return;
+        }
+        let dt = f64::from(ctx.input(|i| i.stable_dt).min(0.1));
+        self.set_julia_phase(self.julia_path.phase + dt / self.julia_path.loop_seconds);
+        ctx.request_repaint();
+    }
+
+    /// Draws the path over the Mandelbrot view (the plane the Julia constant lives in)
+    /// and adds a spline point per click while picking.
+    pub fn julia_path_overlay(&mut self, response: &egui::Response, image_rect: egui::Rect) {
+        if self.fractal_type != FractalType::Mandelbrot {
+            return;
+        }
+
+        if self.julia_path.picking
+            && response.clicked()
+            && let Some(position) = response.interact_pointer_pos()
+        {
+            let point = self.screen_to_complex(position, image_rect);
+            self.julia_path.path.points.push(point);
+        }
+
+        let painter = response.ctx.layer_painter(response.layer_id).with_clip_rect(image_rect);
+        let stroke = Stroke::new(1.5, Color32::from_rgb(255, 220, 80));
+        let line: Vec<_> = self
+            .julia_path
+            .path
+            .polyline(256)
+            .into_iter()
+            .map(|point| self.complex_to_screen(point, image_rect))
+            .collect();
+        if self.julia_path.path.shape != JuliaPathShape::Spline || self.julia_path.path.points.len() > 1 {
+            painter.add(egui::Shape::line(line, stroke));
+        }
+        if self.julia_path.path.shape == JuliaPathShape::Spline {
+            for point in &self.julia_path.path.points {
+                painter.circle_filled(self.complex_to_screen(*point, image_rect), 4.0, stroke.color);
+            }
+        }
+        let current = self.julia_path.path.point_at(self.julia_path.phase);
+        painter.circle_stroke(self.complex_to_screen(current, image_rect), 6.0, Stroke::new(2.0, Color32::WHITE));
+    }
+
+    /// Shows the Julia path window and tracks a running export.
+    pub fn julia_path_window(&mut self, ctx: &egui::Context) {
+        if let Some(status) = self.julia_path.job.as_ref().and_then(|job| job.poll(ctx)) {
+            self.julia_path.status = Some(status);
+            self.julia_path.job = None;
+        }
+
+        let mut open = self.julia_path.open;
+        let mut phase: Option<f64> = None;
+        let mut start = false;
+
+        egui::Window::new("🌀 Julia Path")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let path = &mut self.julia_path.path;
+                ui.horizontal(|ui| {
+                    ui.label("Shape:");
+                    egui::ComboBox::from_id_salt("julia_path_shape")
+                        .selected_text(path.shape.name())
+                        .show_ui(ui, |ui| {
+                            for shape in JuliaPathShape::all() {
+                                ui.selectable_value(&mut path.shape, shape, shape.name());
+                            }
+                        });
+                });
+
+                let point_editor = |ui: &mut egui::Ui, label: &str, point: &mut Point| {
+                    ui.horizontal(|ui| {
+                        ui.label(label);
+                        ui.add(egui::DragValue::new(&mut point.x).speed(0.001).range(-2.0..=2.0).prefix("Re "));
+                        ui.add(egui::DragValue::new(&mut point.y).speed(0.001).range(-2.0..=2.0).prefix("Im "));
+                    });
+                };
+                match path.shape {
+                    JuliaPathShape::Circle => {
+                        point_editor(ui, "Center:", &mut path.center);
+                        ui.add(egui::Slider::new(&mut path.radius, 0.0..=2.0).text("Radius"));
+                    }
+                    JuliaPathShape::Line => {
+                        point_editor(ui, "From:", &mut path.start);
+                        point_editor(ui, "To:", &mut path.end);
+                        if ui.button("Set end to current c").clicked() {
+                            path.end = self.julia_c;
+                        }
+                    }
+                    JuliaPathShape::Spline => {
+                        ui.label(format!("{} point(s)", path.points.len()));
+                        ui.horizontal(|ui| {
+                            ui.toggle_value(&mut self.julia_path.picking, "📍 Pick on Mandelbrot")
+                                .on_hover_text("Click the Mandelbrot view to add points");
+                            if ui.button("Add current c").clicked() {
+                                path.points.push(self.julia_c);
+                            }
+                            if ui.add_enabled(!path.points.is_empty(), egui::Button::new("Clear")).clicked() {
+                                path.points.clear();
+                            }
+                        });
+                        if self.julia_path.picking && self.fractal_type != FractalType::Mandelbrot {
+                            ui.label(egui::RichText::new("Switch to the Mandelbrot set to pick points.").weak());
+                        }
+                    }
+                    JuliaPathShape::Cardioid => {
+                        ui.add(egui::Slider::new(&mut path.scale, 0.9..=1.1).text("Scale"))
+                            .on_hover_text("1 follows the boundary exactly; below 1 stays inside the set");
+                    }
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let label = if self.julia_path.playing { "⏸ Pause" } else { "▶ Play" };
+                    if ui.button(label).clicked() {
+                        self.julia_path.playing = !self.julia_path.playing;
+                        self.julia_path.picking = false;
+                    }
+                    let mut scrub = self.julia_path.phase;
+                    if ui.add(egui::Slider::new(&mut scrub, 0.0..=1.0).show_value(false)).changed() {
+                        phase = Some(scrub);
+                    }
+                });
+                ui.add(
+                    egui::Slider::new(&mut self.julia_path.loop_seconds, 2.0..=120.0)
+                        .text("Seconds per loop")
+                        .logarithmic(true),
+                );
+
+                ui.separator();
+                ui.label(egui::RichText::new("Export").strong());
+                self.julia_path.settings.ui(ui, "julia_path_anti_aliasing");
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    if let Some(job) = &self.julia_path.job {
+                        job.progress_ui(ui, self.julia_path.settings.frames);
+                    } else if ui
+                        .add_enabled(self.julia_path.settings.has_output(), egui::Button::new("🎬 Render"))
+                        .clicked()
+                    {
+                        start = true;
+                    }
+                });
+
+                if let Some(status) = &self.julia_path.status {
+                    ui.label(status);
+                }
+            });
+
+        self.julia_path.open = open;
+        if !open {
+            self.julia_path.picking = false;
+            self.julia_path.playing = false;
+        }
+        if let Some(phase) = phase {
+            self.set_julia_phase(phase);
+        }
+        if start {
+            self.start_julia_path_export();
+        }
+    }
+
+    /// Renders one loop of the path, keeping the current Julia view, on a background thread.
+    fn start_julia_path_export(&mut self) {
+        let settings = &self.julia_path.settings;
+        let (view, mut base) = (self.current_keyframe(), self.render_params());
+        base.fractal_type = FractalType::Julia;
+        let timeline = JuliaPathAnimation {
+            path: self.julia_path.path.clone(),
+            view: if self.fractal_type == FractalType::Julia {
+                view
+            } else {
+                // The current view is of the Mandelbrot set: frame the whole Julia set instead
+                Keyframe {
+                    center: FractalType::Julia.default_center(),
+                    zoom: 1.0,
+                    ..view
+                }
+            },
+            frames: settings.frames,
+            fps: settings.fps,
+        };
+
+        self.julia_path.job = Some(SequenceJob::spawn(
+            timeline,
+            settings.params(base),
+            self.color_settings(),
+            settings.output(),
+        ));
+        self.julia_path.status = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_playback_stops_with_the_window() {
+        let ctx = egui::Context::default();
+        let mut app = FractalApp::default();
+        app.julia_path.open = true;
+        app.julia_path.playing = true;
+        app.advance_julia_playback(&ctx);
+        assert!(app.julia_path.playing);
+
+        app.julia_path.open = false;
+        let phase = app.julia_path.phase;
+        app.advance_julia_playback(&ctx);
+        assert!(!app.julia_path.playing);
+        assert_eq!(app.julia_path.phase, phase);
+    }
+}
@@ -0,0 +1,627 @@
+use crate::fractals::fractal_type::FractalType;
+use crate::ui::fractal_app::FractalApp;
+use crate::utils::color_scheme::ColorScheme;
+use crate::utils::config;
+use egui::{Key, KeyboardShortcut, Modifiers};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Name of the keymap file inside the configuration directory.
+pub const KEYMAP_FILE: &str = "keymap.json";
+
+/// Version written to the keymap file, bumped on incompatible changes.
+const FORMAT_VERSION: u32 = 1;
+
+/// Fraction of the view height panned per second while a pan key is held.
+const PAN_SPEED: f64 = 0.8;
+
+/// Zoom factor applied per second while a zoom key is held.
+const ZOOM_SPEED: f64 = 2.0;
+
+/// Factor applied to the iteration limit by one press of the iteration keys.
+const ITERATION_STEP: f64 = 1.25;
+
+/// Something the keyboard can do.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum Action {
+    PanLeft,             // Held: moves the view left
+    PanRight,            // Held: moves the view right
+    PanUp,               // Held: moves the view up
+    PanDown,             // Held: moves the view down
+    ZoomIn,              // Held: zooms in on the center
+    ZoomOut,             // Held: zooms out from the center
+    Mandelbrot,          // Switches to the Mandelbrot set
+    Julia,               // Switches to the Julia set
+    BurningShip,         // Switches to the Burning Ship
+    Tricorn,             // Switches to the Tricorn
+    Lyapunov,            // Switches to the Lyapunov fractal
+    NextColorScheme,     // Cycles forward through the color schemes
+    PreviousColorScheme, // Cycles backward through the color schemes
+    MoreIterations,      // Raises the iteration limit
+    FewerIterations,     // Lowers the iteration limit
+    ToggleSettings,      // Shows or hides the control panel
+    ResetView,           // Returns to the default view of the fractal
+    GoTo,                // Opens the "Go to" window
+    OrbitInspector,      // Shows or hides the orbit inspector
+    Undo,                // Goes back in the history
+    Redo,                // Goes forward in the history
+    ToggleHelp,          // Shows or hides the shortcut overlay
+}
+
+impl Action {
+    /// Returns the name of the action, as shown in the help overlay and written to the keymap file.
+    #[inline]
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::PanLeft => "Pan Left",
+            Self::PanRight => "Pan Right",
+            Self::PanUp => "Pan Up",
+            Self::PanDown => "Pan Down",
+            Self::ZoomIn => "Zoom In",
+            Self::ZoomOut => "Zoom Out",
+            Self::Mandelbrot => "Mandelbrot Set",
+            Self::Julia => "Julia Set",
+            Self::BurningShip => "Burning Ship",
+            Self::Tricorn => "Tricorn",
+            Self::Lyapunov => "Lyapunov",
+            Self::NextColorScheme => "Next Color Scheme",
+            Self::PreviousColorScheme => "Previous Color Scheme",
+            Self::MoreIterations => "More Iterations",
+            Self::FewerIterations => "Fewer Iterations",
+            Self::ToggleSettings => "Toggle Control Panel",
+            Self::ResetView => "Reset View",
+            Self::GoTo => "Go To",
+            Self::OrbitInspector => "Orbit Inspector",
+            Self::Undo => "Undo",
+            Self::Redo => "Redo",
+            Self::ToggleHelp => "Keyboard Shortcuts",
+        }
+    }
+
+    /// Returns all actions, in the order of the help overlay.
+    #[inline]
+    #[must_use]
+    pub const fn all() -> [Self; 22] {
+        [
+            Self::PanLeft,
+            Self::PanRight,
+            Self::PanUp,
+            Self::PanDown,
+            Self::ZoomIn,
+            Self::ZoomOut,
+            Self::Mandelbrot,
+            Self::Julia,
+            Self::BurningShip,
+            Self::Tricorn,
+            Self::Lyapunov,
+            Self::NextColorScheme,
+            Self::PreviousColorScheme,
+            Self::MoreIterations,
+            Self::FewerIterations,
+            Self::ToggleSettings,
+            Self::ResetView,
+            Self::GoTo,
+            Self::OrbitInspector,
+            Self::Undo,
+            Self::Redo,
+            Self::ToggleHelp,
+        ]
+    }
+
+    /// Returns the action called `name`.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all().into_iter().find(|action| action.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Returns whether the action repeats for as long as its key is held, rather than once per press.
+    #[inline]
+    #[must_use]
+    pub const fn is_held(&self) -> bool {
+        matches!(
+            self,
+            Self::PanLeft | Self::PanRight | Self::PanUp | Self::PanDown | Self::ZoomIn | Self::ZoomOut
+        )
+    }
+
+    /// Returns whether key repeat triggers the action again (iterations and history steps do).
+    #[inline]
+    #[must_use]
+    pub const fn is_repeatable(&self) -> bool {
+        matches!(self, Self::MoreIterations | Self::FewerIterations | Self::Undo | Self::Redo)
+    }
+
+    /// Returns the fractal the action switches to, if it is one of the fractal keys.
+    #[inline]
+    #[must_use]
+    pub const fn fractal_type(&self) -> Option<FractalType> {
+        match self {
+            Self::Mandelbrot => Some(FractalType::Mandelbrot),
+            Self::Julia => Some(FractalType::Julia),
+            Self::BurningShip => Some(FractalType::BurningShip),
+            Self::Tricorn => Some(FractalType::Tricorn),
+            Self::Lyapunov => Some(FractalType::Lyapunov),
+            _ => None,
+        }
+    }
+
+    /// Returns the keys bound to the action by default.
+    #[must_use]
+    pub fn default_bindings(&self) -> Vec<KeyboardShortcut> {
+        let key = |key| KeyboardShortcut::new(Modifiers::NONE, key);
+        let shift = |key| KeyboardShortcut::new(Modifiers::SHIFT, key);
+        let command = |key| KeyboardShortcut::new(Modifiers::COMMAND, key);
+        match self {
+            Self::PanLeft => vec![key(Key::ArrowLeft), key(Key::A)],
+            Self::PanRight => vec![key(Key::ArrowRight), key(Key::D)],
+            Self::PanUp => vec![key(Key::ArrowUp), key(Key::W)],
+            Self::PanDown => vec![key(Key::ArrowDown), key(Key::S)],
+            Self::ZoomIn => vec![key(Key::Plus), key(Key::Equals)],
+            Self::ZoomOut => vec![key(Key::Minus)],
+            Self::Mandelbrot => vec![key(Key::Num1)],
+            Self::Julia => vec![key(Key::Num2)],
+            Self::BurningShip => vec![key(Key::Num3)],
+            Self::Tricorn => vec![key(Key::Num4)],
+            Self::Lyapunov => vec![key(Key::Num5)],
+            Self::NextColorScheme => vec![key(Key::C)],
+            Self::PreviousColorScheme => vec![shift(Key::C)],
+            Self::MoreIterations => vec![key(Key::I)],
+            Self::FewerIterations => vec![shift(Key::I)],
+            Self::ToggleSettings => vec![key(Key::P)],
+            Self::ResetView => vec![key(Key::R), key(Key::Home)],
+            Self::GoTo => vec![command(Key::G)],
+            Self::OrbitInspector => vec![key(Key::O)],
+            Self::Undo => vec![command(Key::Z)],
+            Self::Redo => vec![KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::Z), command(Key::Y)],
+            Self::ToggleHelp => vec![key(Key::F1), key(Key::H)],
+        }
+    }
+}
+
+/// Formats a shortcut the way the keymap file spells it, e.g. `Ctrl+Shift+Z`.
+#[must_use]
+pub fn format_shortcut(shortcut: &KeyboardShortcut) -> String {
+    let modifiers = shortcut.modifiers;
+    let mut parts = Vec::new();
+    if modifiers.command || modifiers.ctrl || modifiers.mac_cmd {
+        parts.push("Ctrl");
+    }
+    if modifiers.alt {
+        parts.push("Alt");
+    }
+    if modifiers.shift {
+        parts.push("Shift");
+    }
+    parts.push(shortcut.logical_key.name());
+    parts.join("+")
+}
+
+/// Parses a shortcut such as `Ctrl+Shift+Z`, `Shift+C` or `Left`. `Ctrl` is the Command key on macOS.
+pub fn parse_shortcut(text: &str) -> Result<KeyboardShortcut, String> {
+    let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+    let key_name = parts.pop().unwrap_or_default();
+    let logical_key = Key::from_name(key_name).ok_or_else(|| format!("unknown key '{key_name}' in '{text}'"))?;
+
+    let mut modifiers = Modifiers::NONE;
+    for part in parts {
+        match part.to_ascii_lowercase().as_str() {
+            "ctrl" | "cmd" | "command" => modifiers |= Modifiers::COMMAND,
+            "shift" => modifiers |= Modifiers::SHIFT,
+            "alt" | "option" => modifiers |= Modifiers::ALT,
+            _ => return Err(format!("unknown modifier '{part}' in '{text}'")),
+        }
+    }
+    Ok(KeyboardShortcut::new(modifiers, logical_key))
+}
+
+/// Returns how many modifiers a shortcut needs; more specific shortcuts win over less specific ones.
+const fn modifier_count(modifiers: Modifiers) -> u8 {
+    (modifiers.command || modifiers.ctrl || modifiers.mac_cmd) as u8 + modifiers.shift as u8 + modifiers.alt as u8
+}
+
+/// On-disk layout of the keymap file: action names mapped to their shortcuts.
+#[derive(Serialize, Deserialize)]
+struct KeymapFile {
+    version: u32,
+    bindings: BTreeMap<String, Vec<String>>,
+}
+
+/// The shortcuts bound to each action.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Keymap {
+    pub bindings: BTreeMap<Action, Vec<KeyboardShortcut>>,
+}
+
+impl Default for Keymap {
+    /// Creates the default keymap.
+    #[inline]
+    fn default() -> Self {
+        Self {
+            bindings: Action::all()
+                .into_iter()
+                .map(|action| (action, action.default_bindings()))
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    /// Reads a keymap file. Actions the file does not mention keep their default keys, and unknown
+    /// actions or keys are skipped, so files from older and newer versions load.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let mut keymap = Self::default();
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(keymap),
+            Err(error) => return Err(error),
+        };
+        let file: KeymapFile = serde_json::from_str(&text).map_err(io::Error::other)?;
+        for (name, shortcuts) in file.bindings {
+            if let Some(action) = Action::from_name(&name) {
+                let shortcuts = shortcuts.iter().filter_map(|text| parse_shortcut(text).ok()).collect();
+                keymap.bindings.insert(action, shortcuts);
+            }
+        }
+        Ok(keymap)
+    }
+
+    /// Writes the keymap file; the previous one is replaced only once the new one is complete.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = KeymapFile {
+            version: FORMAT_VERSION,
+            bindings: self
+                .bindings
+                .iter()
+                .map(|(action, shortcuts)| (String::from(action.name()), shortcuts.iter().map(format_shortcut).collect()))
+                .collect(),
+        };
+        let json = serde_json::to_string_pretty(&file).map_err(io::Error::other)?;
+        let partial = path.with_extension("json.part");
+        fs::write(&partial, json + "\n")?;
+        fs::rename(&partial, path)
+    }
+
+    /// Returns the shortcuts of an action.
+    #[must_use]
+    pub fn shortcuts(&self, action: Action) -> &[KeyboardShortcut] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the shortcuts of an action as text, e.g. `Ctrl+Shift+Z, Ctrl+Y`.
+    #[must_use]
+    pub fn describe(&self, action: Action) -> String {
+        let shortcuts: Vec<String> = self.shortcuts(action).iter().map(format_shortcut).collect();
+        shortcuts.join(", ")
+    }
+
+    /// Returns the action triggered by pressing `key` with `modifiers` held. When several
+    /// shortcuts match (`Ctrl+Z` also matches `Ctrl+Shift+Z`), the most specific one wins.
+    #[must_use]
+    pub fn action_for(&self, modifiers: Modifiers, key: Key) -> Option<Action> {
+        self.bindings
+            .iter()
+            .flat_map(|(action, shortcuts)| shortcuts.iter().map(move |shortcut| (*action, shortcut)))
+            .filter(|(_, shortcut)| shortcut.logical_key == key && modifiers.matches_logically(shortcut.modifiers))
+            .max_by_key(|(_, shortcut)| modifier_count(shortcut.modifiers))
+            .map(|(action, _)| action)
+    }
+
+    /// Binds `shortcut` to `action` alone, replacing the action's keys and unbinding it elsewhere.
+    pub fn rebind(&mut self, action: Action, shortcut: KeyboardShortcut) {
+        for shortcuts in self.bindings.values_mut() {
+            shortcuts.retain(|bound| *bound != shortcut);
+        }
+        self.bindings.insert(action, vec![shortcut]);
+    }
+}
+
+/// Keyboard state of the viewer: the keymap, the help overlay and key capture for rebinding.
+#[derive(Default)]
+pub struct Keyboard {
+    pub keymap: Keymap,
+    pub show_help: bool,
+    pub navigating: bool, // A pan or zoom key is held
+    pub status: Option<String>,
+    path: Option<PathBuf>, // Keymap file, once looked up
+    capturing: Option<Action>,
+}
+
+impl Keyboard {
+    /// Loads the keymap from the configuration directory unless already loaded.
+    pub fn ensure_loaded(&mut self) {
+        if self.path.is_some() {
+            return;
+        }
+        let Some(dir) = config::config_dir() else {
+            self.path = Some(PathBuf::new());
+            return;
+        };
+        let path = dir.join(KEYMAP_FILE);
+        match Keymap::load(&path) {
+            Ok(keymap) => self.keymap = keymap,
+            Err(error) => self.status = Some(format!("❌ Could not read {}: {error}", path.display())),
+        }
+        self.path = Some(path);
+    }
+
+    /// Saves the keymap to the configuration directory.
+    fn save(&mut self) {
+        let Some(path) = self.path.as_ref().filter(|path| !path.as_os_str().is_empty()) else {
+            self.status = Some(String::from("❌ No configuration directory to keep the keymap in"));
+            return;
+        };
+        self.status = match self.keymap.save(path) {
+            Ok(()) => Some(format!("Saved to {}", path.display())),
+            Err(error) => Some(format!("❌ Could not save the keymap: {error}")),
+        };
+    }
+}
+
+impl FractalApp {
+    /// Runs the actions of the keys pressed or held this frame, unless a text field has focus.
+    pub fn handle_keyboard_input(&mut self, ctx: &egui::Context) {
+        self.keyboard.ensure_loaded();
+        self.keyboard.navigating = false;
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        if let Some(action) = self.keyboard.capturing {
+            self.capture_shortcut(ctx, action);
+            return;
+        }
+
+        let keymap = &self.keyboard.keymap;
+        let (pressed, held, dt) = ctx.input(|i| {
+            let pressed: Vec<Action> = i
+                .events
+                .iter()
+                .filter_map(|event| match event {
+                    egui::Event::Key {
+                        key,
+                        pressed: true,
+                        repeat,
+                        modifiers,
+                        ..
+                    } => keymap
+                        .action_for(*modifiers, *key)
+                        // Held actions run every frame below; key repeat would only make them jerky
+                        .filter(|action| !action.is_held() && (!repeat || action.is_repeatable())),
+                    _ => None,
+                })
+                .collect();
+            let held: Vec<Action> = Action::all()
+                .into_iter()
+                .filter(|action| action.is_held())
+                .filter(|action| {
+                    keymap.shortcuts(*action).iter().any(|shortcut| {
+                        i.key_down(shortcut.logical_key) && i.modifiers.matches_logically(shortcut.modifiers)
+                    })
+                })
+                .collect();
+            (pressed, held, f64::from(i.stable_dt.min(0.1)))
+        });
+
+        for action in pressed {
+            self.run_action(action);
+        }
+        for action in held {
+            self.run_held_action(action, dt);
+        }
+        if self.keyboard.navigating {
+            ctx.request_repaint();
+        }
+    }
+
+    /// Runs a pan or zoom action for `dt` seconds.
+    fn run_held_action(&mut self, action: Action, dt: f64) {
+        self.navigation.transition = None;
+        let step = PAN_SPEED * dt * 4.0 / self.zoom;
+        match action {
+            Action::PanLeft => self.center.x -= step,
+            Action::PanRight => self.center.x += step,
+            Action::PanUp => self.center.y -= step,
+            Action::PanDown => self.center.y += step,
+            Action::ZoomIn => self.zoom *= ZOOM_SPEED.powf(dt),
+            Action::ZoomOut => self.zoom /= ZOOM_SPEED.powf(dt),
+            _ => return,
+        }
+        self.keyboard.navigating = true;
+        self.needs_update = true;
+    }
+
+    /// Runs a single-press action.
+    pub fn run_action(&mut self, action: Action) {
+        if let Some(fractal_type) = action.fractal_type() {
+            if self.fractal_type != fractal_type {
+                self.fractal_type = fractal_type;
+                self.center = fractal_type.default_center();
+                self.zoom = 1.0;
+                self.needs_update = true;
+            }
+            return;
+        }
+        match action {
+            Action::NextColorScheme | Action::PreviousColorScheme => {
+                let schemes = ColorScheme::all();
+                let index = schemes.iter().position(|scheme| *scheme == self.color_scheme).unwrap_or(0);
+                let offset = if action == Action::NextColorScheme { 1 } else { schemes.len() - 1 };
+                self.color_scheme = schemes[(index + offset) % schemes.len()];
+                self.use_custom_gradient = false;
+                self.needs_recolor = true;
+            }
+            Action::MoreIterations | Action::FewerIterations => {
+                let factor = if action == Action::MoreIterations { ITERATION_STEP } else { 1.0 / ITERATION_STEP };
+                // Same range as the iteration slider
+                self.max_iterations = (f64::from(self.max_iterations) * factor).round().clamp(10.0, 3000.0) as u16;
+                self.needs_update = true;
+            }
+            Action::ToggleSettings => self.show_settings = !self.show_settings,
+            Action::ResetView => self.go_to_view(self.fractal_type.default_center(), 1.0),
+            Action::GoTo => self.open_go_to(),
+            Action::OrbitInspector => self.orbit_inspector.open = !self.orbit_inspector.open,
+            Action::Undo => self.undo(),
+            Action::Redo => self.redo(),
+            Action::ToggleHelp => self.keyboard.show_help = !self.keyboard.show_help,
+            _ => {}
+        }
+    }
+
+    /// Waits for the next key press and binds it to `action`; Escape cancels.
+    fn capture_shortcut(&mut self, ctx: &egui::Context, action: Action) {
+        let pressed = ctx.input(|i| {
+            i.events.iter().find_map(|event| match event {
+                egui::Event::Key {
+                    key,
+                    pressed: true,
+                    modifiers,
+                    ..
+                } => Some(KeyboardShortcut::new(*modifiers, *key)),
+                _ => None,
+            })
+        });
+        let Some(mut shortcut) = pressed else {
+            return;
+        };
+        self.keyboard.capturing = None;
+        if shortcut.logical_key == Key::Escape {
+            return;
+        }
+        // Store Ctrl portably: it is the Command key on macOS
+        if shortcut.modifiers.command || shortcut.modifiers.ctrl || shortcut.modifiers.mac_cmd {
+            shortcut.modifiers = Modifiers {
+                ctrl: false,
+                mac_cmd: false,
+                command: true,
+                ..shortcut.modifiers
+            };
+        }
+        self.keyboard.keymap.rebind(action, shortcut);
+        self.keyboard.save();
+    }
+
+    /// Shows the overlay listing every shortcut, where each action can be rebound.
+    pub fn keymap_help_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.keyboard.show_help;
+        let mut reset = false;
+
+        egui::Window::new("⌨ Keyboard Shortcuts")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                egui::Grid::new("keymap_grid")
+                    .num_columns(3)
+                    .spacing([16.0, 4.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for action in Action::all() {
+                            ui.label(action.name());
+                            if self.keyboard.capturing == Some(action) {
+                                ui.label(egui::RichText::new("Press a key... (Esc cancels)").italics());
+                            } else {
+                                ui.monospace(self.keyboard.keymap.describe(action));
+                            }
+                            if ui.small_button("✏").on_hover_text("Change the key").clicked() {
+                                self.keyboard.capturing = Some(action);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Restore Defaults").clicked() {
+                        reset = true;
+                    }
+                    if let Some(status) = &self.keyboard.status {
+                        ui.label(egui::RichText::new(status).small().weak());
+                    }
+                });
+            });
+
+        if reset {
+            self.keyboard.keymap = Keymap::default();
+            self.keyboard.capturing = None;
+            self.keyboard.save();
+        }
+        if !open {
+            self.keyboard.capturing = None;
+        }
+        self.keyboard.show_help = open;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortcut_text_round_trip() {
+        for text in ["Ctrl+Shift+Z", "Shift+C", "Left", "Plus", "1", "F1", "Alt+Home"] {
+            assert_eq!(format_shortcut(&parse_shortcut(text).unwrap()), text);
+        }
+        assert_eq!(parse_shortcut("cmd + y").unwrap(), KeyboardShortcut::new(Modifiers::COMMAND, Key::Y));
+        assert!(parse_shortcut("Hyper+Z").is_err());
+        assert!(parse_shortcut("Ctrl+").is_err());
+    }
+
+    #[test]
+    fn test_most_specific_shortcut_wins() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.action_for(Modifiers::COMMAND, Key::Z), Some(Action::Undo));
+        assert_eq!(keymap.action_for(Modifiers::COMMAND | Modifiers::SHIFT, Key::Z), Some(Action::Redo));
+        assert_eq!(keymap.action_for(Modifiers::NONE, Key::C), Some(Action::NextColorScheme));
+        assert_eq!(keymap.action_for(Modifiers::SHIFT, Key::C), Some(Action::PreviousColorScheme));
+        assert_eq!(keymap.action_for(Modifiers::NONE, Key::Num2), Some(Action::Julia));
+        // Plain letters do not fire while Ctrl is held
+        assert_eq!(keymap.action_for(Modifiers::COMMAND, Key::S), None);
+    }
+
+    #[test]
+    fn test_keymap_file_round_trip() {
+        let path = std::env::temp_dir().join(format!("fractals_rs_keymap_{}.json", std::process::id()));
+        let mut keymap = Keymap::default();
+        keymap.rebind(Action::ResetView, parse_shortcut("C").unwrap());
+        assert!(keymap.shortcuts(Action::NextColorScheme).is_empty());
+        keymap.save(&path).unwrap();
+        assert_eq!(Keymap::load(&path).unwrap(), keymap);
+
+        // Missing actions keep their defaults; unknown actions and keys are skipped
+        fs::write(
+            &path,
+            r#"{ "version": 1, "bindings": { "Zoom In": ["Q", "NoSuchKey"], "Teleport": ["T"] } }"#,
+        )
+        .unwrap();
+        let loaded = Keymap::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.shortcuts(Action::ZoomIn), [parse_shortcut("Q").unwrap()]);
+        assert_eq!(loaded.shortcuts(Action::Undo), Action::Undo.default_bindings());
+    }
+
+    #[test]
+    fn test_keyboard_actions() {
+        let mut app = FractalApp::default();
+        app.run_action(Action::Julia);
+        assert_eq!(app.fractal_type, FractalType::Julia);
+        app.run_action(Action::PreviousColorScheme);
+        assert_eq!(app.color_scheme, *ColorScheme::all().last().unwrap());
+        app.run_action(Action::NextColorScheme);
+        assert_eq!(app.color_scheme, ColorScheme::default());
+        app.run_action(Action::MoreIterations);
+        assert_eq!(app.max_iterations, 375);
+
+        let center = app.center;
+        app.run_held_action(Action::PanRight, 0.5);
+        assert!(app.center.x > center.x);
+        assert_eq!(app.center.y, center.y);
+        assert!(app.keyboard.navigating);
+    }
+}
@@ -1,2 +1,16 @@
 pub mod fractal_app;
 pub mod frame_actions;
+pub mod gradient_editor;
+pub mod export_dialog;
+pub mod animation_dialog;
+pub mod julia_path_dialog;
+pub mod bookmarks_panel;
+pub mod scene_dialog;
+pub mod history;
+pub mod keymap;
+pub mod navigation;
+pub mod goto_dialog;
+pub mod orbit_inspector;
+pub mod overlays;
+pub mod density_dialog;
+pub mod ifs_dialog;
\ No newline at end of file
@@ -0,0 +1,231 @@
+use crate::animation::keyframe::Keyframe;
+use crate::ui::fractal_app::FractalApp;
+use crate::utils::point::Point;
+use eframe::epaint::{Color32, Stroke, StrokeKind};
+use std::time::{Duration, Instant};
+
+/// Length of the animated transition between two views.
+pub const TRANSITION_DURATION: Duration = Duration::from_millis(350);
+
+/// Selections smaller than this many pixels on either side are ignored.
+const MIN_BOX_SIZE: f32 = 4.0;
+
+/// What a box drawn over the view does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum BoxZoom {
+    In,  // Shift-drag: the selected region fills the view
+    Out, // Ctrl-drag: the current view shrinks into the selected region
+}
+
+/// An animated move from one view to another.
+#[derive(Clone, Copy, Debug)]
+pub struct ViewTransition {
+    from: Keyframe,
+    to: Keyframe,
+    start: Instant,
+    finished: bool, // Set on the last frame, so the whole move is one history step
+}
+
+/// State of the mouse navigation beyond plain panning and scrolling.
+#[derive(Clone, Copy, Debug)]
+pub struct Navigation {
+    pub double_click_zoom: f64, // Zoom factor of a double-click
+    pub animate: bool,          // Whether double-click and box zooms are animated
+    pub selection: Option<(egui::Pos2, BoxZoom)>, // Anchor of the box being drawn
+    pub transition: Option<ViewTransition>,
+    pub pan_remainder: egui::Vec2, // Fraction of an image pixel dragged but not yet panned
+}
+
+impl Default for Navigation {
+    /// Doubles the zoom on double-click, with animated transitions.
+    #[inline]
+    fn default() -> Self {
+        Self {
+            double_click_zoom: 2.0,
+            animate: true,
+            selection: None,
+            transition: None,
+            pan_remainder: egui::Vec2::ZERO,
+        }
+    }
+}
+
+impl Navigation {
+    /// Returns whether a transition is playing.
+    #[inline]
+    #[must_use]
+    pub const fn is_animating(&self) -> bool {
+        self.transition.is_some()
+    }
+}
+
+/// Returns the center and zoom that fit `selection` (in screen space over `image_rect`) into the
+/// view for `BoxZoom::In`, or that fit the current view into `selection` for `BoxZoom::Out`.
+pub fn box_zoom_target(
+    app: &FractalApp,
+    selection: egui::Rect,
+    image_rect: egui::Rect,
+    mode: BoxZoom,
+) -> (Point, f64) {
+    // The side that limits the fit decides the factor, so the whole region stays visible
+    let factor = f64::from((image_rect.width() / selection.width()).min(image_rect.height() / selection.height()));
+    match mode {
+        BoxZoom::In => (app.screen_to_complex(selection.center(), image_rect), app.zoom * factor),
+        BoxZoom::Out => {
+            let zoom = app.zoom / factor;
+            // The old center must land on the middle of the box
+            let units_per_pixel = 4.0 / zoom / f64::from(image_rect.height());
+            let offset = selection.center() - image_rect.center();
+            let center = Point::new(
+                f64::from(offset.x).mul_add(-units_per_pixel, app.center.x),
+                f64::from(offset.y).mul_add(-units_per_pixel, app.center.y),
+            );
+            (center, zoom)
+        }
+    }
+}
+
+impl FractalApp {
+    /// Moves the view to `center` and `zoom`, animated unless animations are turned off.
+    pub fn go_to_view(&mut self, center: Point, zoom: f64) {
+        if self.navigation.animate {
+            let from = self.current_keyframe();
+            self.navigation.transition = Some(ViewTransition {
+                from,
+                to: Keyframe { center, zoom, ..from },
+                start: Instant::now(),
+                finished: false,
+            });
+        } else {
+            self.center = center;
+            self.zoom = zoom;
+            self.needs_update = true;
+        }
+    }
+
+    /// Moves the playing transition forward; the view is rendered without supersampling until it ends.
+    pub fn advance_transition(&mut self, ctx: &egui::Context) {
+        let Some(transition) = &mut self.navigation.transition else {
+            return;
+        };
+        if transition.finished {
+            self.navigation.transition = None;
+            // Render the final view at full quality
+            self.needs_update = true;
+            return;
+        }
+
+        let t = (transition.start.elapsed().as_secs_f64() / TRANSITION_DURATION.as_secs_f64()).min(1.0);
+        transition.finished = t >= 1.0;
+        let keyframe = if transition.finished {
+            transition.to
+        } else {
+            transition.from.interpolate(&transition.to, t)
+        };
+        self.center = keyframe.center;
+        self.zoom = keyframe.zoom;
+        self.needs_update = true;
+        ctx.request_repaint();
+    }
+
+    /// Handles shift-drag (zoom in) and ctrl-drag (zoom out) boxes; returns whether the drag was used.
+    pub fn handle_box_zoom(&mut self, response: &egui::Response, image_rect: egui::Rect) -> bool {
+        if response.drag_started() {
+            let (modifiers, origin) = response.ctx.input(|i| (i.modifiers, i.pointer.press_origin()));
+            let mode = if modifiers.shift {
+                Some(BoxZoom::In)
+            } else if modifiers.command || modifiers.ctrl {
+                Some(BoxZoom::Out)
+            } else {
+                None
+            };
+            self.navigation.selection = mode.zip(origin).map(|(mode, origin)| (origin, mode));
+        }
+        let Some((anchor, mode)) = self.navigation.selection else {
+            return false;
+        };
+
+        let pointer = response.ctx.input(|i| i.pointer.latest_pos()).unwrap_or(anchor);
+        let selection = egui::Rect::from_two_pos(anchor, pointer).intersect(image_rect);
+        if response.dragged() {
+            let color = match mode {
+                BoxZoom::In => Color32::WHITE,
+                BoxZoom::Out => Color32::from_rgb(255, 180, 80),
+            };
+            let painter = response.ctx.layer_painter(response.layer_id);
+            painter.rect_filled(selection, 0.0, color.gamma_multiply(0.15));
+            painter.rect_stroke(selection, 0.0, Stroke::new(1.0, color), StrokeKind::Inside);
+        }
+        if response.drag_stopped() {
+            self.navigation.selection = None;
+            if selection.width() >= MIN_BOX_SIZE && selection.height() >= MIN_BOX_SIZE {
+                let (center, zoom) = box_zoom_target(self, selection, image_rect, mode);
+                self.go_to_view(center, zoom);
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app() -> FractalApp {
+        FractalApp {
+            center: Point::new(-0.5, 0.25),
+            zoom: 3.0,
+            ..FractalApp::default()
+        }
+    }
+
+    #[test]
+    fn test_box_zoom_in_fits_selection() {
+        let app = app();
+        let image_rect = egui::Rect::from_min_size(egui::pos2(0.0, 0.0), egui::vec2(800.0, 600.0));
+        // A wide box: its width limits the fit
+        let selection = egui::Rect::from_min_max(egui::pos2(100.0, 200.0), egui::pos2(300.0, 250.0));
+        let (center, zoom) = box_zoom_target(&app, selection, image_rect, BoxZoom::In);
+        assert!((zoom - 12.0).abs() < 1e-9);
+
+        let zoomed = FractalApp { center, zoom, ..FractalApp::default() };
+        let left = zoomed.screen_to_complex(image_rect.left_center(), image_rect);
+        let expected = app.screen_to_complex(selection.left_center(), image_rect);
+        assert!((left.x - expected.x).abs() < 1e-9);
+        assert!((left.y - expected.y).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_box_zoom_out_maps_view_into_selection() {
+        let app = app();
+        let image_rect = egui::Rect::from_min_size(egui::pos2(0.0, 0.0), egui::vec2(800.0, 600.0));
+        let selection = egui::Rect::from_center_size(egui::pos2(600.0, 150.0), egui::vec2(200.0, 150.0));
+        let (center, zoom) = box_zoom_target(&app, selection, image_rect, BoxZoom::Out);
+        assert!((zoom - 0.75).abs() < 1e-9);
+
+        // The corners of the old view land on the corners of the box
+        let zoomed = FractalApp { center, zoom, ..FractalApp::default() };
+        for (view_corner, box_corner) in [
+            (image_rect.left_top(), selection.left_top()),
+            (image_rect.right_bottom(), selection.right_bottom()),
+        ] {
+            let point = app.screen_to_complex(view_corner, image_rect);
+            let screen = zoomed.complex_to_screen(point, image_rect);
+            assert!((screen - box_corner).length() < 1e-2);
+        }
+    }
+
+    #[test]
+    fn test_transition_ends_on_target() {
+        let mut app = app();
+        let ctx = egui::Context::default();
+        app.go_to_view(Point::new(0.3, -0.1), 48.0);
+        app.navigation.transition.as_mut().unwrap().start -= TRANSITION_DURATION;
+        app.advance_transition(&ctx);
+        assert_eq!((app.center, app.zoom), (Point::new(0.3, -0.1), 48.0));
+        assert!(app.navigation.is_animating());
+        app.advance_transition(&ctx);
+        assert!(!app.navigation.is_animating());
+    }
+}
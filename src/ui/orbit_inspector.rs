@@ -0,0 +1,227 @@
+use crate::fractals::fractal_type::FractalType;
+use crate::fractals::orbit::Orbit;
+use crate::ui::fractal_app::FractalApp;
+use crate::utils::point::Point;
+use eframe::epaint::{Color32, Stroke};
+use std::time::{Duration, Instant};
+
+/// Delay between two steps while the orbit plays.
+const STEP_INTERVAL: Duration = Duration::from_millis(120);
+
+/// State of the orbit inspector: the point under the cursor (or pinned with a click) and how far
+/// through its orbit the overlay is drawn.
+#[derive(Default)]
+pub struct OrbitInspector {
+    pub open: bool,
+    pub pinned: bool,           // A click fixes the inspected point until the next click
+    pub point: Option<Point>,   // Inspected point of the plane
+    pub orbit: Option<Orbit>,
+    pub step: usize,            // Index of the last drawn orbit point
+    pub follow: bool,           // Whether the overlay shows the whole orbit as the point moves
+    pub playing: bool,
+    last_step: Option<Instant>, // When the playing orbit last advanced
+    computed_for: Option<(Point, FractalType, u16, Point)>, // Point and parameters of `orbit`
+}
+
+impl OrbitInspector {
+    /// Returns the number of the last orbit point.
+    #[inline]
+    #[must_use]
+    pub fn last_step(&self) -> usize {
+        self.orbit.as_ref().map_or(0, |orbit| orbit.points.len() - 1)
+    }
+}
+
+impl FractalApp {
+    /// Recomputes the orbit of the inspected point if the point, the fractal, the iteration limit
+    /// or the Julia constant changed since it was computed.
+    pub fn update_orbit(&mut self) {
+        let inspector = &mut self.orbit_inspector;
+        let key = inspector
+            .point
+            .map(|point| (point, self.fractal_type, self.max_iterations, self.julia_c));
+        if key == inspector.computed_for {
+            return;
+        }
+        inspector.computed_for = key;
+        // Lyapunov fractals iterate a logistic map, not a point of the plane: there is no orbit to show
+        inspector.orbit = key.and_then(|(point, fractal_type, max_iterations, julia_c)| {
+            fractal_type
+                .escape_time()
+                .map(|fractal| Orbit::compute(fractal, point, max_iterations, julia_c))
+        });
+        if inspector.follow || inspector.step > inspector.last_step() {
+            inspector.step = inspector.last_step();
+        }
+    }
+
+    /// Tracks the cursor (or a pinned point) and draws its orbit as a polyline over the view.
+    pub fn orbit_overlay(&mut self, response: &egui::Response, image_rect: egui::Rect) {
+        if response.clicked()
+            && let Some(position) = response.interact_pointer_pos()
+        {
+            // A click pins the point under the cursor, a second click releases it
+            self.orbit_inspector.pinned = !self.orbit_inspector.pinned;
+            self.orbit_inspector.point = Some(self.screen_to_complex(position, image_rect));
+            self.orbit_inspector.follow = true;
+        } else if !self.orbit_inspector.pinned
+            && let Some(position) = response.hover_pos()
+        {
+            let point = self.screen_to_complex(position, image_rect);
+            if self.orbit_inspector.point != Some(point) {
+                self.orbit_inspector.point = Some(point);
+                self.orbit_inspector.follow = true;
+            }
+        }
+        self.update_orbit();
+
+        let Some(orbit) = &self.orbit_inspector.orbit else {
+            return;
+        };
+        // Escaping points can be astronomically far away; keep them representable on screen
+        let far = image_rect.expand(image_rect.width().max(image_rect.height()) * 4.0);
+        let to_screen = |point: Point| {
+            let position = self.complex_to_screen(point, image_rect);
+            egui::pos2(position.x.clamp(far.min.x, far.max.x), position.y.clamp(far.min.y, far.max.y))
+        };
+
+        let painter = response.ctx.layer_painter(response.layer_id).with_clip_rect(image_rect);
+        let shown = &orbit.points[..=self.orbit_inspector.step.min(orbit.points.len() - 1)];
+        let line: Vec<egui::Pos2> = shown.iter().map(|point| to_screen(*point)).collect();
+        let color = if orbit.escaped {
+            Color32::from_rgb(255, 120, 80)
+        } else {
+            Color32::from_rgb(120, 220, 255)
+        };
+        painter.add(egui::Shape::line(line.clone(), Stroke::new(1.5, color.gamma_multiply(0.8))));
+        for position in &line {
+            painter.circle_filled(*position, 2.5, color);
+        }
+        painter.circle_stroke(to_screen(orbit.c), 5.0, Stroke::new(1.5, Color32::WHITE));
+        if let Some(current) = line.last() {
+            painter.circle_stroke(*current, 6.0, Stroke::new(2.0, Color32::YELLOW));
+        }
+    }
+
+    /// Shows the inspector window: coordinates, iteration count, escape value and step controls.
+    pub fn orbit_inspector_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.orbit_inspector.open;
+        let last = self.orbit_inspector.last_step();
+
+        if self.orbit_inspector.playing {
+            let due = self
+                .orbit_inspector
+                .last_step
+                .is_none_or(|time| time.elapsed() >= STEP_INTERVAL);
+            if due {
+                self.orbit_inspector.step = (self.orbit_inspector.step + 1).min(last);
+                self.orbit_inspector.last_step = Some(Instant::now());
+                self.orbit_inspector.playing = self.orbit_inspector.step < last;
+            }
+            ctx.request_repaint_after(STEP_INTERVAL);
+        }
+
+        egui::Window::new("🔍 Orbit Inspector")
+            .open(&mut open)
+            .default_width(280.0)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let inspector = &mut self.orbit_inspector;
+                let Some(orbit) = &inspector.orbit else {
+                    ui.label(if self.fractal_type.escape_time().is_some() {
+                        "Hover over the fractal to inspect a point; click to pin it."
+                    } else {
+                        "Lyapunov fractals have no orbits in the plane."
+                    });
+                    return;
+                };
+                let point = inspector.point.unwrap_or(orbit.c);
+                egui::Grid::new("orbit_grid").num_columns(2).spacing([10.0, 4.0]).show(ui, |ui| {
+                    ui.label("Point:");
+                    ui.monospace(format!("{:.12} {:+.12}i", point.x, point.y));
+                    ui.end_row();
+
+                    ui.label("Iterations:");
+                    ui.monospace(if orbit.escaped {
+                        format!("{} (escaped)", orbit.iterations)
+                    } else {
+                        format!("{} (bounded)", orbit.iterations)
+                    });
+                    ui.end_row();
+
+                    ui.label("|zₙ|:");
+                    ui.monospace(format!("{:.6}", orbit.escape_modulus()));
+                    ui.end_row();
+
+                    ui.label("Smooth count:");
+                    ui.monospace(orbit.smooth_iterations().map_or_else(|| String::from("—"), |smooth| format!("{smooth:.4}")));
+                    ui.end_row();
+
+                    let z = orbit.points[inspector.step.min(last)];
+                    ui.label(format!("z{}:", inspector.step));
+                    ui.monospace(format!("{:.9} {:+.9}i", z.x, z.y));
+                    ui.end_row();
+                });
+
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    if ui.button("⏮").on_hover_text("First point").clicked() {
+                        inspector.step = 0;
+                        inspector.follow = false;
+                    }
+                    if ui.button("◀").on_hover_text("Previous point").clicked() {
+                        inspector.step = inspector.step.saturating_sub(1);
+                        inspector.follow = false;
+                    }
+                    let play = if inspector.playing { "⏸" } else { "▶ Play" };
+                    if ui.button(play).clicked() {
+                        inspector.playing = !inspector.playing;
+                        if inspector.playing && inspector.step >= last {
+                            inspector.step = 0;
+                        }
+                        inspector.follow = false;
+                        inspector.last_step = None;
+                    }
+                    if ui.button("▶").on_hover_text("Next point").clicked() {
+                        inspector.step = (inspector.step + 1).min(last);
+                        inspector.follow = false;
+                    }
+                    if ui.button("⏭").on_hover_text("Whole orbit").clicked() {
+                        inspector.step = last;
+                        inspector.follow = true;
+                    }
+                });
+                if ui.add(egui::Slider::new(&mut inspector.step, 0..=last).text("Step")).changed() {
+                    inspector.follow = inspector.step == last;
+                }
+                ui.checkbox(&mut inspector.pinned, "📌 Pinned (click the view to pin or release)");
+                ui.label(egui::RichText::new("Computed in double precision").small().weak());
+            });
+
+        if !open {
+            self.orbit_inspector = OrbitInspector::default();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orbit_follows_parameters() {
+        let mut app = FractalApp::default();
+        app.orbit_inspector.point = Some(Point::new(0.3, 0.5));
+        app.orbit_inspector.follow = true;
+        app.update_orbit();
+        let mandelbrot = app.orbit_inspector.orbit.clone().unwrap();
+        assert_eq!(app.orbit_inspector.step, mandelbrot.points.len() - 1);
+
+        app.fractal_type = FractalType::Julia;
+        app.update_orbit();
+        let julia = app.orbit_inspector.orbit.as_ref().unwrap();
+        assert_eq!(julia.points[0], Point::new(0.3, 0.5));
+        assert_eq!(julia.c, app.julia_c);
+        assert_ne!(julia, &mandelbrot);
+    }
+}
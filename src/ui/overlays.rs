@@ -0,0 +1,198 @@
+use crate::fractals::fractal_type::FractalType;
+use crate::fractals::mariani_silver::RenderAlgorithm;
+use crate::ui::fractal_app::FractalApp;
+use crate::utils::point::Point;
+use eframe::epaint::{Color32, FontId, Stroke};
+
+/// Approximate distance between two grid lines, in pixels.
+const GRID_TARGET_PIXELS: f64 = 110.0;
+
+/// Approximate length of the scale bar, in pixels.
+const SCALE_BAR_TARGET_PIXELS: f64 = 120.0;
+
+/// Which guides are drawn over the view, and where the cursor points.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Overlays {
+    pub axes: bool,
+    pub grid: bool,
+    pub scale_bar: bool,
+    pub crosshair: bool,
+    pub cursor: Option<Point>, // Point of the plane under the cursor, shown in the status bar
+}
+
+/// Returns the "nice" step (1, 2 or 5 times a power of ten) closest above `raw`.
+#[must_use]
+pub fn nice_step(raw: f64) -> f64 {
+    let magnitude = 10f64.powf(raw.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= raw)
+        .unwrap_or(10.0 * magnitude)
+}
+
+/// Returns the number of decimals needed to tell apart values `step` apart.
+#[must_use]
+pub fn step_decimals(step: f64) -> usize {
+    (-step.log10().floor()).clamp(0.0, 17.0) as usize
+}
+
+/// Formats a length of the plane for the scale bar: plain decimals near 1, scientific otherwise.
+#[must_use]
+pub fn format_length(length: f64) -> String {
+    if (1e-3..1e4).contains(&length) {
+        format!("{length:.prec$}", prec = step_decimals(length))
+    } else {
+        format!("{length:.0e}")
+    }
+}
+
+/// Draws text with a dark halo so it stays readable over any colors.
+fn outlined_text(painter: &egui::Painter, position: egui::Pos2, align: egui::Align2, text: &str) {
+    let font = FontId::monospace(11.0);
+    for offset in [egui::vec2(1.0, 1.0), egui::vec2(-1.0, -1.0)] {
+        painter.text(position + offset, align, text, font.clone(), Color32::from_black_alpha(200));
+    }
+    painter.text(position, align, text, font, Color32::WHITE);
+}
+
+impl FractalApp {
+    /// Returns the size of one pixel in units of the plane, for a view drawn in `image_rect`.
+    #[must_use]
+    pub fn units_per_pixel(&self, image_rect: egui::Rect) -> f64 {
+        4.0 / self.zoom / f64::from(image_rect.height())
+    }
+
+    /// Records the cursor position and draws the enabled guides over the view.
+    pub fn draw_overlays(&mut self, response: &egui::Response, image_rect: egui::Rect) {
+        self.overlays.cursor = response
+            .hover_pos()
+            .map(|position| self.screen_to_complex(position, image_rect));
+
+        let overlays = self.overlays;
+        let painter = response.ctx.layer_painter(response.layer_id).with_clip_rect(image_rect);
+        if overlays.grid {
+            self.draw_grid(&painter, image_rect);
+        }
+        if overlays.axes {
+            let origin = self.complex_to_screen(Point::new(0.0, 0.0), image_rect);
+            let stroke = Stroke::new(1.5, Color32::from_white_alpha(200));
+            painter.hline(image_rect.x_range(), origin.y, stroke);
+            painter.vline(origin.x, image_rect.y_range(), stroke);
+        }
+        if overlays.scale_bar {
+            self.draw_scale_bar(&painter, image_rect);
+        }
+        if overlays.crosshair
+            && let Some(position) = response.hover_pos()
+        {
+            let stroke = Stroke::new(1.0, Color32::from_white_alpha(160));
+            painter.hline(image_rect.x_range(), position.y, stroke);
+            painter.vline(position.x, image_rect.y_range(), stroke);
+        }
+    }
+
+    /// Draws grid lines at a round spacing that follows the zoom, labeled in plane units.
+    fn draw_grid(&self, painter: &egui::Painter, image_rect: egui::Rect) {
+        let step = nice_step(GRID_TARGET_PIXELS * self.units_per_pixel(image_rect));
+        let decimals = step_decimals(step);
+        let min = self.screen_to_complex(image_rect.min, image_rect);
+        let max = self.screen_to_complex(image_rect.max, image_rect);
+        let stroke = Stroke::new(1.0, Color32::from_white_alpha(60));
+
+        // Lines are numbered from the origin, so they stay put while panning
+        let lines = |from: f64, to: f64| (from / step).ceil() as i64..=(to / step).floor() as i64;
+        for n in lines(min.x, max.x) {
+            let x = n as f64 * step;
+            let screen = self.complex_to_screen(Point::new(x, min.y), image_rect).x;
+            painter.vline(screen, image_rect.y_range(), stroke);
+            let label = format!("{x:.decimals$}");
+            outlined_text(painter, egui::pos2(screen + 3.0, image_rect.bottom() - 3.0), egui::Align2::LEFT_BOTTOM, &label);
+        }
+        for n in lines(min.y, max.y) {
+            let y = n as f64 * step;
+            let screen = self.complex_to_screen(Point::new(min.x, y), image_rect).y;
+            painter.hline(image_rect.x_range(), screen, stroke);
+            let label = format!("{y:.decimals$}i");
+            outlined_text(painter, egui::pos2(image_rect.left() + 3.0, screen - 2.0), egui::Align2::LEFT_BOTTOM, &label);
+        }
+    }
+
+    /// Draws a bar of round length in the bottom-right corner of the view.
+    fn draw_scale_bar(&self, painter: &egui::Painter, image_rect: egui::Rect) {
+        let units_per_pixel = self.units_per_pixel(image_rect);
+        let length = nice_step(SCALE_BAR_TARGET_PIXELS * units_per_pixel);
+        let pixels = (length / units_per_pixel) as f32;
+
+        let right = image_rect.right() - 16.0;
+        let y = image_rect.bottom() - 24.0;
+        let stroke = Stroke::new(2.0, Color32::WHITE);
+        let shadow = Stroke::new(4.0, Color32::from_black_alpha(160));
+        for stroke in [shadow, stroke] {
+            painter.hline((right - pixels)..=right, y, stroke);
+            painter.vline(right - pixels, (y - 5.0)..=(y + 5.0), stroke);
+            painter.vline(right, (y - 5.0)..=(y + 5.0), stroke);
+        }
+        outlined_text(painter, egui::pos2(right - pixels / 2.0, y - 6.0), egui::Align2::CENTER_BOTTOM, &format_length(length));
+    }
+
+    /// Shows the status bar: cursor coordinates, zoom and the size of a pixel.
+    pub fn status_bar(&self, ctx: &egui::Context) {
+        egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                // Enough digits to tell neighbouring pixels apart
+                let pixel = 4.0 / self.zoom / f64::from(self.image_size.1.max(1));
+                let decimals = step_decimals(pixel) + 1;
+                match self.overlays.cursor {
+                    Some(cursor) => ui.monospace(format!("{:.decimals$} {:+.decimals$}i", cursor.x, cursor.y)),
+                    None => ui.monospace("—"),
+                };
+                ui.separator();
+                ui.monospace(format!("Zoom {:.3e}×", self.zoom));
+                ui.separator();
+                ui.monospace(format!("Pixel {pixel:.2e}"));
+                if self.algorithm == RenderAlgorithm::Perturbation {
+                    ui.separator();
+                    if self.fractal_type != FractalType::Mandelbrot {
+                        ui.monospace("Perturbation: Mandelbrot only");
+                    } else if let Some(buffer) = &self.iteration_buffer {
+                        ui.monospace(format!("Series skipped {} iterations", buffer.skipped_iterations));
+                    }
+                }
+            });
+        });
+    }
+
+    /// Adds the overlay toggles to a menu.
+    pub fn overlay_menu(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(&mut self.overlays.axes, "Axes");
+        ui.checkbox(&mut self.overlays.grid, "Grid");
+        ui.checkbox(&mut self.overlays.scale_bar, "Scale Bar");
+        ui.checkbox(&mut self.overlays.crosshair, "Crosshair");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nice_steps() {
+        assert_eq!(nice_step(0.8), 1.0);
+        assert_eq!(nice_step(1.0), 1.0);
+        assert_eq!(nice_step(1.3), 2.0);
+        assert_eq!(nice_step(3.0), 5.0);
+        assert_eq!(nice_step(7.0), 10.0);
+        assert!((nice_step(3.2e-9) - 5e-9).abs() < 1e-20);
+        assert_eq!(step_decimals(0.05), 2);
+        assert_eq!(step_decimals(5e-9), 9);
+        assert_eq!(step_decimals(20.0), 0);
+    }
+
+    #[test]
+    fn test_scale_labels() {
+        assert_eq!(format_length(0.5), "0.5");
+        assert_eq!(format_length(20.0), "20");
+        assert_eq!(format_length(2e-7), "2e-7");
+    }
+}
@@ -0,0 +1,140 @@
+use crate::ui::fractal_app::FractalApp;
+use crate::utils::scene::Scene;
+use std::path::Path;
+
+/// Whether the scene window reads or writes a file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SceneAction {
+    Open,
+    Save,
+}
+
+/// State of the "Open Scene" / "Save Scene" window.
+pub struct SceneDialog {
+    pub action: Option<SceneAction>, // `None` while the window is closed
+    pub path: String,
+    pub status: Option<String>,
+}
+
+impl Default for SceneDialog {
+    /// Creates a closed dialog pointing at `scene.json`.
+    #[inline]
+    fn default() -> Self {
+        Self {
+            action: None,
+            path: String::from("scene.json"),
+            status: None,
+        }
+    }
+}
+
+impl FractalApp {
+    /// Returns the current view and colors as a scene, at the size of the view.
+    #[must_use]
+    pub fn scene(&self) -> Scene {
+        let mut render = self.render_params();
+        render.anti_aliasing = self.anti_aliasing;
+        Scene::new(render, self.color_settings())
+    }
+
+    /// Restores a scene. The view keeps the window's size; the scene's size only applies to exports.
+    pub fn apply_scene(&mut self, scene: &Scene) {
+        let render = &scene.render;
+        self.fractal_type = render.fractal_type;
+        self.center = render.center;
+        self.zoom = render.zoom;
+        self.julia_c = render.julia_c;
+        self.lyapunov_sequence = render.lyapunov_sequence;
+        self.lyapunov_input = render.lyapunov_sequence.to_string();
+        self.max_iterations = render.max_iterations;
+        self.precision_mode = render.precision_mode;
+        self.anti_aliasing = render.anti_aliasing;
+        self.algorithm = render.algorithm;
+
+        let colors = &scene.colors;
+        self.color_scheme = colors.scheme;
+        self.color_mapping = colors.mapping;
+        self.palette_offset = colors.offset.rem_euclid(1.0);
+        self.use_custom_gradient = colors.gradient.is_some();
+        if let Some(gradient) = &colors.gradient {
+            self.custom_gradient = gradient.clone();
+        }
+        self.needs_update = true;
+    }
+
+    /// Restores the scene of a file dropped onto the window: a scene file or an exported PNG.
+    pub fn handle_dropped_files(&mut self, ctx: &egui::Context) {
+        let dropped = ctx.input(|i| i.raw.dropped_files.clone());
+        let Some(path) = dropped.into_iter().find_map(|file| file.path) else {
+            return;
+        };
+        match Scene::load(&path) {
+            Ok(scene) => self.apply_scene(&scene),
+            Err(error) => {
+                // Reuse the open window to show why the file was not accepted
+                self.scene_dialog.action = Some(SceneAction::Open);
+                self.scene_dialog.path = path.display().to_string();
+                self.scene_dialog.status = Some(format!("❌ {error}"));
+            }
+        }
+    }
+
+    /// Shows the open/save scene window.
+    pub fn scene_window(&mut self, ctx: &egui::Context) {
+        let Some(action) = self.scene_dialog.action else {
+            return;
+        };
+        let mut open = true;
+        let mut confirm = false;
+
+        let title = match action {
+            SceneAction::Open => "📂 Open Scene",
+            SceneAction::Save => "💾 Save Scene",
+        };
+        egui::Window::new(title)
+            .open(&mut open)
+            .resizable(false)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("File:");
+                    let response = ui.text_edit_singleline(&mut self.scene_dialog.path);
+                    if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        confirm = true;
+                    }
+                });
+                let label = match action {
+                    SceneAction::Open => "Open",
+                    SceneAction::Save => "Save",
+                };
+                if ui
+                    .add_enabled(!self.scene_dialog.path.trim().is_empty(), egui::Button::new(label))
+                    .clicked()
+                {
+                    confirm = true;
+                }
+                if let Some(status) = &self.scene_dialog.status {
+                    ui.label(status);
+                }
+            });
+
+        if confirm {
+            let path = self.scene_dialog.path.trim().to_owned();
+            let result = match action {
+                SceneAction::Open => Scene::load(Path::new(&path)).map(|scene| self.apply_scene(&scene)),
+                SceneAction::Save => self.scene().save(Path::new(&path)),
+            };
+            match result {
+                Ok(()) => {
+                    self.scene_dialog.status = None;
+                    open = false;
+                }
+                Err(error) => self.scene_dialog.status = Some(format!("❌ {error}")),
+            }
+        }
+        if !open {
+            self.scene_dialog.action = None;
+        }
+    }
+}
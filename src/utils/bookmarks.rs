@@ -0,0 +1,240 @@
+use crate::fractals::fractal_type::FractalType;
+use crate::fractals::lyapunov::LyapunovSequence;
+use crate::utils::color_scheme::ColorScheme;
+use crate::utils::point::Point;
+use crate::utils::precision_mode::PrecisionMode;
+use image::{ImageFormat, RgbImage};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Size of the thumbnail rendered for each bookmark.
+pub const THUMBNAIL_SIZE: (u32, u32) = (160, 100);
+
+/// Name of the index file inside the bookmarks directory.
+const INDEX_FILE: &str = "bookmarks.json";
+
+/// Version written to the index file, bumped on incompatible changes.
+const FORMAT_VERSION: u32 = 1;
+
+/// A named location, with everything needed to render it again.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub name: String,
+    #[serde(deserialize_with = "fractal_type_or_default")]
+    pub fractal_type: FractalType,
+    pub center: Point,
+    pub zoom: f64,
+    pub max_iterations: u16,
+    pub precision_mode: PrecisionMode,
+    #[serde(deserialize_with = "color_scheme_or_default")]
+    pub color_scheme: ColorScheme,
+    pub julia_c: Point,
+    #[serde(default)]
+    pub lyapunov_sequence: LyapunovSequence, // Absent from bookmarks saved before Lyapunov fractals
+    #[serde(default)]
+    pub thumbnail: Option<String>, // PNG file name inside the bookmarks directory
+}
+
+/// Reads a fractal type, falling back to the default on unknown names (fractals from a newer
+/// version), so one such bookmark does not make the whole store unreadable.
+fn fractal_type_or_default<'de, D: Deserializer<'de>>(deserializer: D) -> Result<FractalType, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    Ok(FractalType::from_name(&name).unwrap_or_default())
+}
+
+/// Reads a color scheme with the same fallback as [`fractal_type_or_default`].
+fn color_scheme_or_default<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ColorScheme, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    Ok(ColorScheme::from_name(&name).unwrap_or_default())
+}
+
+/// On-disk layout of the index file.
+#[derive(Serialize, Deserialize)]
+struct BookmarkFile {
+    version: u32,
+    bookmarks: Vec<Bookmark>,
+}
+
+/// Bookmarks persisted as a JSON index plus one PNG thumbnail per bookmark in a directory.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BookmarkStore {
+    pub directory: PathBuf,
+    pub bookmarks: Vec<Bookmark>,
+}
+
+impl BookmarkStore {
+    /// Loads the bookmarks saved in `directory`; a directory without bookmarks gives an empty store.
+    pub fn open(directory: PathBuf) -> io::Result<Self> {
+        let bookmarks = match fs::read_to_string(directory.join(INDEX_FILE)) {
+            Ok(text) => {
+                let file: BookmarkFile = serde_json::from_str(&text).map_err(io::Error::other)?;
+                file.bookmarks
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(error) => return Err(error),
+        };
+        Ok(Self { directory, bookmarks })
+    }
+
+    /// Writes the index file; the previous one is replaced only once the new one is complete.
+    pub fn save(&self) -> io::Result<()> {
+        fs::create_dir_all(&self.directory)?;
+        let file = BookmarkFile {
+            version: FORMAT_VERSION,
+            bookmarks: self.bookmarks.clone(),
+        };
+        let json = serde_json::to_string_pretty(&file).map_err(io::Error::other)?;
+
+        let path = self.directory.join(INDEX_FILE);
+        let partial = path.with_extension("json.part");
+        fs::write(&partial, json)?;
+        fs::rename(&partial, &path)
+    }
+
+    /// Appends a bookmark with its thumbnail and saves the store.
+    pub fn add(&mut self, mut bookmark: Bookmark, thumbnail: Option<&RgbImage>) -> io::Result<()> {
+        fs::create_dir_all(&self.directory)?;
+        bookmark.thumbnail = match thumbnail {
+            Some(image) => {
+                let name = self.unused_thumbnail_name();
+                image
+                    .save_with_format(self.directory.join(&name), ImageFormat::Png)
+                    .map_err(io::Error::other)?;
+                Some(name)
+            }
+            None => None,
+        };
+        self.bookmarks.push(bookmark);
+        self.save()
+    }
+
+    /// Deletes a bookmark and its thumbnail and saves the store.
+    pub fn remove(&mut self, index: usize) -> io::Result<()> {
+        let bookmark = self.bookmarks.remove(index);
+        if let Some(path) = self.thumbnail_path(&bookmark) {
+            let _ = fs::remove_file(path);
+        }
+        self.save()
+    }
+
+    /// Returns the path of a bookmark's thumbnail, if it has one.
+    #[must_use]
+    pub fn thumbnail_path(&self, bookmark: &Bookmark) -> Option<PathBuf> {
+        // Only plain file names are accepted: an edited index cannot point outside the directory
+        let name = bookmark.thumbnail.as_deref()?;
+        (Path::new(name).file_name()? == name).then(|| self.directory.join(name))
+    }
+
+    /// Returns a thumbnail file name that no file in the directory uses yet.
+    fn unused_thumbnail_name(&self) -> String {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis());
+        (0u32..)
+            .map(|n| format!("thumb_{stamp}_{n}.png"))
+            .find(|name| !self.directory.join(name).exists())
+            .expect("an unbounded range always yields a free name")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bookmark(name: &str) -> Bookmark {
+        Bookmark {
+            name: String::from(name),
+            fractal_type: FractalType::Julia,
+            center: Point::new(-0.743_643_887_037_151, 0.131_825_904_205_33),
+            zoom: 1.234_567_890_123e9,
+            max_iterations: 1500,
+            precision_mode: PrecisionMode::High,
+            color_scheme: ColorScheme::Viridis,
+            julia_c: Point::new(-0.8, 0.156),
+            lyapunov_sequence: "AABAB".parse().unwrap(),
+            thumbnail: None,
+        }
+    }
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("fractals_rs_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_missing_directory_is_empty() {
+        let store = BookmarkStore::open(scratch_dir("no_bookmarks")).unwrap();
+        assert!(store.bookmarks.is_empty());
+    }
+
+    #[test]
+    fn test_bookmarks_round_trip_with_thumbnails() {
+        let dir = scratch_dir("bookmarks");
+        let mut store = BookmarkStore::open(dir.clone()).unwrap();
+        let thumbnail = RgbImage::from_pixel(THUMBNAIL_SIZE.0, THUMBNAIL_SIZE.1, image::Rgb([10, 20, 30]));
+        store.add(bookmark("Seahorse"), Some(&thumbnail)).unwrap();
+        store.add(bookmark("Spiral"), Some(&thumbnail)).unwrap();
+
+        let reloaded = BookmarkStore::open(dir.clone()).unwrap();
+        assert_eq!(reloaded, store);
+        // Coordinates survive the trip to text bit for bit
+        assert_eq!(reloaded.bookmarks[0].center, bookmark("").center);
+
+        let first = reloaded.thumbnail_path(&reloaded.bookmarks[0]).unwrap();
+        assert_eq!(image::open(&first).unwrap().into_rgb8(), thumbnail);
+
+        let mut reloaded = reloaded;
+        reloaded.remove(0).unwrap();
+        assert!(!first.exists());
+        assert_eq!(BookmarkStore::open(dir.clone()).unwrap().bookmarks[0].name, "Spiral");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_thumbnail_path_stays_inside_directory() {
+        let store = BookmarkStore {
+            directory: PathBuf::from("bookmarks"),
+            bookmarks: Vec::new(),
+        };
+        let mut escaping = bookmark("Escaping");
+        escaping.thumbnail = Some(String::from("../secret.png"));
+        assert_eq!(store.thumbnail_path(&escaping), None);
+    }
+
+    #[test]
+    fn test_unknown_names_fall_back() {
+        let dir = scratch_dir("future_bookmarks");
+        let mut store = BookmarkStore::open(dir.clone()).unwrap();
+        store.add(bookmark("Known"), None).unwrap();
+        let index = dir.join(INDEX_FILE);
+        let text = fs::read_to_string(&index).unwrap().replace("Julia Set", "Some Future Fractal").replace("Viridis", "Plaid");
+        fs::write(&index, text).unwrap();
+
+        let reloaded = BookmarkStore::open(dir.clone()).unwrap();
+        assert_eq!(reloaded.bookmarks[0].fractal_type, FractalType::default());
+        assert_eq!(reloaded.bookmarks[0].color_scheme, ColorScheme::default());
+        assert_eq!(reloaded.bookmarks[0].zoom, bookmark("").zoom);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_bookmarks_without_lyapunov_sequence_load() {
+        let dir = scratch_dir("old_bookmarks");
+        let mut store = BookmarkStore::open(dir.clone()).unwrap();
+        store.add(bookmark("Old"), None).unwrap();
+        let index = dir.join(INDEX_FILE);
+        let text = fs::read_to_string(&index).unwrap().replace("\"lyapunov_sequence\": \"AABAB\",", "");
+        fs::write(&index, text).unwrap();
+
+        let reloaded = BookmarkStore::open(dir.clone()).unwrap();
+        assert_eq!(reloaded.bookmarks[0].lyapunov_sequence, LyapunovSequence::default());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
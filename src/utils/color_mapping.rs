@@ -0,0 +1,196 @@
+use crate::fractals::renderer::IterationBuffer;
+use crate::utils::color_scheme::ColorScheme;
+use crate::utils::gradient::Gradient;
+use eframe::epaint::Color32;
+use serde::{Deserialize, Serialize};
+
+/// How escape iteration counts are spread over the palette.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum ColorMapping {
+    #[default]
+    Linear,    // iterations / max_iterations
+    Histogram, // Cumulative distribution of the current frame's iterations
+}
+
+impl ColorMapping {
+    /// Returns the name of the color mapping.
+    #[inline]
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Linear => "Linear",
+            Self::Histogram => "Histogram Equalized",
+        }
+    }
+
+    /// Returns all available color mappings.
+    #[inline]
+    #[must_use]
+    pub const fn all() -> [Self; 2] {
+        [Self::Linear, Self::Histogram]
+    }
+
+    /// Returns the palette position in `[0, 1]` of every escaped iteration count `0..max_iterations`.
+    #[must_use]
+    pub fn positions(&self, buffer: &IterationBuffer) -> Vec<f32> {
+        let max_iterations = buffer.max_iterations;
+        match self {
+            Self::Linear => (0..max_iterations)
+                .map(|i| f32::from(i) / f32::from(max_iterations))
+                .collect(),
+            Self::Histogram => {
+                let mut histogram = vec![0u64; max_iterations as usize];
+                for &iterations in &buffer.iterations {
+                    if iterations < max_iterations {
+                        histogram[iterations as usize] += 1;
+                    }
+                }
+
+                let escaped: u64 = histogram.iter().sum();
+                if escaped == 0 {
+                    return Self::Linear.positions(buffer);
+                }
+
+                // Each count maps to the share of escaped pixels at or below it
+                let mut cumulative = 0u64;
+                histogram
+                    .iter()
+                    .map(|&count| {
+                        cumulative += count;
+                        (cumulative as f64 / escaped as f64) as f32
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Everything that decides how an iteration buffer is turned into colors.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColorSettings {
+    pub scheme: ColorScheme,
+    pub gradient: Option<Gradient>, // Replaces the built-in scheme when set
+    pub mapping: ColorMapping,
+    pub offset: f32, // Palette rotation in [0, 1), used to cycle colors
+}
+
+impl ColorSettings {
+    /// Builds the `max_iterations + 1` entry palette used to color `buffer`; in-set points stay black.
+    /// With histogram mapping the palette depends on `buffer`'s own distribution.
+    #[must_use]
+    pub fn build_palette(&self, buffer: &IterationBuffer) -> Vec<Color32> {
+        if self.mapping == ColorMapping::Linear && self.gradient.is_none() && self.offset == 0.0 {
+            return self.scheme.build_palette(buffer.max_iterations);
+        }
+
+        let mut palette: Vec<Color32> = self
+            .mapping
+            .positions(buffer)
+            .into_iter()
+            .map(|t| if self.offset == 0.0 { t } else { (t + self.offset).fract() })
+            .map(|t| match &self.gradient {
+                Some(gradient) => gradient.sample(ColorScheme::ultra_smooth(t)),
+                None => self.scheme.color_at(t),
+            })
+            .collect();
+        palette.push(Color32::BLACK);
+        palette
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer(iterations: Vec<u16>) -> IterationBuffer {
+        IterationBuffer {
+            width: iterations.len(),
+            height: 1,
+            max_iterations: 10,
+            iterations,
+            subsamples: None,
+            skipped_iterations: 0,
+        }
+    }
+
+    #[test]
+    fn test_linear_positions() {
+        let positions = ColorMapping::Linear.positions(&buffer(vec![0]));
+        assert_eq!(positions.len(), 10);
+        assert_eq!(positions[0], 0.0);
+        assert!((positions[5] - 0.5).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_histogram_positions_follow_distribution() {
+        // Half the escaped pixels at 2, half at 8; in-set pixels (10) are ignored
+        let positions = ColorMapping::Histogram.positions(&buffer(vec![2, 2, 8, 8, 10, 10]));
+        assert_eq!(positions[1], 0.0);
+        assert_eq!(positions[2], 0.5);
+        assert_eq!(positions[7], 0.5);
+        assert_eq!(positions[8], 1.0);
+    }
+
+    #[test]
+    fn test_histogram_without_escapes_falls_back_to_linear() {
+        let in_set = buffer(vec![10, 10]);
+        assert_eq!(
+            ColorMapping::Histogram.positions(&in_set),
+            ColorMapping::Linear.positions(&in_set)
+        );
+    }
+
+    #[test]
+    fn test_build_palette_linear_matches_scheme() {
+        let buffer = buffer(vec![0, 3]);
+        let settings = ColorSettings {
+            scheme: ColorScheme::Hot,
+            ..ColorSettings::default()
+        };
+        assert_eq!(settings.build_palette(&buffer), ColorScheme::Hot.build_palette(10));
+    }
+
+    #[test]
+    fn test_histogram_palette_spreads_colors() {
+        // Iterations bunched at the low end: linear mapping squeezes them, histogram spreads them
+        let buffer = buffer(vec![1, 1, 2, 2, 3, 3]);
+        let linear = ColorSettings::default().build_palette(&buffer);
+        let histogram = ColorSettings {
+            mapping: ColorMapping::Histogram,
+            ..ColorSettings::default()
+        }
+        .build_palette(&buffer);
+
+        assert_eq!(histogram[3], ColorScheme::Classic.color_at(1.0));
+        assert_ne!(histogram[3], linear[3]);
+        assert_eq!(histogram[10], Color32::BLACK);
+    }
+
+    #[test]
+    fn test_palette_offset_rotates_colors() {
+        let buffer = buffer(vec![0]);
+        let rotated = ColorSettings {
+            offset: 0.5,
+            ..ColorSettings::default()
+        }
+        .build_palette(&buffer);
+        assert_eq!(rotated[0], ColorScheme::Classic.color_at(0.5));
+        assert_eq!(rotated[5], ColorScheme::Classic.color_at(0.0));
+    }
+
+    #[test]
+    fn test_build_palette_with_gradient() {
+        let buffer = buffer(vec![0, 3]);
+        let gradient = Gradient::default();
+        let settings = ColorSettings {
+            gradient: Some(gradient.clone()),
+            ..ColorSettings::default()
+        };
+        let palette = settings.build_palette(&buffer);
+        assert_eq!(palette.len(), 11);
+        assert_eq!(palette[0], gradient.sample(0.0));
+        assert_eq!(palette[10], Color32::BLACK);
+    }
+}
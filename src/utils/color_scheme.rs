@@ -1,4 +1,8 @@
+use crate::fractals::fractal_type::normalize_name;
+use crate::utils::color_space::{InterpolationSpace, Oklch};
+use crate::utils::gradient::{self, GradientStop};
 use eframe::epaint::Color32;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
 use std::f32::consts::PI;
 
 #[derive(Clone, Debug, Copy, PartialEq, Default)]
@@ -36,8 +40,39 @@ pub enum ColorScheme {
     AmethystCavern,
     SakuraRain,
     ElectricStorm,
+    Viridis,
+    Inferno,
+    Twilight,
+    PerceptualRainbow,
 }
 
+/// Viridis key colors, blended in OKLab.
+const VIRIDIS_STOPS: &[GradientStop] = &[
+    GradientStop::new(0.0, Color32::from_rgb(68, 1, 84)),
+    GradientStop::new(0.25, Color32::from_rgb(59, 82, 139)),
+    GradientStop::new(0.5, Color32::from_rgb(33, 145, 140)),
+    GradientStop::new(0.75, Color32::from_rgb(94, 201, 98)),
+    GradientStop::new(1.0, Color32::from_rgb(253, 231, 37)),
+];
+
+/// Inferno key colors, blended in OKLab.
+const INFERNO_STOPS: &[GradientStop] = &[
+    GradientStop::new(0.0, Color32::from_rgb(0, 0, 4)),
+    GradientStop::new(0.25, Color32::from_rgb(87, 16, 110)),
+    GradientStop::new(0.5, Color32::from_rgb(188, 55, 84)),
+    GradientStop::new(0.75, Color32::from_rgb(249, 142, 9)),
+    GradientStop::new(1.0, Color32::from_rgb(252, 255, 164)),
+];
+
+/// Cyclic twilight key colors, blended in OKLCH so hues sweep without graying out.
+const TWILIGHT_STOPS: &[GradientStop] = &[
+    GradientStop::new(0.0, Color32::from_rgb(226, 217, 226)),
+    GradientStop::new(0.25, Color32::from_rgb(94, 128, 185)),
+    GradientStop::new(0.5, Color32::from_rgb(47, 20, 55)),
+    GradientStop::new(0.75, Color32::from_rgb(180, 90, 74)),
+    GradientStop::new(1.0, Color32::from_rgb(226, 217, 226)),
+];
+
 impl ColorScheme {
     /// Returns the name of the color scheme.
     #[inline]
@@ -73,12 +108,16 @@ impl ColorScheme {
             Self::AmethystCavern => "Amethyst Cavern",
             Self::SakuraRain => "Sakura Rain",
             Self::ElectricStorm => "Electric Storm",
+            Self::Viridis => "Viridis",
+            Self::Inferno => "Inferno",
+            Self::Twilight => "Twilight",
+            Self::PerceptualRainbow => "Perceptual Rainbow",
         }
     }
 
     /// Returns all available color schemes.
     #[inline]
-    pub const fn all() -> [Self; 30] {
+    pub const fn all() -> [Self; 34] {
         [
             Self::Classic,
             Self::Hot,
@@ -110,9 +149,20 @@ impl ColorScheme {
             Self::AmethystCavern,
             Self::SakuraRain,
             Self::ElectricStorm,
+            Self::Viridis,
+            Self::Inferno,
+            Self::Twilight,
+            Self::PerceptualRainbow,
         ]
     }
 
+    /// Parses a color scheme from its name, ignoring case and spaces (`"deep-ocean"` matches "Deep Ocean").
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        let wanted = normalize_name(name);
+        Self::all().into_iter().find(|scheme| normalize_name(scheme.name()) == wanted)
+    }
+
     /// Smooth step function for smooth interpolation between two edges.
     #[inline(always)]
     fn smooth_step(edge0: f32, edge1: f32, x: f32) -> f32 {
@@ -126,6 +176,14 @@ impl ColorScheme {
         t.mul_add(b - a, a)
     }
 
+    /// Blends two key colors, given as sRGB channels, in OKLab so a segment keeps its lightness
+    /// instead of dipping through a muddy sRGB midpoint.
+    #[inline(always)]
+    fn blend(from: [f32; 3], to: [f32; 3], t: f32) -> Color32 {
+        let [from, to] = [from, to].map(|[r, g, b]| Color32::from_rgb(r as u8, g as u8, b as u8));
+        InterpolationSpace::Oklab.mix(from, to, t)
+    }
+
     /// Converts HSV color to RGB.
     #[inline(always)]
     fn hsv_to_rgb(h: f32, s: f32, v: f32) -> Color32 {
@@ -154,7 +212,7 @@ impl ColorScheme {
     /// Enhanced smooth coloring function for ultra-high quality rendering
     /// Uses multiple smoothing techniques for elimination of color banding
     #[inline(always)]
-    fn ultra_smooth(t: f32) -> f32 {
+    pub(crate) fn ultra_smooth(t: f32) -> f32 {
         // Triple smoothing for maximum quality
         let smooth1 = t.sqrt();
         let smooth2 = smooth1.sqrt();
@@ -186,8 +244,22 @@ impl ColorScheme {
             return Color32::BLACK;
         }
 
-        let t: f32 = f32::from(iterations) / f32::from(max_iterations);
+        self.color_at(f32::from(iterations) / f32::from(max_iterations))
+    }
+
+    /// Returns the key colors of a scheme defined by gradient stops, with the space they blend in.
+    const fn stops(&self) -> Option<(&'static [GradientStop], InterpolationSpace)> {
+        match self {
+            Self::Viridis => Some((VIRIDIS_STOPS, InterpolationSpace::Oklab)),
+            Self::Inferno => Some((INFERNO_STOPS, InterpolationSpace::Oklab)),
+            Self::Twilight => Some((TWILIGHT_STOPS, InterpolationSpace::Oklch)),
+            _ => None,
+        }
+    }
 
+    /// Returns the color of an escaped point at palette position `t` in `[0, 1)`.
+    #[must_use]
+    pub fn color_at(&self, t: f32) -> Color32 {
         // Use ultra-smooth algorithm for better quality
         let smoothed: f32 = Self::ultra_smooth(t);
 
@@ -211,34 +283,26 @@ impl ColorScheme {
                 if heat < 0.2 {
                     // Deep ember
                     let t = heat * 5.0;
-                    let r = (100.0 + 155.0 * t * flicker) as u8;
-                    let g = (10.0 + 25.0 * t) as u8;
-                    let b = 0;
-                    Color32::from_rgb(r, g, b)
+                    Self::blend([100.0, 10.0, 0.0], [100.0 + 155.0 * flicker, 35.0, 0.0], t)
                 } else if heat < 0.45 {
                     // Red hot
                     let t = (heat - 0.2) / 0.25;
                     let smooth_t = Self::smooth_step(0.0, 1.0, t);
-                    let r = 255;
-                    let g = (35.0 + 80.0 * smooth_t) as u8;
-                    let b = 0;
-                    Color32::from_rgb(r, g, b)
+                    Self::blend([255.0, 35.0, 0.0], [255.0, 115.0, 0.0], smooth_t)
                 } else if heat < 0.75 {
                     // Orange to yellow
                     let t = (heat - 0.45) / 0.3;
                     let smooth_t = Self::smooth_step(0.0, 1.0, t);
-                    let r = 255;
-                    let g = (115.0 + 140.0 * smooth_t * flicker) as u8;
-                    let b = (50.0 * smooth_t) as u8;
-                    Color32::from_rgb(r, g, b)
+                    Self::blend(
+                        [255.0, 115.0, 0.0],
+                        [255.0, 115.0 + 140.0 * flicker, 50.0],
+                        smooth_t,
+                    )
                 } else {
                     // White hot
                     let t = (heat - 0.75) / 0.25;
                     let smooth_t = Self::smooth_step(0.0, 1.0, t);
-                    let r = 255;
-                    let g = 255;
-                    let b = (50.0 + 205.0 * smooth_t) as u8;
-                    Color32::from_rgb(r, g, b)
+                    Self::blend([255.0, 255.0, 50.0], [255.0, 255.0, 255.0], smooth_t)
                 }
             }
             Self::Cool => {
@@ -276,34 +340,34 @@ impl ColorScheme {
                     // Deep twilight blue
                     let t = sun / 0.25;
                     let smooth_t = Self::smooth_step(0.0, 1.0, t);
-                    let r = (40.0 + 80.0 * smooth_t) as u8;
-                    let g = (20.0 + 60.0 * smooth_t) as u8;
-                    let b = (100.0 + 55.0 * smooth_t * atmosphere) as u8;
-                    Color32::from_rgb(r, g, b)
+                    Self::blend(
+                        [40.0, 20.0, 100.0],
+                        [120.0, 80.0, 100.0 + 55.0 * atmosphere],
+                        smooth_t,
+                    )
                 } else if sun < 0.5 {
                     // Purple to magenta transition
                     let t = (sun - 0.25) / 0.25;
                     let smooth_t = Self::smooth_step(0.0, 1.0, t);
-                    let r = (120.0 + 135.0 * smooth_t) as u8;
-                    let g = (80.0 + 70.0 * smooth_t * atmosphere) as u8;
-                    let b = (155.0 - 50.0 * smooth_t) as u8;
-                    Color32::from_rgb(r, g, b)
+                    Self::blend(
+                        [120.0, 80.0, 155.0],
+                        [255.0, 80.0 + 70.0 * atmosphere, 105.0],
+                        smooth_t,
+                    )
                 } else if sun < 0.75 {
                     // Orange to golden
                     let t = (sun - 0.5) / 0.25;
                     let smooth_t = Self::smooth_step(0.0, 1.0, t);
-                    let r = 255;
-                    let g = (150.0 + 80.0 * smooth_t * glow) as u8;
-                    let b = (105.0 - 50.0 * smooth_t) as u8;
-                    Color32::from_rgb(r, g, b)
+                    Self::blend([255.0, 150.0, 105.0], [255.0, 150.0 + 80.0 * glow, 55.0], smooth_t)
                 } else {
                     // Bright sky to white
                     let t = (sun - 0.75) / 0.25;
                     let smooth_t = Self::smooth_step(0.0, 1.0, t);
-                    let r = 255;
-                    let g = (230.0 + 25.0 * smooth_t) as u8;
-                    let b = (55.0 + 180.0 * smooth_t * atmosphere) as u8;
-                    Color32::from_rgb(r, g, b)
+                    Self::blend(
+                        [255.0, 230.0, 55.0],
+                        [255.0, 255.0, 55.0 + 180.0 * atmosphere],
+                        smooth_t,
+                    )
                 }
             }
             Self::Electric => {
@@ -325,25 +389,22 @@ impl ColorScheme {
                 if smoothed < 0.3 {
                     // Forest depths
                     let t = smoothed / 0.3;
-                    let r = (10.0 + 35.0 * t * hue_wave) as u8;
-                    let g = (20.0 + 60.0 * t) as u8;
-                    let b = (8.0 + 25.0 * t * depth_wave) as u8;
-                    Color32::from_rgb(r, g, b)
+                    Self::blend(
+                        [10.0, 20.0, 8.0],
+                        [10.0 + 35.0 * hue_wave, 80.0, 8.0 + 25.0 * depth_wave],
+                        t,
+                    )
                 } else if smoothed < 0.7 {
                     // Mid forest
                     let t = (smoothed - 0.3) / 0.4;
-                    let r = (45.0 + 80.0 * t * hue_wave) as u8;
-                    let g = (80.0 + 100.0 * t) as u8;
-                    let b = (25.0 + 35.0 * t * (1.0 - hue_wave * 0.6)) as u8;
-                    Color32::from_rgb(r, g, b)
+                    let b = 25.0 + 35.0 * (1.0 - hue_wave * 0.6);
+                    Self::blend([45.0, 80.0, 25.0], [45.0 + 80.0 * hue_wave, 180.0, b], t)
                 } else {
                     // Sunlit canopy
                     let t = (smoothed - 0.7) / 0.3;
                     let golden_hour = (hue_wave * depth_wave).powf(0.5);
-                    let r = (125.0 + 130.0 * t * golden_hour) as u8;
-                    let g = (180.0 + 75.0 * t) as u8;
-                    let b = (60.0 + 40.0 * t * (1.0 - golden_hour * 0.8)) as u8;
-                    Color32::from_rgb(r, g, b)
+                    let b = 60.0 + 40.0 * (1.0 - golden_hour * 0.8);
+                    Self::blend([125.0, 180.0, 60.0], [125.0 + 130.0 * golden_hour, 255.0, b], t)
                 }
             }
             Self::Galaxy => {
@@ -388,6 +449,7 @@ impl ColorScheme {
                 let flicker = (smoothed * 12.0).sin() * 0.05 + 1.0;
 
                 if heat < 0.2 {
+                    // Each channel ignites at its own rate, so this ramp is not a two-color blend
                     let t: f32 = heat * 5.0;
                     let r: u8 =
                         (175.0 * Self::smooth_step(0.0, 1.0, t)).mul_add(flicker, 80.0) as u8;
@@ -397,24 +459,24 @@ impl ColorScheme {
                 } else if heat < 0.5 {
                     let t: f32 = (heat - 0.2) / 0.3;
                     let smooth_t: f32 = Self::smooth_step(0.0, 1.0, t);
-                    let r: u8 = (255.0 * flicker) as u8;
-                    let g: u8 = (195.0 * smooth_t).mul_add(flicker, 30.0) as u8;
-                    let b: u8 = 35.0f32.mul_add(smooth_t, 15.0) as u8;
-                    Color32::from_rgb(r, g, b)
+                    let r: f32 = 255.0 * flicker;
+                    Self::blend(
+                        [r, 30.0, 15.0],
+                        [r, 195.0f32.mul_add(flicker, 30.0), 50.0],
+                        smooth_t,
+                    )
                 } else if heat < 0.8 {
                     let t: f32 = (heat - 0.5) / 0.3;
                     let smooth_t: f32 = Self::smooth_step(0.0, 1.0, t);
-                    let r: u8 = 255;
-                    let g: u8 = 30.0f32.mul_add(smooth_t, 225.0) as u8;
-                    let b: u8 = (150.0 * smooth_t).mul_add(flicker, 50.0) as u8;
-                    Color32::from_rgb(r, g, b)
+                    Self::blend(
+                        [255.0, 225.0, 50.0],
+                        [255.0, 255.0, 150.0f32.mul_add(flicker, 50.0)],
+                        smooth_t,
+                    )
                 } else {
                     let t: f32 = (heat - 0.8) / 0.2;
                     let smooth_t: f32 = Self::smooth_step(0.0, 1.0, t);
-                    let r: u8 = 255;
-                    let g: u8 = 255;
-                    let b: u8 = 55.0f32.mul_add(smooth_t, 200.0) as u8;
-                    Color32::from_rgb(r, g, b)
+                    Self::blend([255.0, 255.0, 200.0], [255.0, 255.0, 255.0], smooth_t)
                 }
             }
 
@@ -472,24 +534,29 @@ impl ColorScheme {
                 if warmth < 0.4 {
                     let t: f32 = warmth / 0.4;
                     let smooth_t: f32 = Self::smooth_step(0.0, 1.0, t);
-                    let r: u8 = (180.0 * smooth_t).mul_add(glow, 40.0) as u8;
-                    let g: u8 = (100.0 * smooth_t).mul_add(glow, 20.0) as u8;
-                    let b: u8 = (80.0 * (1.0 - smooth_t)) as u8;
-                    Color32::from_rgb(r, g, b)
+                    let to: [f32; 3] = [
+                        180.0f32.mul_add(glow, 40.0),
+                        100.0f32.mul_add(glow, 20.0),
+                        0.0,
+                    ];
+                    Self::blend([40.0, 20.0, 80.0], to, smooth_t)
                 } else if warmth < 0.8 {
                     let t: f32 = (warmth - 0.4) / 0.4;
                     let smooth_t: f32 = Self::smooth_step(0.0, 1.0, t);
-                    let r: u8 = (35.0 * smooth_t).mul_add(glow, 220.0) as u8;
-                    let g: u8 = (100.0 * smooth_t).mul_add(glow, 120.0) as u8;
-                    let b: u8 = 70.0f32.mul_add(smooth_t, 30.0) as u8;
-                    Color32::from_rgb(r, g, b)
+                    let to: [f32; 3] = [
+                        35.0f32.mul_add(glow, 220.0),
+                        100.0f32.mul_add(glow, 120.0),
+                        100.0,
+                    ];
+                    Self::blend([220.0, 120.0, 30.0], to, smooth_t)
                 } else {
                     let t: f32 = (warmth - 0.8) / 0.2;
                     let smooth_t: f32 = Self::smooth_step(0.0, 1.0, t);
-                    let r: u8 = 255;
-                    let g: u8 = 35.0f32.mul_add(smooth_t, 220.0) as u8;
-                    let b: u8 = (100.0 * smooth_t).mul_add(glow, 100.0) as u8;
-                    Color32::from_rgb(r, g, b)
+                    Self::blend(
+                        [255.0, 220.0, 100.0],
+                        [255.0, 255.0, 100.0f32.mul_add(glow, 100.0)],
+                        smooth_t,
+                    )
                 }
             }
 
@@ -502,34 +569,31 @@ impl ColorScheme {
                 if heat < 0.15 {
                     // Deep volcanic rock - almost black with hint of red
                     let t = heat / 0.15;
-                    let r = (90.0 * t * core_temp) as u8;
-                    let g = (15.0 * t) as u8;
-                    let b = (5.0 * t) as u8;
-                    Color32::from_rgb(r, g, b)
+                    Self::blend([0.0, 0.0, 0.0], [90.0 * core_temp, 15.0, 5.0], t)
                 } else if heat < 0.4 {
                     // Heating rock - dark red to orange
                     let t = (heat - 0.15) / 0.25;
                     let smooth_t = Self::smooth_step(0.0, 1.0, t);
-                    let r = (165.0 * smooth_t * turbulence + 90.0) as u8;
-                    let g = (50.0 * smooth_t + 15.0) as u8;
-                    let b = (10.0 * smooth_t + 5.0) as u8;
-                    Color32::from_rgb(r, g, b)
+                    Self::blend(
+                        [90.0, 15.0, 5.0],
+                        [165.0 * turbulence + 90.0, 65.0, 15.0],
+                        smooth_t,
+                    )
                 } else if heat < 0.7 {
                     // Molten lava - bright orange to yellow
                     let t = (heat - 0.4) / 0.3;
                     let smooth_t = Self::smooth_step(0.0, 1.0, t);
-                    let r = (255.0 * turbulence).min(255.0) as u8;
-                    let g = (140.0 * smooth_t * turbulence + 65.0) as u8;
-                    let b = (25.0 * smooth_t + 15.0) as u8;
-                    Color32::from_rgb(r, g, b)
+                    let r = (255.0 * turbulence).min(255.0);
+                    Self::blend([r, 65.0, 15.0], [r, 140.0 * turbulence + 65.0, 40.0], smooth_t)
                 } else {
                     // White-hot core - extreme heat
                     let t = (heat - 0.7) / 0.3;
                     let smooth_t = Self::smooth_step(0.0, 1.0, t);
-                    let r = 255;
-                    let g = (50.0 * smooth_t + 205.0) as u8;
-                    let b = (180.0 * smooth_t * core_temp + 40.0) as u8;
-                    Color32::from_rgb(r, g, b)
+                    Self::blend(
+                        [255.0, 205.0, 40.0],
+                        [255.0, 255.0, 180.0 * core_temp + 40.0],
+                        smooth_t,
+                    )
                 }
             }
 
@@ -578,24 +642,27 @@ impl ColorScheme {
                 if paradise < 0.3 {
                     // Deep ocean turquoise
                     let t = paradise / 0.3;
-                    let r = (30.0 + 50.0 * t * wave) as u8;
-                    let g = (120.0 + 80.0 * t) as u8;
-                    let b = (150.0 + 70.0 * t * bloom) as u8;
-                    Color32::from_rgb(r, g, b)
+                    Self::blend(
+                        [30.0, 120.0, 150.0],
+                        [30.0 + 50.0 * wave, 200.0, 150.0 + 70.0 * bloom],
+                        t,
+                    )
                 } else if paradise < 0.6 {
                     // Tropical cyan to mint
                     let t = (paradise - 0.3) / 0.3;
-                    let r = (80.0 + 100.0 * t * bloom) as u8;
-                    let g = (200.0 + 35.0 * t) as u8;
-                    let b = (220.0 - 40.0 * t * wave) as u8;
-                    Color32::from_rgb(r, g, b)
+                    Self::blend(
+                        [80.0, 200.0, 220.0],
+                        [80.0 + 100.0 * bloom, 235.0, 220.0 - 40.0 * wave],
+                        t,
+                    )
                 } else {
                     // Sunset coral and pink
                     let t = (paradise - 0.6) / 0.4;
-                    let r = (180.0 + 75.0 * t) as u8;
-                    let g = (235.0 - 50.0 * t * wave) as u8;
-                    let b = (180.0 + 50.0 * t * bloom) as u8;
-                    Color32::from_rgb(r, g, b)
+                    Self::blend(
+                        [180.0, 235.0, 180.0],
+                        [255.0, 235.0 - 50.0 * wave, 180.0 + 50.0 * bloom],
+                        t,
+                    )
                 }
             }
 
@@ -701,34 +768,37 @@ impl ColorScheme {
                 if flame < 0.2 {
                     // Deep ember with dragon magic
                     let t = flame / 0.2;
-                    let r = (60.0 + 140.0 * t * magic) as u8;
-                    let g = (10.0 + 30.0 * t) as u8;
-                    let b = (80.0 + 60.0 * t * magic) as u8; // Purple hint
-                    Color32::from_rgb(r, g, b)
+                    // Purple hint in the blue channel
+                    Self::blend(
+                        [60.0, 10.0, 80.0],
+                        [60.0 + 140.0 * magic, 40.0, 80.0 + 60.0 * magic],
+                        t,
+                    )
                 } else if flame < 0.5 {
                     // Red-violet dragon fire
                     let t = (flame - 0.2) / 0.3;
                     let smooth_t = Self::smooth_step(0.0, 1.0, t);
-                    let r = (200.0 + 55.0 * smooth_t * dragon_energy) as u8;
-                    let g = (40.0 + 60.0 * smooth_t * magic) as u8;
-                    let b = (140.0 + 50.0 * smooth_t * intensity) as u8;
-                    Color32::from_rgb(r, g, b)
+                    let to = [
+                        200.0 + 55.0 * dragon_energy,
+                        40.0 + 60.0 * magic,
+                        140.0 + 50.0 * intensity,
+                    ];
+                    Self::blend([200.0, 40.0, 140.0], to, smooth_t)
                 } else if flame < 0.75 {
                     // Orange-gold magical flame
                     let t = (flame - 0.5) / 0.25;
                     let smooth_t = Self::smooth_step(0.0, 1.0, t);
-                    let r = 255;
-                    let g = (100.0 + 130.0 * smooth_t * dragon_energy) as u8;
-                    let b = (190.0 - 100.0 * smooth_t + 50.0 * magic) as u8;
-                    Color32::from_rgb(r, g, b)
+                    let to = [255.0, 100.0 + 130.0 * dragon_energy, 90.0 + 50.0 * magic];
+                    Self::blend([255.0, 100.0, 190.0 + 50.0 * magic], to, smooth_t)
                 } else {
                     // White-hot dragon core with spectral edge
                     let t = (flame - 0.75) / 0.25;
                     let smooth_t = Self::smooth_step(0.0, 1.0, t);
-                    let r = 255;
-                    let g = (230.0 + 25.0 * smooth_t) as u8;
-                    let b = (140.0 + 115.0 * smooth_t * core) as u8;
-                    Color32::from_rgb(r, g, b)
+                    Self::blend(
+                        [255.0, 230.0, 140.0],
+                        [255.0, 255.0, 140.0 + 115.0 * core],
+                        smooth_t,
+                    )
                 }
             }
 
@@ -760,26 +830,23 @@ impl ColorScheme {
                 if fall < 0.3 {
                     // Misty dawn - soft blues and pinks
                     let t = fall / 0.3;
-                    let r = (180.0 + 60.0 * t * petals) as u8;
-                    let g = (190.0 + 50.0 * t * mist) as u8;
-                    let b = (220.0 + 25.0 * t * breeze) as u8;
-                    Color32::from_rgb(r, g, b)
+                    let to = [180.0 + 60.0 * petals, 190.0 + 50.0 * mist, 220.0 + 25.0 * breeze];
+                    Self::blend([180.0, 190.0, 220.0], to, t)
                 } else if fall < 0.7 {
                     // Sakura bloom - delicate pink
                     let t = (fall - 0.3) / 0.4;
                     let smooth_t = Self::smooth_step(0.0, 1.0, t);
-                    let r = (240.0 + 15.0 * smooth_t * petals) as u8;
-                    let g = (180.0 + 40.0 * smooth_t * mist) as u8;
-                    let b = (200.0 + 30.0 * smooth_t * soft) as u8;
-                    Color32::from_rgb(r, g, b)
+                    let to = [240.0 + 15.0 * petals, 180.0 + 40.0 * mist, 200.0 + 30.0 * soft];
+                    Self::blend([240.0, 180.0, 200.0], to, smooth_t)
                 } else {
                     // Bright sky through petals
                     let t = (fall - 0.7) / 0.3;
                     let smooth_t = Self::smooth_step(0.0, 1.0, t);
-                    let r = 255;
-                    let g = (220.0 + 35.0 * smooth_t) as u8;
-                    let b = (230.0 + 25.0 * smooth_t * breeze) as u8;
-                    Color32::from_rgb(r, g, b)
+                    Self::blend(
+                        [255.0, 220.0, 230.0],
+                        [255.0, 255.0, 230.0 + 25.0 * breeze],
+                        smooth_t,
+                    )
                 }
             }
 
@@ -800,10 +867,38 @@ impl ColorScheme {
                 let b = (60.0 + 140.0 * electric_blue + 195.0 * voltage) as u8;
                 Color32::from_rgb(r, g, b)
             }
+
+            Self::Viridis | Self::Inferno | Self::Twilight => {
+                let (stops, space) = self.stops().expect("stop-based scheme");
+                gradient::sample_stops(stops, space, smoothed)
+            }
+
+            Self::PerceptualRainbow => {
+                // Same sweep as Rainbow Smooth, but in OKLCH: no hue is brighter than another
+                let hue: f32 = smoothed * 360.0;
+                let chroma: f32 = 0.04f32.mul_add((smoothed * 2.0 * PI).sin().abs(), 0.11);
+                let lightness: f32 = 0.06f32.mul_add((smoothed * 3.0 * PI).cos().abs(), 0.72);
+
+                Oklch::new(lightness, chroma, hue).to_color32()
+            }
         }
     }
 }
 
+/// Color schemes are stored by display name, which stays readable in saved files.
+impl Serialize for ColorScheme {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for ColorScheme {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Self::from_name(&name).ok_or_else(|| de::Error::custom(format!("unknown color scheme `{name}`")))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -819,6 +914,19 @@ mod tests {
         assert_eq!(ColorScheme::Electric.name(), "Electric");
         assert_eq!(ColorScheme::Forest.name(), "Forest");
         assert_eq!(ColorScheme::Galaxy.name(), "Galaxy");
+        assert_eq!(ColorScheme::Viridis.name(), "Viridis");
+        assert_eq!(ColorScheme::Inferno.name(), "Inferno");
+        assert_eq!(ColorScheme::Twilight.name(), "Twilight");
+        assert_eq!(ColorScheme::PerceptualRainbow.name(), "Perceptual Rainbow");
+    }
+
+    #[test]
+    fn test_color_scheme_from_name() {
+        for scheme in ColorScheme::all() {
+            assert_eq!(ColorScheme::from_name(scheme.name()), Some(scheme));
+        }
+        assert_eq!(ColorScheme::from_name("deep-ocean"), Some(ColorScheme::DeepOcean));
+        assert_eq!(ColorScheme::from_name("plaid"), None);
     }
 
     #[test]
@@ -833,6 +941,15 @@ mod tests {
         assert_eq!(color, Color32::from_rgb(0, 0, 255)); // Blue
     }
 
+    #[test]
+    fn test_blend_mixes_key_colors_in_oklab() {
+        let (ember, white) = (Color32::from_rgb(100, 10, 0), Color32::WHITE);
+        for t in [0.0, 0.25, 0.5, 1.0] {
+            let blended = ColorScheme::blend([100.0, 10.0, 0.0], [255.0, 255.0, 255.0], t);
+            assert_eq!(blended, InterpolationSpace::Oklab.mix(ember, white, t));
+        }
+    }
+
     #[test]
     fn test_smooth_step() {
         assert_eq!(ColorScheme::smooth_step(0.0, 1.0, 0.0), 0.0);
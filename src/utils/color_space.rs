@@ -0,0 +1,309 @@
+use eframe::epaint::Color32;
+use serde::{Deserialize, Serialize};
+
+/// Color space in which two palette colors are blended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum InterpolationSpace {
+    Srgb,      // Gamma-encoded channels, the historical behaviour (muddy midpoints)
+    LinearRgb, // Physically linear light, correct for averaging samples
+    #[default]
+    Oklab,     // Perceptually uniform lightness and chroma
+    Oklch,     // Polar OKLab, interpolates hue along the shortest arc
+}
+
+/// A color in the OKLab perceptual color space.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Oklab {
+    pub l: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
+/// A color in the OKLCH space: OKLab lightness, chroma and hue (radians).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Oklch {
+    pub l: f32,
+    pub c: f32,
+    pub h: f32,
+}
+
+/// Converts an 8-bit sRGB channel to linear light in `[0, 1]`.
+#[inline]
+#[must_use]
+pub fn srgb_to_linear(channel: u8) -> f32 {
+    let c = f32::from(channel) / 255.0;
+    if c <= 0.040_45 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts a linear light channel back to 8-bit sRGB, clamping out-of-gamut values.
+#[inline]
+#[must_use]
+pub fn linear_to_srgb(channel: f32) -> u8 {
+    let c = channel.clamp(0.0, 1.0);
+    let encoded = if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055f32.mul_add(c.powf(1.0 / 2.4), -0.055)
+    };
+    (encoded * 255.0).round() as u8
+}
+
+/// Converts a color to linear RGB channels.
+#[inline]
+#[must_use]
+pub fn to_linear_rgb(color: Color32) -> [f32; 3] {
+    [
+        srgb_to_linear(color.r()),
+        srgb_to_linear(color.g()),
+        srgb_to_linear(color.b()),
+    ]
+}
+
+/// Converts linear RGB channels to an opaque color.
+#[inline]
+#[must_use]
+pub fn from_linear_rgb(rgb: [f32; 3]) -> Color32 {
+    Color32::from_rgb(
+        linear_to_srgb(rgb[0]),
+        linear_to_srgb(rgb[1]),
+        linear_to_srgb(rgb[2]),
+    )
+}
+
+impl Oklab {
+    /// Converts linear RGB channels to OKLab.
+    #[must_use]
+    pub fn from_linear_rgb([r, g, b]: [f32; 3]) -> Self {
+        let l = 0.051_445_995f32.mul_add(b, 0.412_221_46f32.mul_add(r, 0.536_332_55 * g));
+        let m = 0.107_396_96f32.mul_add(b, 0.211_903_5f32.mul_add(r, 0.680_699_5 * g));
+        let s = 0.629_978_7f32.mul_add(b, 0.088_302_46f32.mul_add(r, 0.281_718_85 * g));
+
+        let l = l.cbrt();
+        let m = m.cbrt();
+        let s = s.cbrt();
+
+        Self {
+            l: (-0.004_072_047f32).mul_add(s, 0.210_454_26f32.mul_add(l, 0.793_617_8 * m)),
+            a: 0.450_593_7f32.mul_add(s, 1.977_998_5f32.mul_add(l, -2.428_592_2 * m)),
+            b: (-0.808_675_77f32).mul_add(s, 0.025_904_037f32.mul_add(l, 0.782_771_77 * m)),
+        }
+    }
+
+    /// Converts OKLab back to (possibly out-of-gamut) linear RGB channels.
+    #[must_use]
+    pub fn to_linear_rgb(self) -> [f32; 3] {
+        let l = 0.215_803_76f32.mul_add(self.b, 0.396_337_78f32.mul_add(self.a, self.l));
+        let m = (-0.063_854_17f32).mul_add(self.b, (-0.105_561_346f32).mul_add(self.a, self.l));
+        let s = (-1.291_485_5f32).mul_add(self.b, (-0.089_484_18f32).mul_add(self.a, self.l));
+
+        let l = l * l * l;
+        let m = m * m * m;
+        let s = s * s * s;
+
+        [
+            0.230_969_94f32.mul_add(s, 4.076_741_7f32.mul_add(l, -3.307_711_6 * m)),
+            (-0.341_319_4f32).mul_add(s, (-1.268_438f32).mul_add(l, 2.609_757_4 * m)),
+            1.707_614_7f32.mul_add(s, (-0.004_196_086_3f32).mul_add(l, -0.703_418_6 * m)),
+        ]
+    }
+
+    /// Converts an sRGB color to OKLab.
+    #[inline]
+    #[must_use]
+    pub fn from_color32(color: Color32) -> Self {
+        Self::from_linear_rgb(to_linear_rgb(color))
+    }
+
+    /// Converts OKLab to an sRGB color, clamping to the displayable gamut.
+    #[inline]
+    #[must_use]
+    pub fn to_color32(self) -> Color32 {
+        from_linear_rgb(self.to_linear_rgb())
+    }
+}
+
+impl Oklch {
+    /// Creates an OKLCH color from lightness, chroma and hue in degrees.
+    #[inline]
+    #[must_use]
+    pub fn new(l: f32, c: f32, hue_degrees: f32) -> Self {
+        Self {
+            l,
+            c,
+            h: hue_degrees.to_radians(),
+        }
+    }
+
+    /// Converts OKLab to its polar form.
+    #[inline]
+    #[must_use]
+    pub fn from_oklab(lab: Oklab) -> Self {
+        Self {
+            l: lab.l,
+            c: lab.a.hypot(lab.b),
+            h: lab.b.atan2(lab.a),
+        }
+    }
+
+    /// Converts the polar form back to OKLab.
+    #[inline]
+    #[must_use]
+    pub fn to_oklab(self) -> Oklab {
+        let (sin, cos) = self.h.sin_cos();
+        Oklab {
+            l: self.l,
+            a: self.c * cos,
+            b: self.c * sin,
+        }
+    }
+
+    /// Converts OKLCH to an sRGB color, clamping to the displayable gamut.
+    #[inline]
+    #[must_use]
+    pub fn to_color32(self) -> Color32 {
+        self.to_oklab().to_color32()
+    }
+}
+
+impl InterpolationSpace {
+    /// Returns the name of the interpolation space.
+    #[inline]
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Srgb => "sRGB",
+            Self::LinearRgb => "Linear RGB",
+            Self::Oklab => "OKLab",
+            Self::Oklch => "OKLCH",
+        }
+    }
+
+    /// Returns all available interpolation spaces.
+    #[inline]
+    #[must_use]
+    pub const fn all() -> [Self; 4] {
+        [Self::Srgb, Self::LinearRgb, Self::Oklab, Self::Oklch]
+    }
+
+    /// Blends `a` towards `b` by `t` (0 → `a`, 1 → `b`) in this color space.
+    #[must_use]
+    pub fn mix(&self, a: Color32, b: Color32, t: f32) -> Color32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Srgb => {
+                let channel = |x: u8, y: u8| t.mul_add(f32::from(y) - f32::from(x), f32::from(x)).round() as u8;
+                Color32::from_rgb(channel(a.r(), b.r()), channel(a.g(), b.g()), channel(a.b(), b.b()))
+            }
+            Self::LinearRgb => {
+                let (a, b) = (to_linear_rgb(a), to_linear_rgb(b));
+                from_linear_rgb([
+                    t.mul_add(b[0] - a[0], a[0]),
+                    t.mul_add(b[1] - a[1], a[1]),
+                    t.mul_add(b[2] - a[2], a[2]),
+                ])
+            }
+            Self::Oklab => {
+                let (a, b) = (Oklab::from_color32(a), Oklab::from_color32(b));
+                Oklab {
+                    l: t.mul_add(b.l - a.l, a.l),
+                    a: t.mul_add(b.a - a.a, a.a),
+                    b: t.mul_add(b.b - a.b, a.b),
+                }
+                .to_color32()
+            }
+            Self::Oklch => {
+                let a = Oklch::from_oklab(Oklab::from_color32(a));
+                let b = Oklch::from_oklab(Oklab::from_color32(b));
+
+                // Achromatic endpoints have no meaningful hue: borrow the other one's
+                const GRAY_CHROMA: f32 = 1e-4;
+                let (ha, hb) = match (a.c < GRAY_CHROMA, b.c < GRAY_CHROMA) {
+                    (true, false) => (b.h, b.h),
+                    (false, true) => (a.h, a.h),
+                    _ => (a.h, b.h),
+                };
+
+                // Shortest arc around the hue circle
+                let mut dh = hb - ha;
+                if dh > std::f32::consts::PI {
+                    dh -= std::f32::consts::TAU;
+                } else if dh < -std::f32::consts::PI {
+                    dh += std::f32::consts::TAU;
+                }
+
+                Oklch {
+                    l: t.mul_add(b.l - a.l, a.l),
+                    c: t.mul_add(b.c - a.c, a.c),
+                    h: t.mul_add(dh, ha),
+                }
+                .to_color32()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_srgb_linear_round_trip() {
+        for channel in 0..=255u8 {
+            assert_eq!(linear_to_srgb(srgb_to_linear(channel)), channel);
+        }
+    }
+
+    #[test]
+    fn test_oklab_reference_values() {
+        let white = Oklab::from_color32(Color32::WHITE);
+        assert!((white.l - 1.0).abs() < 1e-3);
+        assert!(white.a.abs() < 1e-3 && white.b.abs() < 1e-3);
+
+        let black = Oklab::from_color32(Color32::BLACK);
+        assert!(black.l.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_oklab_round_trip() {
+        for color in [
+            Color32::from_rgb(255, 0, 0),
+            Color32::from_rgb(12, 200, 99),
+            Color32::from_rgb(40, 40, 255),
+            Color32::from_rgb(128, 128, 128),
+        ] {
+            assert_eq!(Oklab::from_color32(color).to_color32(), color);
+            assert_eq!(Oklch::from_oklab(Oklab::from_color32(color)).to_color32(), color);
+        }
+    }
+
+    #[test]
+    fn test_mix_endpoints() {
+        let a = Color32::from_rgb(255, 0, 0);
+        let b = Color32::from_rgb(0, 0, 255);
+        for space in InterpolationSpace::all() {
+            assert_eq!(space.mix(a, b, 0.0), a);
+            assert_eq!(space.mix(a, b, 1.0), b);
+        }
+    }
+
+    #[test]
+    fn test_linear_midpoint_is_brighter_than_srgb() {
+        let a = Color32::from_rgb(255, 0, 0);
+        let b = Color32::from_rgb(0, 255, 0);
+        let srgb = InterpolationSpace::Srgb.mix(a, b, 0.5);
+        let linear = InterpolationSpace::LinearRgb.mix(a, b, 0.5);
+        assert!(linear.r() > srgb.r() && linear.g() > srgb.g());
+    }
+
+    #[test]
+    fn test_oklch_keeps_hue_for_gray_endpoint() {
+        let red = Color32::from_rgb(255, 0, 0);
+        let mid = InterpolationSpace::Oklch.mix(red, Color32::WHITE, 0.5);
+        assert!(mid.r() > mid.g() && mid.r() > mid.b());
+    }
+}
@@ -0,0 +1,21 @@
+use std::path::PathBuf;
+
+/// Name of the application's folder inside the platform configuration directory.
+const APP_DIR: &str = "fractals-rs";
+
+/// Returns the directory where the application keeps its files (bookmarks, ...):
+/// `%APPDATA%\fractals-rs` on Windows, `~/Library/Application Support/fractals-rs` on macOS,
+/// and `$XDG_CONFIG_HOME/fractals-rs` (or `~/.config/fractals-rs`) elsewhere.
+#[must_use]
+pub fn config_dir() -> Option<PathBuf> {
+    let env = |name: &str| std::env::var_os(name).filter(|value| !value.is_empty()).map(PathBuf::from);
+
+    let base = if cfg!(target_os = "windows") {
+        env("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        env("HOME").map(|home| home.join("Library").join("Application Support"))
+    } else {
+        env("XDG_CONFIG_HOME").or_else(|| env("HOME").map(|home| home.join(".config")))
+    };
+    base.map(|base| base.join(APP_DIR))
+}
@@ -0,0 +1,160 @@
+use crate::fractals::renderer::{self, RenderParams};
+use crate::utils::color_mapping::ColorSettings;
+use crate::utils::scene::Scene;
+use egui::Color32;
+use image::{ImageFormat, ImageResult, RgbImage};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+use std::path::Path;
+
+/// Keyword of the iTXt chunk holding the scene JSON in exported PNGs.
+pub const SCENE_KEYWORD: &str = "fractals-rs:scene";
+
+/// Renders `params` at its own resolution and writes it as a PNG that embeds its scene.
+/// The palette is built from the export's own iteration buffer, so histogram
+/// mapping follows the distribution of the exported frame.
+pub fn render_png(params: &RenderParams, colors: &ColorSettings, path: &Path) -> io::Result<()> {
+    let image = render_image(params, colors);
+    write_png(&image, path, Some(&Scene::new(params.clone(), colors.clone())))
+}
+
+/// Renders `params` at its own resolution into an 8-bit RGB image.
+#[must_use]
+pub fn render_image(params: &RenderParams, colors: &ColorSettings) -> RgbImage {
+    let buffer = renderer::compute_iterations(params);
+    to_rgb_image(&renderer::colorize(&buffer, &colors.build_palette(&buffer)))
+}
+
+/// Renders `params` with a palette built beforehand, so that the pieces of a larger image
+/// (bands, tiles) are all colored alike.
+#[must_use]
+pub fn render_with_palette(params: &RenderParams, palette: &[Color32]) -> RgbImage {
+    to_rgb_image(&renderer::colorize(&renderer::compute_iterations(params), palette))
+}
+
+/// Writes an RGB image as a PNG, with `scene` as JSON in an iTXt chunk so the view can be restored.
+pub fn write_png(image: &RgbImage, path: &Path, scene: Option<&Scene>) -> io::Result<()> {
+    let mut writer = png_writer(path, image.width(), image.height(), scene)?;
+    writer.write_image_data(image.as_raw()).map_err(io::Error::other)?;
+    writer.finish().map_err(io::Error::other)
+}
+
+/// Creates an 8-bit RGB PNG at `path` and writes its header and text chunks, leaving the
+/// image data to the caller (all at once, or streamed row by row).
+pub fn png_writer(path: &Path, width: u32, height: u32, scene: Option<&Scene>) -> io::Result<png::Writer<BufWriter<File>>> {
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = png::Encoder::new(file, width, height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .add_text_chunk(String::from("Software"), String::from("Fractals-rs"))
+        .map_err(io::Error::other)?;
+    if let Some(scene) = scene {
+        encoder
+            .add_itxt_chunk(String::from(SCENE_KEYWORD), scene.to_json())
+            .map_err(io::Error::other)?;
+    }
+    encoder.write_header().map_err(io::Error::other)
+}
+
+/// Reads the scene embedded in a PNG written by `write_png`.
+pub fn read_png_scene(path: &Path) -> Result<Scene, String> {
+    let file = File::open(path).map_err(|error| format!("{}: {error}", path.display()))?;
+    // Only the chunks before the image data are parsed; the pixels are never decoded
+    let reader = png::Decoder::new(BufReader::new(file))
+        .read_info()
+        .map_err(|error| format!("{}: {error}", path.display()))?;
+
+    let info = reader.info();
+    let itxt = info
+        .utf8_text
+        .iter()
+        .find(|chunk| chunk.keyword == SCENE_KEYWORD)
+        .map(png::text_metadata::ITXtChunk::get_text);
+    let text = match itxt {
+        Some(text) => text.map_err(|error| error.to_string())?,
+        // Some tools rewrite text chunks as plain tEXt
+        None => info
+            .uncompressed_latin1_text
+            .iter()
+            .find(|chunk| chunk.keyword == SCENE_KEYWORD)
+            .map(|chunk| chunk.text.clone())
+            .ok_or_else(|| format!("{} has no embedded fractal parameters", path.display()))?,
+    };
+    Scene::from_json(&text)
+}
+
+/// Writes a color image as an 8-bit RGB PNG.
+pub fn save_png(image: &egui::ColorImage, path: &Path) -> ImageResult<()> {
+    to_rgb_image(image).save_with_format(path, ImageFormat::Png)
+}
+
+/// Converts a color image to an 8-bit RGB image, dropping the (always opaque) alpha channel.
+#[must_use]
+pub fn to_rgb_image(image: &egui::ColorImage) -> RgbImage {
+    let rgb: Vec<u8> = image
+        .pixels
+        .iter()
+        .flat_map(|pixel| [pixel.r(), pixel.g(), pixel.b()])
+        .collect();
+
+    RgbImage::from_raw(image.size[0] as u32, image.size[1] as u32, rgb)
+        .expect("a ColorImage holds exactly width × height pixels")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fractals::anti_aliasing::AntiAliasing;
+    use crate::fractals::fractal_type::FractalType;
+    use crate::fractals::lyapunov::LyapunovSequence;
+    use crate::fractals::mariani_silver::RenderAlgorithm;
+    use crate::utils::color_mapping::ColorMapping;
+    use crate::utils::point::Point;
+    use crate::utils::precision_mode::PrecisionMode;
+
+    #[test]
+    fn test_render_png_round_trip() {
+        let params = RenderParams {
+            fractal_type: FractalType::Mandelbrot,
+            center: Point::new(-0.5, 0.0),
+            zoom: 1.0,
+            julia_c: Point::new(0.0, 0.0),
+            lyapunov_sequence: LyapunovSequence::default(),
+            max_iterations: 64,
+            precision_mode: PrecisionMode::High,
+            anti_aliasing: AntiAliasing::Adaptive,
+            algorithm: RenderAlgorithm::BruteForce,
+            width: 24,
+            height: 16,
+        };
+        let colors = ColorSettings {
+            mapping: ColorMapping::Histogram,
+            ..ColorSettings::default()
+        };
+        let path = std::env::temp_dir().join(format!("fractals_rs_export_test_{}.png", std::process::id()));
+
+        render_png(&params, &colors, &path).unwrap();
+        let decoded = image::open(&path).unwrap().into_rgb8();
+        let scene = read_png_scene(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(scene, Scene::new(params.clone(), colors.clone()));
+
+        let buffer = renderer::compute_iterations(&params);
+        let expected = renderer::colorize(&buffer, &colors.build_palette(&buffer));
+        assert_eq!(decoded.dimensions(), (24, 16));
+        for (decoded, expected) in decoded.pixels().zip(&expected.pixels) {
+            assert_eq!(decoded.0, [expected.r(), expected.g(), expected.b()]);
+        }
+    }
+
+    #[test]
+    fn test_png_without_scene() {
+        let path = std::env::temp_dir().join(format!("fractals_rs_plain_test_{}.png", std::process::id()));
+        write_png(&RgbImage::new(4, 4), &path, None).unwrap();
+        let error = read_png_scene(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert!(error.contains("no embedded fractal parameters"));
+    }
+}
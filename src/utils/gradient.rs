@@ -0,0 +1,144 @@
+use crate::utils::color_space::InterpolationSpace;
+use eframe::epaint::Color32;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+/// A color anchored at a position in `[0, 1]` along a gradient.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GradientStop {
+    pub position: f32,
+    #[serde(with = "hex_color")]
+    pub color: Color32,
+}
+
+impl GradientStop {
+    /// Creates a new gradient stop.
+    #[inline]
+    #[must_use]
+    pub const fn new(position: f32, color: Color32) -> Self {
+        Self { position, color }
+    }
+}
+
+/// A user-editable multi-stop gradient blended in a chosen color space.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Gradient {
+    pub stops: Vec<GradientStop>,
+    pub space: InterpolationSpace,
+}
+
+impl Default for Gradient {
+    /// A deep blue → white → orange → black gradient, a common escape-time starting point.
+    fn default() -> Self {
+        Self {
+            stops: vec![
+                GradientStop::new(0.0, Color32::from_rgb(0, 7, 100)),
+                GradientStop::new(0.16, Color32::from_rgb(32, 107, 203)),
+                GradientStop::new(0.42, Color32::from_rgb(237, 255, 255)),
+                GradientStop::new(0.64, Color32::from_rgb(255, 170, 0)),
+                GradientStop::new(0.86, Color32::from_rgb(0, 2, 0)),
+            ],
+            space: InterpolationSpace::default(),
+        }
+    }
+}
+
+impl Gradient {
+    /// Samples the gradient at `t` in `[0, 1]`.
+    #[inline]
+    #[must_use]
+    pub fn sample(&self, t: f32) -> Color32 {
+        sample_stops(&self.stops, self.space, t)
+    }
+
+    /// Sorts stops by position, as required by `sample`. Call after editing positions.
+    pub fn sort_stops(&mut self) {
+        self.stops
+            .sort_by(|a, b| a.position.total_cmp(&b.position));
+    }
+}
+
+/// Stores colors as `#rrggbb` strings, which are easier to read and edit than byte arrays.
+mod hex_color {
+    use super::{Color32, Deserialize, Deserializer, Serializer, de};
+
+    pub fn serialize<S: Serializer>(color: &Color32, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("#{:02x}{:02x}{:02x}", color.r(), color.g(), color.b()))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color32, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        let hex = text.strip_prefix('#').unwrap_or(&text);
+        match u32::from_str_radix(hex, 16) {
+            Ok(rgb) if hex.len() == 6 => {
+                let [_, r, g, b] = rgb.to_be_bytes();
+                Ok(Color32::from_rgb(r, g, b))
+            }
+            _ => Err(de::Error::custom(format!("expected a `#rrggbb` color, got `{text}`"))),
+        }
+    }
+}
+
+/// Samples a list of stops sorted by position at `t` in `[0, 1]`.
+/// Positions outside the first/last stop take that stop's color.
+#[must_use]
+pub fn sample_stops(stops: &[GradientStop], space: InterpolationSpace, t: f32) -> Color32 {
+    let (Some(first), Some(last)) = (stops.first(), stops.last()) else {
+        return Color32::BLACK;
+    };
+
+    if t <= first.position {
+        return first.color;
+    }
+    if t >= last.position {
+        return last.color;
+    }
+
+    // First stop strictly past `t`; the one before it starts the segment
+    let upper = stops.partition_point(|stop| stop.position <= t);
+    let (a, b) = (stops[upper - 1], stops[upper]);
+    let span = b.position - a.position;
+    if span <= f32::EPSILON {
+        return b.color;
+    }
+
+    space.mix(a.color, b.color, (t - a.position) / span)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_hits_stops() {
+        let gradient = Gradient::default();
+        for stop in &gradient.stops {
+            assert_eq!(gradient.sample(stop.position), stop.color);
+        }
+    }
+
+    #[test]
+    fn test_sample_clamps_outside_stops() {
+        let gradient = Gradient::default();
+        assert_eq!(gradient.sample(-1.0), gradient.stops[0].color);
+        assert_eq!(gradient.sample(2.0), gradient.stops[4].color);
+    }
+
+    #[test]
+    fn test_sample_empty_is_black() {
+        assert_eq!(sample_stops(&[], InterpolationSpace::Oklab, 0.5), Color32::BLACK);
+    }
+
+    #[test]
+    fn test_sort_stops() {
+        let mut gradient = Gradient {
+            stops: vec![
+                GradientStop::new(1.0, Color32::WHITE),
+                GradientStop::new(0.0, Color32::BLACK),
+            ],
+            space: InterpolationSpace::LinearRgb,
+        };
+        gradient.sort_stops();
+        assert_eq!(gradient.stops[0].color, Color32::BLACK);
+        assert_eq!(gradient.sample(0.0), Color32::BLACK);
+    }
+}
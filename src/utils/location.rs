@@ -0,0 +1,194 @@
+use crate::utils::point::Point;
+
+/// A place in the complex plane, with the center kept as the decimal text it was given in.
+///
+/// Coordinates may have any number of digits; they are rounded to the nearest `f64` only when the
+/// view moves there, so a pasted location can be copied on without losing digits.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Location {
+    pub re: String,
+    pub im: String,
+    pub zoom: f64,
+    pub iterations: Option<u16>,
+}
+
+/// Returns whether `text` is a decimal number: an optional sign, digits with an optional point,
+/// and an optional exponent (`1.5`, `-.25`, `3E-12`).
+#[must_use]
+pub fn is_decimal(text: &str) -> bool {
+    let unsigned = text.strip_prefix(['+', '-']).unwrap_or(text);
+    let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+        Some(index) => (&unsigned[..index], Some(&unsigned[index + 1..])),
+        None => (unsigned, None),
+    };
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = |part: &str| part.bytes().all(|byte| byte.is_ascii_digit());
+
+    let mantissa_ok = !(whole.is_empty() && fraction.is_empty()) && digits(whole) && digits(fraction);
+    let exponent_ok = exponent.is_none_or(|exponent| {
+        let exponent = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+        !exponent.is_empty() && digits(exponent)
+    });
+    mantissa_ok && exponent_ok
+}
+
+/// Parses a positive, finite zoom factor.
+fn parse_zoom(text: &str) -> Result<f64, String> {
+    let text = text.trim();
+    match text.parse::<f64>() {
+        Ok(zoom) if zoom.is_finite() && zoom > 0.0 => Ok(zoom),
+        _ => Err(format!("'{text}' is not a valid zoom")),
+    }
+}
+
+/// Checks that a coordinate is a decimal number and returns it trimmed.
+fn parse_coordinate(text: &str, axis: &str) -> Result<String, String> {
+    let text = text.trim();
+    if is_decimal(text) {
+        Ok(String::from(text))
+    } else {
+        Err(format!("{axis} '{text}' is not a decimal number"))
+    }
+}
+
+impl Location {
+    /// Creates a location from checked coordinate text.
+    pub fn new(re: &str, im: &str, zoom: f64) -> Result<Self, String> {
+        if !zoom.is_finite() || zoom <= 0.0 {
+            return Err(String::from("the zoom must be positive"));
+        }
+        Ok(Self {
+            re: parse_coordinate(re, "real part")?,
+            im: parse_coordinate(im, "imaginary part")?,
+            zoom,
+            iterations: None,
+        })
+    }
+
+    /// Returns the location of a view.
+    #[must_use]
+    pub fn from_view(center: Point, zoom: f64, iterations: u16) -> Self {
+        // `{}` prints the shortest decimal that reads back to the same `f64`
+        Self {
+            re: center.x.to_string(),
+            im: center.y.to_string(),
+            zoom,
+            iterations: Some(iterations),
+        }
+    }
+
+    /// Returns the center, rounded to the nearest `f64`.
+    pub fn center(&self) -> Point {
+        Point::new(self.re.parse().unwrap_or(0.0), self.im.parse().unwrap_or(0.0))
+    }
+
+    /// Returns the location as `Re:`/`Im:`/`Zoom:`/`Iterations:` lines, as written by Kalles Fraktaler.
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        let mut text = format!("Re: {}\nIm: {}\nZoom: {:E}\n", self.re, self.im, self.zoom);
+        if let Some(iterations) = self.iterations {
+            text += &format!("Iterations: {iterations}\n");
+        }
+        text
+    }
+
+    /// Parses a location from pasted text. Accepted forms:
+    /// - Kalles Fraktaler parameters: `Re: ...`, `Im: ...`, `Zoom: ...` lines (other lines are ignored)
+    /// - XaoS positions: `(view RE IM WIDTH HEIGHT)`, where the height sets the zoom
+    /// - Plain numbers: `RE, IM` or `RE IM ZOOM`
+    pub fn parse(text: &str) -> Result<Self, String> {
+        if let Some(start) = text.find("(view") {
+            return Self::parse_xaos(&text[start + "(view".len()..]);
+        }
+        if text.lines().any(|line| line.trim_start().to_ascii_lowercase().starts_with("re:")) {
+            return Self::parse_kalles(text);
+        }
+
+        let fields: Vec<&str> = text
+            .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+            .filter(|field| !field.is_empty())
+            .collect();
+        match fields.as_slice() {
+            [re, im] => Self::new(re, im, 1.0),
+            [re, im, zoom] => Self::new(re, im, parse_zoom(zoom)?),
+            _ => Err(String::from("expected 'RE, IM', 'RE IM ZOOM', Kalles Fraktaler or XaoS parameters")),
+        }
+    }
+
+    /// Parses `Key: value` lines; `Zoom` defaults to 1 and `Iterations` is optional.
+    fn parse_kalles(text: &str) -> Result<Self, String> {
+        let value = |key: &str| {
+            text.lines().find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                name.trim().eq_ignore_ascii_case(key).then(|| value.trim())
+            })
+        };
+        let re = value("Re").ok_or("missing 'Re:' line")?;
+        let im = value("Im").ok_or("missing 'Im:' line")?;
+        let zoom = value("Zoom").map_or(Ok(1.0), parse_zoom)?;
+        let mut location = Self::new(re, im, zoom)?;
+        location.iterations = value("Iterations")
+            .map(|text| text.parse::<u64>().map_err(|_| format!("'{text}' is not a valid iteration count")))
+            .transpose()?
+            .map(|iterations| iterations.min(u64::from(u16::MAX)) as u16);
+        Ok(location)
+    }
+
+    /// Parses the arguments of a XaoS `(view RE IM WIDTH HEIGHT)` command.
+    fn parse_xaos(arguments: &str) -> Result<Self, String> {
+        let arguments = arguments.split(')').next().unwrap_or_default();
+        let fields: Vec<&str> = arguments.split_whitespace().collect();
+        let [re, im, _width, height] = fields.as_slice() else {
+            return Err(String::from("expected '(view RE IM WIDTH HEIGHT)'"));
+        };
+        // Our view is 4 units tall at zoom 1
+        let height = parse_zoom(height).map_err(|_| format!("'{height}' is not a valid view height"))?;
+        Self::new(re, im, 4.0 / height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decimals() {
+        for valid in ["0", "-1.5", "+.25", "3.", "1e10", "-2.5E-12", "0.131825904205311970493132056385139"] {
+            assert!(is_decimal(valid), "{valid}");
+        }
+        for invalid in ["", "-", ".", "1e", "1.2.3", "0x10", "inf", "NaN", "1,5"] {
+            assert!(!is_decimal(invalid), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_parse_formats() {
+        let kalles = Location::parse(
+            "Re: -0.743643887037158704752191506114774\r\nIm: 0.131825904205311970493132056385139\r\nZoom: 1.5E10\r\nIterations: 12000\r\nSmooth: 1\r\n",
+        )
+        .unwrap();
+        assert_eq!(kalles.re, "-0.743643887037158704752191506114774");
+        assert_eq!(kalles.zoom, 1.5e10);
+        assert_eq!(kalles.iterations, Some(12000));
+        assert_eq!(kalles.center(), Point::new(-0.743_643_887_037_158_7, 0.131_825_904_205_311_97));
+
+        let xaos = Location::parse(";; Position file\n(initstate)\n(view -0.75 0.1 0.5 0.25)\n").unwrap();
+        assert_eq!((xaos.re.as_str(), xaos.im.as_str(), xaos.zoom), ("-0.75", "0.1", 16.0));
+
+        let plain = Location::parse("-0.5, 0.25").unwrap();
+        assert_eq!(plain.center(), Point::new(-0.5, 0.25));
+        assert_eq!(Location::parse("-0.5 0.25 1e3").unwrap().zoom, 1000.0);
+
+        assert!(Location::parse("Re: abc\nIm: 0").is_err());
+        assert!(Location::parse("1 2 0").is_err());
+        assert!(Location::parse("(view 1 2 3)").is_err());
+    }
+
+    #[test]
+    fn test_copied_text_pastes_back() {
+        let location = Location::from_view(Point::new(-1.768_778_833, -0.001_738_996), 2.5e7, 800);
+        let pasted = Location::parse(&location.to_text()).unwrap();
+        assert_eq!(pasted, location);
+        assert_eq!(pasted.center(), Point::new(-1.768_778_833, -0.001_738_996));
+    }
+}
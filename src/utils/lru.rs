@@ -0,0 +1,157 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+/// A cached value with its size and the tick of its last use.
+struct Entry<V> {
+    value: V,
+    size: usize,
+    used: u64,
+}
+
+/// A map that holds at most `capacity` bytes of values, evicting the least recently used ones.
+///
+/// Sizes are given by the caller when inserting, so any notion of cost (bytes, pixels) works.
+pub struct LruCache<K, V> {
+    capacity: usize,
+    size: usize,
+    tick: u64,
+    entries: HashMap<K, Entry<V>>,
+    order: BTreeMap<u64, K>, // Keys by last use, oldest first
+}
+
+impl<K: Clone + Eq + Hash, V> LruCache<K, V> {
+    /// Creates an empty cache holding at most `capacity` bytes.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            size: 0,
+            tick: 0,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the number of cached values.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if nothing is cached.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the total size of the cached values.
+    #[inline]
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the largest total size the cache holds.
+    #[inline]
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the value cached for `key`, marking it as the most recently used.
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let entry = self.entries.get_mut(key)?;
+        self.order.remove(&entry.used);
+        self.tick += 1;
+        entry.used = self.tick;
+        self.order.insert(self.tick, key.clone());
+        Some(&entry.value)
+    }
+
+    /// Caches `value` under `key`, then evicts the least recently used values until the cache fits.
+    /// A value larger than the whole capacity is not kept.
+    pub fn insert(&mut self, key: K, value: V, size: usize) {
+        self.remove(&key);
+        if size > self.capacity {
+            return;
+        }
+        self.tick += 1;
+        self.order.insert(self.tick, key.clone());
+        self.entries.insert(
+            key,
+            Entry {
+                value,
+                size,
+                used: self.tick,
+            },
+        );
+        self.size += size;
+        self.evict();
+    }
+
+    /// Removes and returns the value cached for `key`.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let entry = self.entries.remove(key)?;
+        self.order.remove(&entry.used);
+        self.size -= entry.size;
+        Some(entry.value)
+    }
+
+    /// Changes the capacity, evicting values if the cache no longer fits.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.evict();
+    }
+
+    /// Drops the least recently used values until the cache fits its capacity.
+    fn evict(&mut self) {
+        while self.size > self.capacity {
+            let Some((_, oldest)) = self.order.pop_first() else {
+                break;
+            };
+            if let Some(entry) = self.entries.remove(&oldest) {
+                self.size -= entry.size;
+            }
+        }
+    }
+
+    /// Removes every value.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+        self.size = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mut cache = LruCache::new(10);
+        cache.insert("a", 1, 4);
+        cache.insert("b", 2, 4);
+        assert_eq!(cache.get(&"a"), Some(&1));
+
+        // "b" is now the oldest and makes room for "c"
+        cache.insert("c", 3, 4);
+        assert_eq!(cache.get(&"b"), None);
+        assert_eq!((cache.len(), cache.size()), (2, 8));
+
+        // Replacing a value updates the size instead of adding to it
+        cache.insert("a", 4, 2);
+        assert_eq!(cache.size(), 6);
+        assert_eq!(cache.get(&"a"), Some(&4));
+
+        cache.insert("huge", 5, 11);
+        assert_eq!(cache.get(&"huge"), None);
+        assert_eq!(cache.len(), 2);
+
+        cache.set_capacity(3);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get(&"a"), Some(&4));
+    }
+}
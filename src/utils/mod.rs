@@ -1,3 +1,14 @@
 pub mod precision_mode;
 pub mod color_scheme;
-pub mod point;
+pub mod color_space;
+pub mod color_mapping;
+pub mod gradient;
+pub mod export;
+pub mod poster;
+pub mod tiles;
+pub mod lru;
+pub mod config;
+pub mod bookmarks;
+pub mod scene;
+pub mod location;
+pub mod point;
\ No newline at end of file
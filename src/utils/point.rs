@@ -1,5 +1,7 @@
+use serde::{Deserialize, Serialize};
+
 /// This structure represents a point in a 2D space with `x` and `y` coordinates.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 #[must_use]
 pub struct Point {
     pub x: f64,
@@ -0,0 +1,339 @@
+use crate::fractals::anti_aliasing::AntiAliasing;
+use crate::fractals::renderer::{self, RenderParams};
+use crate::utils::color_mapping::ColorSettings;
+use crate::utils::export;
+use crate::utils::scene::Scene;
+use egui::Color32;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Samples, primary and supersampled, held in memory per band when no band height is asked for:
+/// about 32 MB of iteration counts.
+pub const BAND_SAMPLES: u64 = 16 * 1024 * 1024;
+
+/// Largest image, in pixels, rendered in one piece; larger exports are rendered in bands.
+pub const DIRECT_RENDER_PIXELS: u64 = 32 * 1024 * 1024;
+
+/// Longest side of the preview whose iteration counts stand in for the whole poster when
+/// building the palette.
+const PREVIEW_SIZE: u32 = 1024;
+
+/// Name of the file recording what the bands of a work directory belong to.
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// What `render_poster` did, band by band.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PosterSummary {
+    pub rendered: u32,
+    pub reused: u32,  // Bands found on disk from an interrupted run
+    pub complete: bool, // False when cancelled before the output was written
+}
+
+/// The scene and band height the bands of a work directory were rendered with.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Manifest {
+    band_height: u32,
+    scene: Scene,
+}
+
+/// Returns `true` if an image of this size is too large to render in one piece.
+#[inline]
+#[must_use]
+pub const fn needs_bands(width: u32, height: u32) -> bool {
+    width as u64 * height as u64 > DIRECT_RENDER_PIXELS
+}
+
+/// Returns the number of rows per band that keeps a band of `params` within `BAND_SAMPLES`
+/// samples, whatever its width and supersampling.
+#[must_use]
+pub fn default_band_height(params: &RenderParams) -> u32 {
+    let supersamples = match params.anti_aliasing {
+        AntiAliasing::Off => 0,
+        mode => mode.grid_size() * mode.grid_size(),
+    };
+    let row_samples = u64::from(params.width.max(1)) * (1 + supersamples as u64);
+    (BAND_SAMPLES / row_samples).clamp(1, u64::from(params.height.max(1))) as u32
+}
+
+/// Returns the number of bands of `band_height` rows covering `height` rows.
+#[inline]
+#[must_use]
+pub const fn band_count(height: u32, band_height: u32) -> u32 {
+    height.div_ceil(band_height)
+}
+
+/// Returns the directory holding the finished bands of `output` while it is rendered.
+#[must_use]
+pub fn work_directory(output: &Path) -> PathBuf {
+    let mut name = output.as_os_str().to_owned();
+    name.push(".bands");
+    PathBuf::from(name)
+}
+
+/// Returns the path of band `index` inside `directory`.
+#[inline]
+#[must_use]
+fn band_path(directory: &Path, index: u32) -> PathBuf {
+    directory.join(format!("band_{index:05}.png"))
+}
+
+/// Builds the palette of the whole poster from a preview of at most `PREVIEW_SIZE` pixels a side,
+/// so every band is colored alike even with histogram mapping.
+#[must_use]
+pub fn poster_palette(params: &RenderParams, colors: &ColorSettings) -> Vec<Color32> {
+    let longest = params.width.max(params.height);
+    let preview = if longest <= PREVIEW_SIZE {
+        params.clone()
+    } else {
+        let scale = f64::from(PREVIEW_SIZE) / f64::from(longest);
+        RenderParams {
+            width: ((f64::from(params.width) * scale).round() as u32).max(1),
+            height: ((f64::from(params.height) * scale).round() as u32).max(1),
+            anti_aliasing: AntiAliasing::Off,
+            ..params.clone()
+        }
+    };
+    colors.build_palette(&renderer::compute_iterations(&preview))
+}
+
+/// Returns `true` for the files a poster keeps in its work directory.
+fn is_work_file(name: &str) -> bool {
+    name == MANIFEST_FILE || name.starts_with("band_") && (name.ends_with(".png") || name.ends_with(".png.part"))
+}
+
+/// Deletes the files a poster kept in `directory`, leaving any other file alone.
+fn remove_work_files(directory: &Path) -> io::Result<()> {
+    for entry in fs::read_dir(directory)? {
+        let entry = entry?;
+        if entry.file_name().to_str().is_some_and(is_work_file) {
+            fs::remove_file(entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+/// Opens the work directory of `output`, discarding the bands of a different render. A directory
+/// of that name that holds files but no manifest of ours is not touched: an error is returned.
+fn prepare_work_directory(directory: &Path, manifest: &Manifest) -> io::Result<()> {
+    let existing = fs::read_to_string(directory.join(MANIFEST_FILE))
+        .ok()
+        .and_then(|text| serde_json::from_str::<Manifest>(&text).ok());
+    if existing.as_ref() == Some(manifest) {
+        return Ok(());
+    }
+
+    if existing.is_some() {
+        remove_work_files(directory)?;
+    } else if directory.exists() && fs::read_dir(directory)?.next().is_some() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} exists and is not the work directory of a poster; move it out of the way", directory.display()),
+        ));
+    }
+    fs::create_dir_all(directory)?;
+    let json = serde_json::to_string_pretty(manifest).map_err(io::Error::other)?;
+    fs::write(directory.join(MANIFEST_FILE), json)
+}
+
+/// Renders `params` to a PNG at `output` in bands of `band_height` rows, so that images far
+/// larger than memory can be exported.
+///
+/// Every finished band is kept as a PNG in `work_directory(output)`. An interrupted render resumes
+/// from those bands when run again with the same scene and band height; once all bands exist they
+/// are streamed row by row into the output, and the work directory is removed. `cancel` is polled
+/// between bands; `progress` receives the number of finished bands.
+pub fn render_poster(
+    params: &RenderParams,
+    colors: &ColorSettings,
+    output: &Path,
+    band_height: u32,
+    cancel: &AtomicBool,
+    mut progress: impl FnMut(u32),
+) -> io::Result<PosterSummary> {
+    let band_height = band_height.clamp(1, params.height.max(1));
+    let scene = Scene::new(params.clone(), colors.clone());
+    let directory = work_directory(output);
+    prepare_work_directory(
+        &directory,
+        &Manifest {
+            band_height,
+            scene: scene.clone(),
+        },
+    )?;
+
+    let bands = band_count(params.height, band_height);
+    let mut palette: Option<Vec<Color32>> = None;
+    let mut summary = PosterSummary::default();
+    for band in 0..bands {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(summary);
+        }
+
+        let path = band_path(&directory, band);
+        if path.is_file() {
+            summary.reused += 1;
+        } else {
+            let palette = palette.get_or_insert_with(|| poster_palette(params, colors));
+            let top = band * band_height;
+            let region = params.region(0, top, params.width, band_height.min(params.height - top));
+            let image = export::render_with_palette(&region, palette);
+
+            // Write then rename: an interrupted band never looks complete on resume
+            let partial = path.with_extension("png.part");
+            export::write_png(&image, &partial, None)?;
+            fs::rename(&partial, &path)?;
+            summary.rendered += 1;
+        }
+        progress(band + 1);
+    }
+
+    assemble(params, &scene, &directory, bands, output)?;
+    remove_work_files(&directory)?;
+    // Kept if something else was put in it meanwhile
+    let _ = fs::remove_dir(&directory);
+    summary.complete = true;
+    Ok(summary)
+}
+
+/// Streams the bands of `directory` into the PNG at `output`, one band in memory at a time.
+fn assemble(params: &RenderParams, scene: &Scene, directory: &Path, bands: u32, output: &Path) -> io::Result<()> {
+    let mut partial = output.as_os_str().to_owned();
+    partial.push(".part");
+    let partial = PathBuf::from(partial);
+
+    let mut writer = export::png_writer(&partial, params.width, params.height, Some(scene))?;
+    let mut stream = writer.stream_writer().map_err(io::Error::other)?;
+    for band in 0..bands {
+        let path = band_path(directory, band);
+        let image = image::open(&path).map_err(io::Error::other)?.into_rgb8();
+        if image.width() != params.width {
+            return Err(io::Error::other(format!("{} does not match the poster's width", path.display())));
+        }
+        stream.write_all(image.as_raw())?;
+    }
+    stream.finish().map_err(io::Error::other)?;
+    fs::rename(&partial, output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fractals::fractal_type::FractalType;
+    use crate::fractals::lyapunov::LyapunovSequence;
+    use crate::fractals::mariani_silver::RenderAlgorithm;
+    use crate::utils::color_mapping::ColorMapping;
+    use crate::utils::point::Point;
+    use crate::utils::precision_mode::PrecisionMode;
+
+    fn params() -> RenderParams {
+        RenderParams {
+            fractal_type: FractalType::Mandelbrot,
+            center: Point::new(-0.5, 0.0),
+            zoom: 1.0,
+            julia_c: Point::new(0.0, 0.0),
+            lyapunov_sequence: LyapunovSequence::default(),
+            max_iterations: 64,
+            precision_mode: PrecisionMode::High,
+            anti_aliasing: AntiAliasing::Off,
+            algorithm: RenderAlgorithm::BruteForce,
+            width: 40,
+            height: 26,
+        }
+    }
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("fractals_rs_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_poster_matches_direct_render() {
+        let dir = scratch_dir("poster");
+        let colors = ColorSettings {
+            mapping: ColorMapping::Histogram,
+            ..ColorSettings::default()
+        };
+        let output = dir.join("poster.png");
+        let summary = render_poster(&params(), &colors, &output, 7, &AtomicBool::new(false), |_| {}).unwrap();
+        assert_eq!(summary, PosterSummary { rendered: 4, reused: 0, complete: true });
+        assert!(!work_directory(&output).exists());
+
+        let direct = dir.join("direct.png");
+        export::render_png(&params(), &colors, &direct).unwrap();
+        let poster = image::open(&output).unwrap().into_rgb8();
+        let expected = image::open(&direct).unwrap().into_rgb8();
+        // Band edges may round a coordinate differently; nearly every pixel must match
+        let differing = poster.pixels().zip(expected.pixels()).filter(|(a, b)| a != b).count();
+        assert_eq!(poster.dimensions(), (40, 26));
+        assert!(differing <= 4, "{differing} pixels differ");
+        assert_eq!(export::read_png_scene(&output).unwrap(), Scene::new(params(), colors));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_poster_resumes_from_bands() {
+        let dir = scratch_dir("poster_resume");
+        let output = dir.join("poster.png");
+        let colors = ColorSettings::default();
+        let cancel = AtomicBool::new(false);
+
+        // Stop after two bands, as if interrupted
+        let summary = render_poster(&params(), &colors, &output, 10, &cancel, |done| {
+            if done == 2 {
+                cancel.store(true, Ordering::Relaxed);
+            }
+        })
+        .unwrap();
+        assert_eq!(summary, PosterSummary { rendered: 2, reused: 0, complete: false });
+        assert!(!output.exists());
+
+        cancel.store(false, Ordering::Relaxed);
+        let summary = render_poster(&params(), &colors, &output, 10, &cancel, |_| {}).unwrap();
+        assert_eq!(summary, PosterSummary { rendered: 1, reused: 2, complete: true });
+        assert_eq!(image::open(&output).unwrap().into_rgb8().dimensions(), (40, 26));
+
+        // Bands of a different scene are not reused
+        let mut other = params();
+        other.zoom = 2.0;
+        render_poster(&other, &colors, &output, 10, &cancel, |done| {
+            if done == 1 {
+                cancel.store(true, Ordering::Relaxed);
+            }
+        })
+        .unwrap();
+        cancel.store(false, Ordering::Relaxed);
+        let summary = render_poster(&params(), &colors, &output, 10, &cancel, |_| {}).unwrap();
+        assert_eq!(summary.reused, 0);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_foreign_work_directory_is_kept() {
+        let dir = scratch_dir("poster_foreign");
+        let output = dir.join("poster.png");
+        let directory = work_directory(&output);
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("notes.txt"), "mine").unwrap();
+
+        let colors = ColorSettings::default();
+        let error = render_poster(&params(), &colors, &output, 10, &AtomicBool::new(false), |_| {}).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read_to_string(directory.join("notes.txt")).unwrap(), "mine");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_default_band_height_follows_samples() {
+        let mut wide = params();
+        (wide.width, wide.height) = (100_000, 100_000);
+        assert_eq!(default_band_height(&wide), (BAND_SAMPLES / 100_000) as u32);
+        wide.anti_aliasing = AntiAliasing::Grid4x4;
+        assert_eq!(default_band_height(&wide), (BAND_SAMPLES / (17 * 100_000)) as u32);
+        assert_eq!(default_band_height(&params()), 26);
+    }
+}
@@ -1,5 +1,7 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
 /// Enum representing different precision modes for numerical computations.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(u8)]
 pub enum PrecisionMode {
     Fast,      // f32 - Standard precision, fastest
@@ -8,6 +10,28 @@ pub enum PrecisionMode {
     UltraHigh, // f128 - Quadruple precision for extreme zooms (128-bit decimal)
 }
 
+impl Serialize for PrecisionMode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{self:?}"))
+    }
+}
+
+/// `UltraHigh` falls back to `High` in builds without the `f128` feature; other names are errors.
+impl<'de> Deserialize<'de> for PrecisionMode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        match name.as_str() {
+            "Fast" => Ok(Self::Fast),
+            "High" => Ok(Self::High),
+            #[cfg(feature = "f128")]
+            "UltraHigh" => Ok(Self::UltraHigh),
+            #[cfg(not(feature = "f128"))]
+            "UltraHigh" => Ok(Self::High),
+            _ => Err(de::Error::custom(format!("unknown precision mode `{name}`"))),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::PrecisionMode;
@@ -20,6 +44,18 @@ mod tests {
         assert_eq!(format!("{:?}", PrecisionMode::UltraHigh), "UltraHigh");
     }
 
+    #[test]
+    fn test_precision_mode_names_load() {
+        let load = |name: &str| serde_json::from_str::<PrecisionMode>(&format!("\"{name}\""));
+        assert_eq!(load("Fast").unwrap(), PrecisionMode::Fast);
+        assert_eq!(load("High").unwrap(), PrecisionMode::High);
+        #[cfg(feature = "f128")]
+        assert_eq!(load("UltraHigh").unwrap(), PrecisionMode::UltraHigh);
+        #[cfg(not(feature = "f128"))]
+        assert_eq!(load("UltraHigh").unwrap(), PrecisionMode::High);
+        assert!(load("Hihg").is_err());
+    }
+
     #[test]
     fn test_precision_mode_equality() {
         assert_eq!(PrecisionMode::Fast, PrecisionMode::Fast);
@@ -0,0 +1,180 @@
+use crate::fractals::renderer::RenderParams;
+use crate::utils::color_mapping::ColorSettings;
+use crate::utils::export;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Version written into new scene files. Bump it when the meaning of an existing field changes;
+/// adding fields does not need a bump, since readers ignore fields they do not know. Files from a
+/// newer version are refused, as their fields may not mean what this version thinks.
+pub const SCENE_VERSION: u32 = 1;
+
+/// First bytes of every PNG file.
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Everything needed to reproduce a render: view, fractal, quality and colors.
+///
+/// Scenes are stored as pretty-printed JSON. Coordinates are written with the shortest decimal
+/// that reads back to the exact same `f64`, so no precision is lost. Missing fields take their
+/// default value and unknown fields are ignored, so files from older versions, and newer files
+/// that only add fields, load.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Scene {
+    pub version: u32,
+    pub render: RenderParams,
+    pub colors: ColorSettings,
+}
+
+impl Default for Scene {
+    /// The viewer's start-up scene.
+    #[inline]
+    fn default() -> Self {
+        Self {
+            version: SCENE_VERSION,
+            render: RenderParams::default(),
+            colors: ColorSettings::default(),
+        }
+    }
+}
+
+impl Scene {
+    /// Creates a scene at the current format version.
+    #[inline]
+    #[must_use]
+    pub const fn new(render: RenderParams, colors: ColorSettings) -> Self {
+        Self {
+            version: SCENE_VERSION,
+            render,
+            colors,
+        }
+    }
+
+    /// Parses a scene from JSON text.
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let scene: Self = serde_json::from_str(text).map_err(|error| format!("invalid scene file: {error}"))?;
+        if scene.version > SCENE_VERSION {
+            return Err(format!(
+                "scene file version {} is newer than the supported version {SCENE_VERSION}",
+                scene.version
+            ));
+        }
+        let render = &scene.render;
+        if render.width == 0 || render.height == 0 || !render.zoom.is_finite() || render.zoom <= 0.0 {
+            return Err(String::from("invalid scene file: size and zoom must be positive"));
+        }
+        Ok(scene)
+    }
+
+    /// Returns the scene as pretty-printed JSON.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("scenes only hold JSON-representable values")
+    }
+
+    /// Reads a scene file, or the scene embedded in a PNG exported by the viewer.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let error = |error: io::Error| format!("{}: {error}", path.display());
+        let mut file = File::open(path).map_err(error)?;
+        let mut header = Vec::with_capacity(PNG_SIGNATURE.len());
+        (&mut file).take(PNG_SIGNATURE.len() as u64).read_to_end(&mut header).map_err(error)?;
+        if header == PNG_SIGNATURE {
+            return export::read_png_scene(path);
+        }
+
+        let mut bytes = header;
+        file.read_to_end(&mut bytes).map_err(error)?;
+        let text = String::from_utf8(bytes).map_err(|_| format!("{} is not a scene file", path.display()))?;
+        Self::from_json(&text)
+    }
+
+    /// Writes a scene file.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_json() + "\n").map_err(|error| format!("{}: {error}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fractals::anti_aliasing::AntiAliasing;
+    use crate::fractals::fractal_type::FractalType;
+    use crate::utils::color_mapping::ColorMapping;
+    use crate::utils::color_scheme::ColorScheme;
+    use crate::utils::gradient::Gradient;
+    use crate::utils::point::Point;
+
+    #[test]
+    fn test_scene_round_trip_is_exact() {
+        let scene = Scene::new(
+            RenderParams {
+                fractal_type: FractalType::Julia,
+                center: Point::new(-0.743_643_887_037_158_7, 0.131_825_904_205_311_97),
+                zoom: 3.141_592_653_589_793e12,
+                julia_c: Point::new(0.1 + 0.2, -0.8),
+                anti_aliasing: AntiAliasing::Jittered,
+                ..RenderParams::default()
+            },
+            ColorSettings {
+                scheme: ColorScheme::DeepOcean,
+                gradient: Some(Gradient::default()),
+                mapping: ColorMapping::Histogram,
+                offset: 0.37,
+            },
+        );
+        assert_eq!(Scene::from_json(&scene.to_json()).unwrap(), scene);
+    }
+
+    #[test]
+    fn test_scene_is_human_readable() {
+        let json = Scene::default().to_json();
+        assert!(json.contains("\"fractal_type\": \"Mandelbrot Set\""));
+        assert!(json.contains("\"scheme\": \"Classic\""));
+        let gradient = serde_json::to_string(&Gradient::default()).unwrap();
+        assert!(gradient.contains("\"#000764\""));
+    }
+
+    #[test]
+    fn test_unknown_and_missing_fields() {
+        let scene = Scene::from_json(
+            r#"{
+                "version": 1,
+                "render": { "fractal_type": "Burning Ship", "zoom": 4.0, "sparkle": true },
+                "colors": { "scheme": "inferno" },
+                "camera": { "roll": 12 }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(scene.version, 1);
+        assert_eq!(scene.render.fractal_type, FractalType::BurningShip);
+        assert_eq!(scene.render.zoom, 4.0);
+        assert_eq!(scene.render.max_iterations, RenderParams::default().max_iterations);
+        assert_eq!(scene.colors.scheme, ColorScheme::Inferno);
+    }
+
+    #[test]
+    fn test_invalid_scenes_are_rejected() {
+        assert!(Scene::from_json("not json").is_err());
+        assert!(Scene::from_json(r#"{ "render": { "zoom": 0.0 } }"#).is_err());
+        assert!(Scene::from_json(r#"{ "render": { "fractal_type": "Nova" } }"#).is_err());
+        assert!(Scene::from_json(r#"{ "render": { "precision_mode": "Hihg" } }"#).is_err());
+        assert!(Scene::from_json(r#"{ "colors": { "scheme": "Plaid" } }"#).is_err());
+        let newer = Scene::from_json(&format!(r#"{{ "version": {} }}"#, SCENE_VERSION + 1)).unwrap_err();
+        assert!(newer.contains("newer"), "{newer}");
+    }
+
+    #[test]
+    fn test_load_json_files() {
+        let path = std::env::temp_dir().join(format!("fractals_rs_scene_{}.json", std::process::id()));
+        let scene = Scene::new(RenderParams::default(), ColorSettings::default());
+        scene.save(&path).unwrap();
+        assert_eq!(Scene::load(&path).unwrap(), scene);
+
+        // Shorter than a PNG signature
+        fs::write(&path, "{}").unwrap();
+        assert_eq!(Scene::load(&path).unwrap(), Scene::default());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,448 @@
+use crate::fractals::renderer::RenderParams;
+use crate::utils::color_mapping::ColorSettings;
+use crate::utils::export;
+use crate::utils::point::Point;
+use crate::utils::poster;
+use crate::utils::scene::Scene;
+use egui::Color32;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+/// Deepest XYZ zoom level of an exported pyramid; level 12 alone is 16 million tiles.
+pub const MAX_XYZ_ZOOM: u32 = 12;
+
+/// Name of the file recording what the tiles of a pyramid directory were rendered from.
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// How the tiles of a pyramid are laid out on disk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum TileLayout {
+    #[default]
+    Dzi, // DeepZoom: `NAME.dzi` plus `NAME_files/LEVEL/COL_ROW.png`, as read by OpenSeadragon
+    Xyz, // Slippy map: `Z/X/Y.png` over a square world, as read by Leaflet and OpenLayers
+}
+
+impl TileLayout {
+    /// Returns the layout's command-line name.
+    #[inline]
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Dzi => "dzi",
+            Self::Xyz => "xyz",
+        }
+    }
+
+    /// Returns every layout.
+    #[inline]
+    #[must_use]
+    pub const fn all() -> [Self; 2] {
+        [Self::Dzi, Self::Xyz]
+    }
+}
+
+/// Shape of an exported tile pyramid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PyramidSettings {
+    pub layout: TileLayout,
+    pub tile_size: u32,
+    pub overlap: u32,  // DZI only: pixels each tile shares with its neighbours
+    pub max_zoom: u32, // XYZ only: deepest zoom level; the DZI depth follows the image size
+}
+
+impl Default for PyramidSettings {
+    /// 256-pixel DZI tiles overlapping by one pixel, or XYZ levels 0 to 6.
+    #[inline]
+    fn default() -> Self {
+        Self {
+            layout: TileLayout::Dzi,
+            tile_size: 256,
+            overlap: 1,
+            max_zoom: 6,
+        }
+    }
+}
+
+/// What `export_pyramid` did, tile by tile.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PyramidSummary {
+    pub rendered: u32,
+    pub skipped: u32, // Tiles already on disk
+}
+
+/// One tile to render: where it goes and which pixels it covers.
+struct Tile {
+    path: PathBuf,
+    params: RenderParams,
+}
+
+/// Everything that decides the pixels of a pyramid's tiles. The depth is left out, so a pyramid
+/// can be extended with deeper levels.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Manifest {
+    scene: Scene,
+    tile_size: u32,
+    overlap: u32,
+}
+
+/// Returns the deepest DZI level of a `width`×`height` image, whose level 0 is a single pixel.
+#[inline]
+#[must_use]
+pub const fn dzi_max_level(width: u32, height: u32) -> u32 {
+    let longest = if width > height { width } else { height };
+    longest.next_power_of_two().trailing_zeros()
+}
+
+/// Returns the size of DZI `level` of a `width`×`height` image: halved, rounding up, per level.
+#[inline]
+#[must_use]
+pub const fn dzi_level_size(width: u32, height: u32, level: u32) -> (u32, u32) {
+    let shift = dzi_max_level(width, height) - level;
+    (width.div_ceil(1 << shift), height.div_ceil(1 << shift))
+}
+
+/// Returns the square XYZ world around a view: centered on it and just large enough to hold it,
+/// one `tile_size` tile across at zoom level 0.
+#[must_use]
+pub fn xyz_world(params: &RenderParams, tile_size: u32) -> RenderParams {
+    let longest = params.width.max(params.height);
+    RenderParams {
+        zoom: params.zoom * f64::from(params.height) / f64::from(longest),
+        width: tile_size,
+        height: tile_size,
+        ..params.clone()
+    }
+}
+
+/// Returns the parameters of XYZ tile `(x, y)` at zoom level `z` of `world`.
+#[must_use]
+pub fn xyz_tile(world: &RenderParams, z: u32, x: u32, y: u32) -> RenderParams {
+    // Computed from the world's corner rather than through a level-sized view, whose pixel
+    // count would overflow at deep levels
+    let tiles = 2f64.powi(z as i32);
+    let (x_scale, y_scale, x_min, y_min) = world.compute_scale();
+    let (tile_x_scale, tile_y_scale) = (x_scale / tiles, y_scale / tiles);
+    let center = Point::new(
+        ((f64::from(x) + 0.5) * f64::from(world.width)).mul_add(tile_x_scale, x_min),
+        ((f64::from(y) + 0.5) * f64::from(world.height)).mul_add(tile_y_scale, y_min),
+    );
+    RenderParams {
+        center,
+        zoom: world.zoom * tiles,
+        ..world.clone()
+    }
+}
+
+/// Returns the DZI descriptor of a `width`×`height` image.
+#[must_use]
+pub fn dzi_descriptor(width: u32, height: u32, tile_size: u32, overlap: u32) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <Image xmlns=\"http://schemas.microsoft.com/deepzoom/2008\" Format=\"png\" Overlap=\"{overlap}\" TileSize=\"{tile_size}\">\n  \
+         <Size Width=\"{width}\" Height=\"{height}\"/>\n\
+         </Image>\n"
+    )
+}
+
+/// Returns the directory holding the tiles of the DZI descriptor at `output`.
+#[must_use]
+pub fn dzi_files_directory(output: &Path) -> PathBuf {
+    let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+    output.with_file_name(format!("{stem}_files"))
+}
+
+/// Yields every tile of the DZI pyramid of `params` in `directory`, coarsest level first. Tiles are
+/// made as they are reached, so the pyramid is never listed in memory.
+fn dzi_tiles<'a>(
+    params: &'a RenderParams,
+    settings: &PyramidSettings,
+    directory: &'a Path,
+) -> impl ParallelIterator<Item = Tile> + 'a {
+    let (size, overlap) = (settings.tile_size, settings.overlap);
+    (0..=dzi_max_level(params.width, params.height)).into_par_iter().flat_map(move |level| {
+        let (width, height) = dzi_level_size(params.width, params.height, level);
+        let level_params = RenderParams {
+            width,
+            height,
+            ..params.clone()
+        };
+        let columns = width.div_ceil(size);
+        (0..columns * height.div_ceil(size)).into_par_iter().map(move |index| {
+            let (col, row) = (index % columns, index / columns);
+            // Tiles reach `overlap` pixels into their neighbours, except at the image's edges
+            let x = (col * size).saturating_sub(overlap);
+            let y = (row * size).saturating_sub(overlap);
+            let right = ((col + 1) * size + overlap).min(width);
+            let bottom = ((row + 1) * size + overlap).min(height);
+            Tile {
+                path: directory.join(level.to_string()).join(format!("{col}_{row}.png")),
+                params: level_params.region(x, y, right - x, bottom - y),
+            }
+        })
+    })
+}
+
+/// Yields every tile of the XYZ pyramid of `params` in `directory` up to `settings.max_zoom`, made
+/// as they are reached: the deepest levels hold millions of tiles.
+fn xyz_tiles<'a>(
+    params: &RenderParams,
+    settings: &PyramidSettings,
+    directory: &'a Path,
+) -> impl ParallelIterator<Item = Tile> + 'a {
+    let world = xyz_world(params, settings.tile_size);
+    (0..=settings.max_zoom.min(MAX_XYZ_ZOOM)).into_par_iter().flat_map(move |z| {
+        let world = world.clone();
+        (0..1u32 << (2 * z)).into_par_iter().map(move |index| {
+            let (x, y) = (index >> z, index & ((1 << z) - 1));
+            Tile {
+                path: directory.join(z.to_string()).join(x.to_string()).join(format!("{y}.png")),
+                params: xyz_tile(&world, z, x, y),
+            }
+        })
+    })
+}
+
+/// Returns the number of tiles `export_pyramid` writes for `params`.
+#[must_use]
+pub fn tile_count(params: &RenderParams, settings: &PyramidSettings) -> u32 {
+    match settings.layout {
+        TileLayout::Dzi => (0..=dzi_max_level(params.width, params.height))
+            .map(|level| {
+                let (width, height) = dzi_level_size(params.width, params.height, level);
+                width.div_ceil(settings.tile_size) * height.div_ceil(settings.tile_size)
+            })
+            .sum(),
+        TileLayout::Xyz => (0..=settings.max_zoom.min(MAX_XYZ_ZOOM)).map(|z| 1u32 << (2 * z)).sum(),
+    }
+}
+
+/// Opens the directory of a pyramid's tiles. Tiles already there are only kept when its manifest
+/// shows they were rendered from the same scene and tile shape; a directory holding anything else
+/// is not touched and an error is returned, rather than mixing stale tiles with new ones.
+fn prepare_tile_directory(directory: &Path, manifest: &Manifest) -> io::Result<()> {
+    let path = directory.join(MANIFEST_FILE);
+    match fs::read_to_string(&path) {
+        Ok(text) if serde_json::from_str::<Manifest>(&text).is_ok_and(|existing| existing == *manifest) => {
+            return Ok(());
+        }
+        Ok(_) => {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} holds tiles of a different scene; export elsewhere or delete it", directory.display()),
+            ));
+        }
+        Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error),
+        Err(_) => {}
+    }
+    if directory.exists() && fs::read_dir(directory)?.next().is_some() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} exists and holds no tile manifest; export elsewhere or delete it", directory.display()),
+        ));
+    }
+    fs::create_dir_all(directory)?;
+    let json = serde_json::to_string_pretty(manifest).map_err(io::Error::other)?;
+    fs::write(path, json)
+}
+
+/// Renders the tile pyramid of `params` to `output`: a `.dzi` file for DZI, a directory for XYZ.
+///
+/// For DZI, `params.width`×`params.height` is the size of the deepest level; for XYZ the view is
+/// fitted into a square world. Tiles are rendered in parallel with one palette built from a preview
+/// of the whole view, so histogram mapping matches across tiles and levels. Tiles already on disk
+/// from the same scene are skipped, so an interrupted or deepened export only renders what is
+/// missing. `cancel` is polled between tiles; `progress` receives the number of finished tiles.
+pub fn export_pyramid(
+    params: &RenderParams,
+    colors: &ColorSettings,
+    settings: &PyramidSettings,
+    output: &Path,
+    cancel: &AtomicBool,
+    progress: impl Fn(u32) + Sync,
+) -> io::Result<PyramidSummary> {
+    if settings.tile_size == 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "the tile size must be positive"));
+    }
+    let manifest = Manifest {
+        scene: Scene::new(params.clone(), colors.clone()),
+        tile_size: settings.tile_size,
+        overlap: if settings.layout == TileLayout::Dzi { settings.overlap } else { 0 },
+    };
+    let palette = poster::poster_palette(params, colors);
+    match settings.layout {
+        TileLayout::Dzi => {
+            let directory = dzi_files_directory(output);
+            prepare_tile_directory(&directory, &manifest)?;
+            let descriptor = dzi_descriptor(params.width, params.height, settings.tile_size, settings.overlap);
+            fs::write(output, descriptor)?;
+            render_tiles(dzi_tiles(params, settings, &directory), &palette, cancel, progress)
+        }
+        TileLayout::Xyz => {
+            prepare_tile_directory(output, &manifest)?;
+            render_tiles(xyz_tiles(params, settings, output), &palette, cancel, progress)
+        }
+    }
+}
+
+/// Renders the tiles not yet on disk, in parallel.
+fn render_tiles(
+    tiles: impl ParallelIterator<Item = Tile>,
+    palette: &[Color32],
+    cancel: &AtomicBool,
+    progress: impl Fn(u32) + Sync,
+) -> io::Result<PyramidSummary> {
+    let done = AtomicU32::new(0);
+    let rendered = AtomicU32::new(0);
+    tiles.try_for_each(|tile| {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        if !tile.path.is_file() {
+            if let Some(parent) = tile.path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let image = export::render_with_palette(&tile.params, palette);
+
+            // Write then rename: an interrupted tile never looks complete to the next export
+            let partial = tile.path.with_extension("png.part");
+            export::write_png(&image, &partial, None)?;
+            fs::rename(&partial, &tile.path)?;
+            rendered.fetch_add(1, Ordering::Relaxed);
+        }
+        progress(done.fetch_add(1, Ordering::Relaxed) + 1);
+        io::Result::Ok(())
+    })?;
+
+    let rendered = rendered.into_inner();
+    Ok(PyramidSummary {
+        rendered,
+        skipped: done.into_inner() - rendered,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fractals::anti_aliasing::AntiAliasing;
+    use crate::fractals::fractal_type::FractalType;
+    use crate::fractals::lyapunov::LyapunovSequence;
+    use crate::fractals::mariani_silver::RenderAlgorithm;
+    use crate::utils::precision_mode::PrecisionMode;
+
+    fn params(width: u32, height: u32) -> RenderParams {
+        RenderParams {
+            fractal_type: FractalType::Mandelbrot,
+            center: Point::new(-0.5, 0.0),
+            zoom: 1.0,
+            julia_c: Point::new(0.0, 0.0),
+            lyapunov_sequence: LyapunovSequence::default(),
+            max_iterations: 40,
+            precision_mode: PrecisionMode::High,
+            anti_aliasing: AntiAliasing::Off,
+            algorithm: RenderAlgorithm::BruteForce,
+            width,
+            height,
+        }
+    }
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("fractals_rs_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_dzi_levels() {
+        assert_eq!(dzi_max_level(1, 1), 0);
+        assert_eq!(dzi_max_level(600, 400), 10);
+        assert_eq!(dzi_level_size(600, 400, 10), (600, 400));
+        assert_eq!(dzi_level_size(600, 400, 9), (300, 200));
+        assert_eq!(dzi_level_size(600, 400, 1), (2, 1));
+        assert_eq!(dzi_level_size(600, 400, 0), (1, 1));
+    }
+
+    #[test]
+    fn test_xyz_tiles_cover_the_world() {
+        let world = xyz_world(&params(300, 200), 64);
+        let (x_scale, _, x_min, y_min) = world.compute_scale();
+        // The top-left tile of level 1 starts at the world's corner, at half the pixel size
+        let (tile_x_scale, _, tile_x_min, tile_y_min) = xyz_tile(&world, 1, 0, 0).compute_scale();
+        assert!((tile_x_min - x_min).abs() < 1e-12 && (tile_y_min - y_min).abs() < 1e-12);
+        assert!((tile_x_scale * 2.0 - x_scale).abs() < 1e-12);
+        // The view is 1.5 times wider than tall, so the world is as wide as the view
+        assert!((64.0 * x_scale - 4.0 * 1.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_export_pyramid_skips_existing() {
+        let dir = scratch_dir("pyramid");
+        let output = dir.join("mandelbrot.dzi");
+        let settings = PyramidSettings {
+            tile_size: 32,
+            ..PyramidSettings::default()
+        };
+        let params = params(70, 40);
+        let colors = ColorSettings::default();
+        let cancel = AtomicBool::new(false);
+
+        let summary = export_pyramid(&params, &colors, &settings, &output, &cancel, |_| {}).unwrap();
+        assert_eq!(summary.rendered, tile_count(&params, &settings));
+        assert!(fs::read_to_string(&output).unwrap().contains("<Size Width=\"70\" Height=\"40\"/>"));
+
+        // Overlapping tiles: the last column of the deepest level reaches one pixel to the left
+        let files = dzi_files_directory(&output);
+        let edge = image::open(files.join("7").join("2_1.png")).unwrap();
+        assert_eq!((edge.width(), edge.height()), (70 - 63, 40 - 31));
+
+        fs::remove_file(files.join("7").join("0_0.png")).unwrap();
+        let summary = export_pyramid(&params, &colors, &settings, &output, &cancel, |_| {}).unwrap();
+        assert_eq!(summary.rendered, 1);
+        assert_eq!(summary.skipped, tile_count(&params, &settings) - 1);
+
+        let xyz = PyramidSettings {
+            layout: TileLayout::Xyz,
+            max_zoom: 2,
+            ..settings
+        };
+        let summary = export_pyramid(&params, &colors, &xyz, &dir.join("xyz"), &cancel, |_| {}).unwrap();
+        assert_eq!(summary.rendered, 1 + 4 + 16);
+        assert!(dir.join("xyz").join("2").join("3").join("1.png").is_file());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_export_pyramid_refuses_other_tiles() {
+        let dir = scratch_dir("pyramid_refuses");
+        let settings = PyramidSettings {
+            layout: TileLayout::Xyz,
+            tile_size: 16,
+            max_zoom: 1,
+            ..PyramidSettings::default()
+        };
+        let colors = ColorSettings::default();
+        let cancel = AtomicBool::new(false);
+        let output = dir.join("xyz");
+        export_pyramid(&params(40, 40), &colors, &settings, &output, &cancel, |_| {}).unwrap();
+
+        // Another scene's tiles are neither reused nor overwritten
+        let mut other = params(40, 40);
+        other.max_iterations = 80;
+        let error = export_pyramid(&other, &colors, &settings, &output, &cancel, |_| {}).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+
+        // Neither is a directory of files without a manifest
+        let foreign = dir.join("foreign");
+        fs::create_dir_all(&foreign).unwrap();
+        fs::write(foreign.join("notes.txt"), "keep me").unwrap();
+        let error = export_pyramid(&params(40, 40), &colors, &settings, &foreign, &cancel, |_| {}).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read_dir(&foreign).unwrap().count(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}